@@ -0,0 +1,141 @@
+//! 密码消息语法（GM/T 0010，基于PKCS#7/RFC 2315）
+//!
+//! 内容类型使用GM/T 0010定义的OID（1.2.156.10197.6.1.4.2.*），解析时也接受PKCS#7的OID。
+//! 摘要算法为SM3，签名算法为SM2，签名与验签时使用默认的用户身份标识1234567812345678；
+//! 数字信封以SM4-CBC加密内容，以SM2（GM/T 0009密文格式）加密内容加密秘钥。
+
+use yasna::models::ObjectIdentifier;
+use yasna::{ASN1Error, ASN1ErrorKind, BERReader, DERWriter, Tag};
+
+use crate::oid;
+use crate::x509::crl::trim;
+use crate::x509::{Certificate, Name};
+
+pub use crate::cms::enveloped::{EnvelopedData, EnvelopedDataBuilder, RecipientInfo};
+pub use crate::cms::signed::{SignedData, SignedDataBuilder, SignerInfo};
+
+mod enveloped;
+mod signed;
+
+/// 证书标识，用于SignerInfo中的签名者与RecipientInfo中的接收者
+///
+/// IssuerAndSerialNumber ::= SEQUENCE { issuer Name, serialNumber CertificateSerialNumber }
+///
+/// 或以\[0] IMPLICIT SubjectKeyIdentifier标识。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Identifier {
+    IssuerAndSerialNumber(Name, Vec<u8>),
+    SubjectKeyIdentifier(Vec<u8>),
+}
+
+impl Identifier {
+    pub(crate) fn new(certificate: &Certificate) -> Self {
+        Identifier::IssuerAndSerialNumber(certificate.issuer().clone(), trim(certificate.serial()).to_vec())
+    }
+
+    /// 证书的签发者，以秘钥标识标识证书时为None
+    pub(crate) fn issuer(&self) -> Option<&Name> {
+        match self {
+            Identifier::IssuerAndSerialNumber(issuer, _) => Some(issuer),
+            Identifier::SubjectKeyIdentifier(_) => None,
+        }
+    }
+
+    /// 十六进制编码的证书序列号
+    pub(crate) fn serial_number(&self) -> Option<String> {
+        match self {
+            Identifier::IssuerAndSerialNumber(_, serial) => Some(hex::encode(serial)),
+            Identifier::SubjectKeyIdentifier(_) => None,
+        }
+    }
+
+    pub(crate) fn subject_key_identifier(&self) -> Option<&[u8]> {
+        match self {
+            Identifier::IssuerAndSerialNumber(..) => None,
+            Identifier::SubjectKeyIdentifier(id) => Some(id),
+        }
+    }
+
+    /// 证书是否与标识匹配
+    pub(crate) fn matches(&self, certificate: &Certificate) -> bool {
+        match self {
+            Identifier::IssuerAndSerialNumber(issuer, serial) => {
+                certificate.issuer() == issuer && trim(certificate.serial()) == trim(serial)
+            }
+            Identifier::SubjectKeyIdentifier(id) => certificate.subject_key_identifier() == Some(id.as_slice()),
+        }
+    }
+
+    pub(crate) fn read(reader: BERReader) -> Result<Identifier, ASN1Error> {
+        if reader.lookahead_tag()? == Tag::context(0) {
+            let id = reader.read_tagged_implicit(Tag::context(0), |reader| reader.read_bytes())?;
+            return Ok(Identifier::SubjectKeyIdentifier(id));
+        }
+        reader.read_sequence(|reader| {
+            let issuer = Name::read(reader.next())?;
+            let (serial, _) = reader.next().read_bigint_bytes()?;
+            Ok(Identifier::IssuerAndSerialNumber(issuer, serial))
+        })
+    }
+
+    pub(crate) fn write(&self, writer: DERWriter) {
+        match self {
+            Identifier::IssuerAndSerialNumber(issuer, serial) => writer.write_sequence(|writer| {
+                issuer.write(writer.next());
+                writer.next().write_bigint_bytes(trim(serial), true);
+            }),
+            Identifier::SubjectKeyIdentifier(id) => {
+                writer.write_tagged_implicit(Tag::context(0), |writer| writer.write_bytes(id))
+            }
+        }
+    }
+}
+
+/// ContentInfo ::= SEQUENCE { contentType ContentType, content \[0] EXPLICIT ANY DEFINED BY contentType }
+///
+/// gm、pkcs7分别为GM/T 0010与PKCS#7中该内容类型的OID，返回content的DER编码。
+pub(crate) fn read_content_info(der: &[u8], gm: &[u64], pkcs7: &[u64]) -> Result<Vec<u8>, ASN1Error> {
+    yasna::parse_der(der, |reader| {
+        reader.read_sequence(|reader| {
+            let content_type = reader.next().read_oid()?;
+            if !is(&content_type, gm) && !is(&content_type, pkcs7) {
+                return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+            }
+            reader.next().read_tagged(Tag::context(0), |reader| reader.read_der())
+        })
+    })
+}
+
+pub(crate) fn write_content_info<F: FnOnce(DERWriter)>(content_type: &[u64], content: F) -> Vec<u8> {
+    yasna::construct_der(|writer| {
+        writer.write_sequence(|writer| {
+            writer.next().write_oid(&ObjectIdentifier::from_slice(content_type));
+            writer.next().write_tagged(Tag::context(0), content);
+        })
+    })
+}
+
+/// AlgorithmIdentifier ::= SEQUENCE { algorithm, parameters }，参数缺省
+pub(crate) fn write_algorithm(writer: DERWriter, algorithm: &[u64]) {
+    writer.write_sequence(|writer| {
+        writer.next().write_oid(&ObjectIdentifier::from_slice(algorithm));
+    })
+}
+
+/// 读取算法标识，参数可以缺省或为NULL
+pub(crate) fn read_algorithm(reader: BERReader) -> Result<ObjectIdentifier, ASN1Error> {
+    reader.read_sequence(|reader| {
+        let algorithm = reader.next().read_oid()?;
+        reader.read_optional(|reader| reader.read_null())?;
+        Ok(algorithm)
+    })
+}
+
+/// 内容类型是否为数据类型（GM/T 0010的data或PKCS#7的data）
+pub(crate) fn is_data(content_type: &ObjectIdentifier) -> bool {
+    is(content_type, oid::GM_DATA) || is(content_type, oid::PKCS7_DATA)
+}
+
+pub(crate) fn is(algorithm: &ObjectIdentifier, oid: &[u64]) -> bool {
+    algorithm.components().as_slice() == oid
+}
@@ -0,0 +1,300 @@
+use std::rc::Rc;
+
+use yasna::models::ObjectIdentifier;
+use yasna::{ASN1Error, ASN1ErrorKind, BERReader, DERWriter, Tag};
+
+use crate::cms::{is, is_data, read_algorithm, read_content_info, write_algorithm, write_content_info, Identifier};
+use crate::error::{Error, Result};
+use crate::oid;
+use crate::pem;
+use crate::sm2;
+use crate::sm2::ecc::{Crypto, Decryption, Encryption};
+use crate::sm2::key::HexKey;
+use crate::sm2::p256::P256Elliptic;
+use crate::sm4::Cryptographer;
+use crate::sm4::cbc::CryptoMode;
+use crate::x509::{Certificate, KeyUsage, Name};
+
+/// 接收者信息
+///
+/// RecipientInfo ::= SEQUENCE {
+///     version                 Version,
+///     issuerAndSerialNumber   IssuerAndSerialNumber,
+///     keyEncryptionAlgorithm  KeyEncryptionAlgorithmIdentifier,
+///     encryptedKey            EncryptedKey
+/// }
+///
+/// 接收者也可以用\[0] IMPLICIT SubjectKeyIdentifier标识（version为2）。
+/// encryptedKey为SM2加密的内容加密秘钥，密文为GM/T 0009定义的DER编码。
+#[derive(Clone, Debug)]
+pub struct RecipientInfo {
+    id: Identifier,
+    sm2: bool,
+    encrypted_key: Vec<u8>,
+}
+
+impl RecipientInfo {
+    /// 接收者证书的签发者，以秘钥标识标识接收者时为None
+    pub fn issuer(&self) -> Option<&Name> {
+        self.id.issuer()
+    }
+
+    /// 十六进制编码的接收者证书序列号
+    pub fn serial_number(&self) -> Option<String> {
+        self.id.serial_number()
+    }
+
+    pub fn subject_key_identifier(&self) -> Option<&[u8]> {
+        self.id.subject_key_identifier()
+    }
+
+    fn read(reader: BERReader) -> std::result::Result<RecipientInfo, ASN1Error> {
+        reader.read_sequence(|reader| {
+            let version = reader.next().read_u8()?;
+            let id = Identifier::read(reader.next())?;
+            if version != 0 && version != 2 {
+                return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+            }
+            let algorithm = read_algorithm(reader.next())?;
+            let encrypted_key = reader.next().read_bytes()?;
+            Ok(RecipientInfo { id, sm2: is(&algorithm, oid::SM2_ENCRYPT), encrypted_key })
+        })
+    }
+
+    fn write(&self, writer: DERWriter) {
+        writer.write_sequence(|writer| {
+            writer.next().write_u8(self.version());
+            self.id.write(writer.next());
+            write_algorithm(writer.next(), oid::SM2_ENCRYPT);
+            writer.next().write_bytes(&self.encrypted_key);
+        })
+    }
+
+    fn version(&self) -> u8 {
+        match self.id {
+            Identifier::IssuerAndSerialNumber(..) => 0,
+            Identifier::SubjectKeyIdentifier(_) => 2,
+        }
+    }
+}
+
+/// 数字信封（GM/T 0010 envelopedData）
+///
+/// EnvelopedData ::= SEQUENCE {
+///     version                 Version,
+///     recipientInfos          RecipientInfos,
+///     encryptedContentInfo    EncryptedContentInfo
+/// }
+///
+/// EncryptedContentInfo ::= SEQUENCE {
+///     contentType                     ContentType,
+///     contentEncryptionAlgorithm      ContentEncryptionAlgorithmIdentifier,
+///     encryptedContent            \[0] IMPLICIT EncryptedContent OPTIONAL
+/// }
+///
+/// 内容使用随机的128位秘钥以SM4-CBC加密（PKCS#7填充，参数为IV），秘钥对每个接收者以SM2加密。
+#[derive(Clone, Debug)]
+pub struct EnvelopedData {
+    der: Vec<u8>,
+    recipients: Vec<RecipientInfo>,
+    iv: Option<Vec<u8>>,
+    encrypted_content: Option<Vec<u8>>,
+}
+
+impl EnvelopedData {
+    /// 解析DER编码的ContentInfo
+    pub fn from_der(der: &[u8]) -> Result<EnvelopedData> {
+        let enveloped = read_content_info(der, oid::GM_ENVELOPED_DATA, oid::PKCS7_ENVELOPED_DATA)
+            .map_err(|_| Error::InvalidDer("The data is not a DER-encoded EnvelopedData ContentInfo."))?;
+
+        let (recipients, content_type, algorithm, encrypted_content) = yasna::parse_der(&enveloped, |reader| {
+            reader.read_sequence(|reader| {
+                reader.next().read_u8()?;
+                let recipients = reader.next().collect_set_of(RecipientInfo::read)?;
+                let (content_type, algorithm, encrypted_content) = reader.next().read_sequence(|reader| {
+                    let content_type = reader.next().read_oid()?;
+                    let algorithm = reader.next().read_der()?;
+                    let content = reader.read_optional(|reader| reader.read_tagged_implicit(Tag::context(0), |reader| reader.read_bytes()))?;
+                    Ok((content_type, algorithm, content))
+                })?;
+                Ok((recipients, content_type, algorithm, encrypted_content))
+            })
+        }).map_err(|_| Error::InvalidDer("The data is not a DER-encoded EnvelopedData."))?;
+
+        if !is_data(&content_type) {
+            return Err(Error::Cms("The content type is not data."));
+        }
+        if recipients.is_empty() {
+            return Err(Error::Cms("The EnvelopedData does not contain any recipient."));
+        }
+
+        // SM4-CBC的参数为16字节的IV，其他算法在解密时报错
+        let iv = yasna::parse_der(&algorithm, |reader| {
+            reader.read_sequence(|reader| {
+                let algorithm = reader.next().read_oid()?;
+                let iv = reader.read_optional(|reader| reader.read_bytes())?;
+                Ok(iv.filter(|e| e.len() == 16 && is(&algorithm, oid::SM4_CBC)))
+            })
+        }).map_err(|_| Error::InvalidDer("The content encryption algorithm is not a DER-encoded AlgorithmIdentifier."))?;
+        Ok(EnvelopedData { der: der.to_vec(), recipients, iv, encrypted_content })
+    }
+
+    /// 解析PEM编码的ContentInfo（CMS）
+    pub fn from_pem(pem: &str) -> Result<EnvelopedData> {
+        EnvelopedData::from_der(&pem::decode("CMS", pem)?)
+    }
+
+    pub fn to_der(&self) -> Vec<u8> {
+        self.der.clone()
+    }
+
+    pub fn to_pem(&self) -> String {
+        pem::encode("CMS", &self.der)
+    }
+
+    pub fn recipients(&self) -> &[RecipientInfo] {
+        &self.recipients
+    }
+
+    /// 以接收者的证书与私钥解密，按签发者与序列号或秘钥标识查找对应的接收者
+    pub fn decrypt(&self, certificate: &Certificate, private_key: &str) -> Result<Vec<u8>> {
+        let recipient = self.recipients.iter().find(|e| e.id.matches(certificate))
+            .ok_or(Error::Cms("The certificate is not one of the recipients."))?;
+        if !recipient.sm2 {
+            return Err(Error::Cms("The key encryption algorithm is not SM2."));
+        }
+        let iv = self.iv.as_ref().ok_or(Error::Cms("The content encryption algorithm is not SM4-CBC."))?;
+        let content = self.encrypted_content.as_ref().ok_or(Error::Cms("The encrypted content is absent."))?;
+
+        let public_key = certificate.sm2_public_key()?;
+        let (prk, _) = sm2::decode_keypair(private_key, Some(&public_key.encode()))?;
+        let key = Crypto::der(Rc::new(P256Elliptic::init())).decryptor(prk).execute_bytes(&recipient.encrypted_key)?;
+        if key.len() != 16 {
+            return Err(Error::Cms("The content encryption key is not 16 bytes."));
+        }
+        CryptoMode::new(&key, iv)?.try_decrypt_bytes(content)
+    }
+}
+
+/// 数字信封的构造器
+pub struct EnvelopedDataBuilder {
+    content: Vec<u8>,
+    recipients: Vec<(Certificate, bool)>,
+}
+
+impl EnvelopedDataBuilder {
+    pub fn new(content: &[u8]) -> Self {
+        EnvelopedDataBuilder { content: content.to_vec(), recipients: vec![] }
+    }
+
+    /// 添加以签发者与序列号标识的接收者
+    pub fn with_recipient(mut self, certificate: &Certificate) -> Self {
+        self.recipients.push((certificate.clone(), false));
+        self
+    }
+
+    /// 添加以秘钥标识标识的接收者，证书须有主体秘钥标识扩展
+    pub fn with_recipient_key_identifier(mut self, certificate: &Certificate) -> Self {
+        self.recipients.push((certificate.clone(), true));
+        self
+    }
+
+    /// 接收者证书的秘钥用途（若有该扩展）须包含keyEncipherment
+    pub fn encrypt(self) -> Result<EnvelopedData> {
+        if self.recipients.is_empty() {
+            return Err(Error::Cms("The EnvelopedData must have at least one recipient."));
+        }
+        let key: [u8; 16] = rand::random();
+        let iv: [u8; 16] = rand::random();
+        let crypto = Crypto::der(Rc::new(P256Elliptic::init()));
+
+        let mut recipients = vec![];
+        for (certificate, by_key_identifier) in self.recipients.iter() {
+            if !certificate.key_usage().map(|e| e.contains(KeyUsage::KEY_ENCIPHERMENT)).unwrap_or(true) {
+                return Err(Error::Cms("The certificate is not allowed to encipher keys."));
+            }
+            let id = match (by_key_identifier, certificate.subject_key_identifier()) {
+                (false, _) => Identifier::new(certificate),
+                (true, Some(id)) => Identifier::SubjectKeyIdentifier(id.to_vec()),
+                (true, None) => return Err(Error::Cms("The certificate does not have a subject key identifier.")),
+            };
+            let encrypted_key = crypto.encryptor(certificate.sm2_public_key()?).execute_bytes(&key);
+            recipients.push(RecipientInfo { id, sm2: true, encrypted_key });
+        }
+        let content = CryptoMode::new(&key, &iv)?.encrypt_bytes(&self.content);
+
+        let version = recipients.iter().map(RecipientInfo::version).max().unwrap_or(0);
+        let der = write_content_info(oid::GM_ENVELOPED_DATA, |writer| {
+            writer.write_sequence(|writer| {
+                writer.next().write_u8(version);
+                writer.next().write_set_of(|writer| {
+                    for recipient in recipients.iter() {
+                        recipient.write(writer.next());
+                    }
+                });
+                writer.next().write_sequence(|writer| {
+                    writer.next().write_oid(&ObjectIdentifier::from_slice(oid::GM_DATA));
+                    writer.next().write_sequence(|writer| {
+                        writer.next().write_oid(&ObjectIdentifier::from_slice(oid::SM4_CBC));
+                        writer.next().write_bytes(&iv);
+                    });
+                    writer.next().write_tagged_implicit(Tag::context(0), |writer| writer.write_bytes(&content));
+                });
+            })
+        });
+        EnvelopedData::from_der(&der)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use crate::x509::fixtures::{CA, CA_PRIVATE_KEY, LEAF, LEAF_PRIVATE_KEY};
+    use crate::x509::{CertificateBuilder, Name};
+
+    use super::*;
+
+    #[test]
+    fn envelope() {
+        let (ca, leaf) = (Certificate::from_pem(CA).unwrap(), Certificate::from_pem(LEAF).unwrap());
+        let (prk, puk) = sm2::generate_keypair();
+        let other = CertificateBuilder::new(Name::new(&[("CN", "接收者")]).unwrap(), &puk).unwrap()
+            .with_validity(UNIX_EPOCH + Duration::from_secs(1792191799), UNIX_EPOCH + Duration::from_secs(1863471799))
+            .with_key_usage(KeyUsage::KEY_ENCIPHERMENT)
+            .sign(&ca, CA_PRIVATE_KEY)
+            .unwrap();
+        let content = "发往政府机构的公文".repeat(10);
+
+        let enveloped = EnvelopedDataBuilder::new(content.as_bytes())
+            .with_recipient(&leaf)
+            .with_recipient_key_identifier(&other)
+            .encrypt()
+            .unwrap();
+        let enveloped = EnvelopedData::from_pem(&enveloped.to_pem()).unwrap();
+        assert_eq!(enveloped.recipients().len(), 2);
+        assert!(enveloped.recipients().iter().any(|e| e.serial_number() == Some(leaf.serial_number())));
+        assert!(enveloped.recipients().iter().any(|e| e.subject_key_identifier() == other.subject_key_identifier()));
+
+        assert_eq!(enveloped.decrypt(&leaf, LEAF_PRIVATE_KEY).unwrap(), content.as_bytes());
+        assert_eq!(enveloped.decrypt(&other, &prk).unwrap(), content.as_bytes());
+        assert!(enveloped.decrypt(&ca, CA_PRIVATE_KEY).is_err());
+        assert!(enveloped.decrypt(&leaf, &prk).is_err());
+    }
+
+    #[test]
+    fn invalid() {
+        let (ca, leaf) = (Certificate::from_pem(CA).unwrap(), Certificate::from_pem(LEAF).unwrap());
+        assert!(EnvelopedDataBuilder::new(b"data").encrypt().is_err());
+        assert!(EnvelopedDataBuilder::new(b"data").with_recipient(&ca).encrypt().is_err());
+
+        let mut der = EnvelopedDataBuilder::new(&[0x5a; 20]).with_recipient(&leaf).encrypt().unwrap().to_der();
+        assert!(EnvelopedData::from_der(&der[..der.len() - 1]).is_err());
+
+        // 密文为两个分组，篡改第一个分组的最后一字节使填充字节大于16
+        let i = der.len() - 17;
+        der[i] ^= 0x20;
+        assert!(EnvelopedData::from_der(&der).unwrap().decrypt(&leaf, LEAF_PRIVATE_KEY).is_err());
+    }
+}
@@ -0,0 +1,431 @@
+use std::time::SystemTime;
+
+use yasna::models::ObjectIdentifier;
+use yasna::{ASN1Error, ASN1ErrorKind, BERReader, DERWriter, Tag};
+
+use crate::cms::{is, is_data, read_algorithm, read_content_info, write_algorithm, write_content_info, Identifier};
+use crate::error::{Error, Result};
+use crate::oid;
+use crate::pem;
+use crate::sm2;
+use crate::sm2::ecc::{Crypto, Signature};
+use crate::sm2::key::{HexKey, KeyPair};
+use crate::sm3;
+use crate::x509::{read_time, write_time, Certificate, KeyUsage, Name};
+
+/// 签名者信息
+///
+/// SignerInfo ::= SEQUENCE {
+///     version                         Version,
+///     issuerAndSerialNumber           IssuerAndSerialNumber,
+///     digestAlgorithm                 DigestAlgorithmIdentifier,
+///     authenticatedAttributes     \[0] IMPLICIT Attributes OPTIONAL,
+///     digestEncryptionAlgorithm       DigestEncryptionAlgorithmIdentifier,
+///     encryptedDigest                 EncryptedDigest,
+///     unauthenticatedAttributes   \[1] IMPLICIT Attributes OPTIONAL
+/// }
+///
+/// 有签名属性时，签名的对象为签名属性的DER编码（标签为SET），否则为内容本身。
+#[derive(Clone, Debug)]
+pub struct SignerInfo {
+    id: Identifier,
+    supported: bool,
+    attributes: Option<Vec<u8>>,
+    content_type: Option<ObjectIdentifier>,
+    message_digest: Option<Vec<u8>>,
+    signing_time: Option<SystemTime>,
+    signature: Vec<u8>,
+}
+
+impl SignerInfo {
+    /// 签名者证书的签发者，以秘钥标识标识签名者时为None
+    pub fn issuer(&self) -> Option<&Name> {
+        self.id.issuer()
+    }
+
+    /// 十六进制编码的签名者证书序列号
+    pub fn serial_number(&self) -> Option<String> {
+        self.id.serial_number()
+    }
+
+    pub fn subject_key_identifier(&self) -> Option<&[u8]> {
+        self.id.subject_key_identifier()
+    }
+
+    /// 签名属性中的签名时间
+    pub fn signing_time(&self) -> Option<SystemTime> {
+        self.signing_time
+    }
+
+    fn read(reader: BERReader) -> std::result::Result<SignerInfo, ASN1Error> {
+        reader.read_sequence(|reader| {
+            let version = reader.next().read_u8()?;
+            let id = Identifier::read(reader.next())?;
+            if version != 1 && version != 3 {
+                return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+            }
+            let digest = read_algorithm(reader.next())?;
+            let attributes = reader.read_optional(|reader| {
+                if reader.lookahead_tag()? != Tag::context(0) {
+                    return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+                }
+                let mut der = reader.read_der()?;
+                der[0] = 0x31;
+                Ok(der)
+            })?;
+            let algorithm = read_algorithm(reader.next())?;
+            let signature = reader.next().read_bytes()?;
+            reader.read_optional(|reader| reader.read_tagged_implicit(Tag::context(1), |reader| reader.collect_set_of(|reader| reader.read_der())))?;
+
+            let supported = is(&digest, oid::SM3) && (is(&algorithm, oid::SM2_SIGN) || is(&algorithm, oid::SM2_WITH_SM3));
+            let mut info = SignerInfo {
+                id, supported, attributes: None, content_type: None, message_digest: None, signing_time: None, signature,
+            };
+            if let Some(der) = attributes {
+                info.read_attributes(&der)?;
+                info.attributes = Some(der);
+            }
+            Ok(info)
+        })
+    }
+
+    /// Attribute ::= SEQUENCE { attrType OBJECT IDENTIFIER, attrValues SET OF AttributeValue }
+    ///
+    /// 须包含单值的内容类型与消息摘要属性。
+    fn read_attributes(&mut self, der: &[u8]) -> std::result::Result<(), ASN1Error> {
+        let attributes = yasna::parse_der(der, |reader| {
+            reader.collect_set_of(|reader| {
+                reader.read_sequence(|reader| {
+                    let kind = reader.next().read_oid()?;
+                    let values = reader.next().collect_set_of(|reader| reader.read_der())?;
+                    Ok((kind, values))
+                })
+            })
+        })?;
+
+        for (kind, values) in attributes.iter() {
+            let value = match values.as_slice() {
+                [value] => value,
+                _ => return Err(ASN1Error::new(ASN1ErrorKind::Invalid)),
+            };
+            if is(kind, oid::CONTENT_TYPE) {
+                self.content_type = Some(yasna::parse_der(value, |reader| reader.read_oid())?);
+            } else if is(kind, oid::MESSAGE_DIGEST) {
+                self.message_digest = Some(yasna::parse_der(value, |reader| reader.read_bytes())?);
+            } else if is(kind, oid::SIGNING_TIME) {
+                self.signing_time = Some(yasna::parse_der(value, read_time)?);
+            }
+        }
+        if self.content_type.is_none() || self.message_digest.is_none() {
+            return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+        }
+        Ok(())
+    }
+
+    fn write(&self, writer: DERWriter) {
+        writer.write_sequence(|writer| {
+            writer.next().write_u8(1);
+            self.id.write(writer.next());
+            write_algorithm(writer.next(), oid::SM3);
+            if let Some(attributes) = &self.attributes {
+                let mut der = attributes.clone();
+                der[0] = 0xa0;
+                writer.next().write_der(&der);
+            }
+            write_algorithm(writer.next(), oid::SM2_SIGN);
+            writer.next().write_bytes(&self.signature);
+        })
+    }
+
+    /// 验证签名者对内容的签名，content_type为SignedData中的内容类型
+    fn verify(&self, content_type: &ObjectIdentifier, content: &[u8], certificate: &Certificate) -> Result<bool> {
+        if !self.supported {
+            return Err(Error::Cms("The digest or signature algorithm is not SM3 or SM2."));
+        }
+        let signed = match &self.attributes {
+            Some(attributes) => {
+                if self.content_type.as_ref() != Some(content_type) {
+                    return Ok(false);
+                }
+                if self.message_digest.as_deref() != Some(sm3::hash(content).as_slice()) {
+                    return Ok(false);
+                }
+                attributes.as_slice()
+            }
+            None => content,
+        };
+        let signature = Signature::decode(&self.signature)?;
+        Ok(Crypto::default().verifier(certificate.sm2_public_key()?).verify_bytes(signed, &signature))
+    }
+}
+
+/// 签名数据（GM/T 0010 signedData）
+///
+/// SignedData ::= SEQUENCE {
+///     version                 Version,
+///     digestAlgorithms        DigestAlgorithmIdentifiers,
+///     contentInfo             ContentInfo,
+///     certificates        \[0] IMPLICIT ExtendedCertificatesAndCertificates OPTIONAL,
+///     crls                \[1] IMPLICIT CertificateRevocationLists OPTIONAL,
+///     signerInfos             SignerInfos
+/// }
+///
+/// 不含内容时为分离式签名，验证时须另行提供原文。
+#[derive(Clone, Debug)]
+pub struct SignedData {
+    der: Vec<u8>,
+    content_type: ObjectIdentifier,
+    content: Option<Vec<u8>>,
+    certificates: Vec<Certificate>,
+    signers: Vec<SignerInfo>,
+}
+
+impl SignedData {
+    /// 解析DER编码的ContentInfo
+    pub fn from_der(der: &[u8]) -> Result<SignedData> {
+        let signed = read_content_info(der, oid::GM_SIGNED_DATA, oid::PKCS7_SIGNED_DATA)
+            .map_err(|_| Error::InvalidDer("The data is not a DER-encoded SignedData ContentInfo."))?;
+
+        let (content_type, content, certificates, signers) = yasna::parse_der(&signed, |reader| {
+            reader.read_sequence(|reader| {
+                reader.next().read_u8()?;
+                reader.next().collect_set_of(|reader| reader.read_der())?;
+                let (content_type, content) = reader.next().read_sequence(|reader| {
+                    let content_type = reader.next().read_oid()?;
+                    let content = reader.read_optional(|reader| reader.read_tagged(Tag::context(0), |reader| reader.read_bytes()))?;
+                    Ok((content_type, content))
+                })?;
+                let certificates = reader.read_optional(|reader| {
+                    reader.read_tagged_implicit(Tag::context(0), |reader| reader.collect_set_of(|reader| reader.read_der()))
+                })?.unwrap_or_default();
+                reader.read_optional(|reader| {
+                    reader.read_tagged_implicit(Tag::context(1), |reader| reader.collect_set_of(|reader| reader.read_der()))
+                })?;
+                let signers = reader.next().collect_set_of(SignerInfo::read)?;
+                Ok((content_type, content, certificates, signers))
+            })
+        }).map_err(|_| Error::InvalidDer("The data is not a DER-encoded SignedData."))?;
+
+        if !is_data(&content_type) {
+            return Err(Error::Cms("The content type is not data."));
+        }
+        if signers.is_empty() {
+            return Err(Error::Cms("The SignedData does not contain any signer."));
+        }
+        let certificates = certificates.iter().map(|e| Certificate::from_der(e)).collect::<Result<_>>()?;
+        Ok(SignedData { der: der.to_vec(), content_type, content, certificates, signers })
+    }
+
+    /// 解析PEM编码的ContentInfo（CMS）
+    pub fn from_pem(pem: &str) -> Result<SignedData> {
+        SignedData::from_der(&pem::decode("CMS", pem)?)
+    }
+
+    pub fn to_der(&self) -> Vec<u8> {
+        self.der.clone()
+    }
+
+    pub fn to_pem(&self) -> String {
+        pem::encode("CMS", &self.der)
+    }
+
+    /// 签名的内容，分离式签名为None
+    pub fn content(&self) -> Option<&[u8]> {
+        self.content.as_deref()
+    }
+
+    pub fn is_detached(&self) -> bool {
+        self.content.is_none()
+    }
+
+    /// 附带的证书
+    pub fn certificates(&self) -> &[Certificate] {
+        &self.certificates
+    }
+
+    pub fn signers(&self) -> &[SignerInfo] {
+        &self.signers
+    }
+
+    /// 在附带的证书与certificates中查找签名者证书
+    pub fn signer_certificate<'a>(&'a self, signer: &SignerInfo, certificates: &'a [Certificate]) -> Option<&'a Certificate> {
+        self.certificates.iter().chain(certificates.iter()).find(|e| signer.id.matches(e))
+    }
+
+    /// 验证所有签名者的签名，certificates为未附带在消息中的签名者证书
+    ///
+    /// 只验证签名本身，签名者证书是否可信须另行通过[TrustStore](crate::x509::TrustStore)验证。
+    pub fn verify(&self, certificates: &[Certificate]) -> Result<bool> {
+        match &self.content {
+            Some(content) => self.verify_content(content, certificates),
+            None => Err(Error::Cms("The SignedData is detached.")),
+        }
+    }
+
+    /// 使用原文验证分离式签名
+    pub fn verify_detached(&self, content: &[u8], certificates: &[Certificate]) -> Result<bool> {
+        self.verify_content(content, certificates)
+    }
+
+    fn verify_content(&self, content: &[u8], certificates: &[Certificate]) -> Result<bool> {
+        for signer in self.signers.iter() {
+            let certificate = self.signer_certificate(signer, certificates)
+                .ok_or(Error::Cms("The certificate of the signer is not found."))?;
+            if !signer.verify(&self.content_type, content, certificate)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+/// 签名数据的构造器
+///
+/// 签名属性包括内容类型、SM3消息摘要与签名时间（默认为当前时间），签名者证书总是附带在消息中。
+pub struct SignedDataBuilder {
+    content: Vec<u8>,
+    signing_time: SystemTime,
+    certificates: Vec<Certificate>,
+}
+
+impl SignedDataBuilder {
+    pub fn new(content: &[u8]) -> Self {
+        SignedDataBuilder { content: content.to_vec(), signing_time: SystemTime::now(), certificates: vec![] }
+    }
+
+    pub fn with_signing_time(mut self, time: SystemTime) -> Self {
+        self.signing_time = time;
+        self
+    }
+
+    /// 附带其他证书，如签名者的中间CA证书
+    pub fn with_certificates(mut self, certificates: Vec<Certificate>) -> Self {
+        self.certificates = certificates;
+        self
+    }
+
+    /// 签名并包含内容，private_key须与证书的公钥匹配
+    pub fn sign(self, certificate: &Certificate, private_key: &str) -> Result<SignedData> {
+        self.build(certificate, private_key, true)
+    }
+
+    /// 分离式签名，消息中不包含内容
+    pub fn sign_detached(self, certificate: &Certificate, private_key: &str) -> Result<SignedData> {
+        self.build(certificate, private_key, false)
+    }
+
+    fn build(self, certificate: &Certificate, private_key: &str, attached: bool) -> Result<SignedData> {
+        let usage = KeyUsage::DIGITAL_SIGNATURE | KeyUsage::NON_REPUDIATION;
+        if !certificate.key_usage().map(|e| e.bits() & usage.bits() != 0).unwrap_or(true) {
+            return Err(Error::Cms("The certificate is not allowed to sign data."));
+        }
+        let public_key = certificate.sm2_public_key()?;
+        let (prk, _) = sm2::decode_keypair(private_key, Some(&public_key.encode()))?;
+
+        let attributes = yasna::construct_der(|writer| {
+            writer.write_set_of(|writer| {
+                write_attribute(writer.next(), oid::CONTENT_TYPE, |writer| writer.write_oid(&ObjectIdentifier::from_slice(oid::GM_DATA)));
+                write_attribute(writer.next(), oid::SIGNING_TIME, |writer| write_time(writer, self.signing_time));
+                write_attribute(writer.next(), oid::MESSAGE_DIGEST, |writer| writer.write_bytes(&sm3::hash(&self.content)));
+            })
+        });
+        let signature = Crypto::default().signer(KeyPair::new(prk, public_key)).sign_bytes(&attributes).encode();
+        let signer = SignerInfo {
+            id: Identifier::new(certificate),
+            supported: true,
+            attributes: Some(attributes),
+            content_type: None,
+            message_digest: None,
+            signing_time: None,
+            signature,
+        };
+
+        let mut certificates = vec![certificate.clone()];
+        certificates.extend(self.certificates.into_iter().filter(|e| e.to_der() != certificate.to_der()));
+        let der = write_content_info(oid::GM_SIGNED_DATA, |writer| {
+            writer.write_sequence(|writer| {
+                writer.next().write_u8(1);
+                writer.next().write_set_of(|writer| write_algorithm(writer.next(), oid::SM3));
+                writer.next().write_sequence(|writer| {
+                    writer.next().write_oid(&ObjectIdentifier::from_slice(oid::GM_DATA));
+                    if attached {
+                        writer.next().write_tagged(Tag::context(0), |writer| writer.write_bytes(&self.content));
+                    }
+                });
+                writer.next().write_tagged_implicit(Tag::context(0), |writer| {
+                    writer.write_set_of(|writer| {
+                        for certificate in certificates.iter() {
+                            writer.next().write_der(&certificate.to_der());
+                        }
+                    })
+                });
+                writer.next().write_set_of(|writer| signer.write(writer.next()));
+            })
+        });
+        SignedData::from_der(&der)
+    }
+}
+
+fn write_attribute<F: FnOnce(DERWriter)>(writer: DERWriter, kind: &[u64], value: F) {
+    writer.write_sequence(|writer| {
+        writer.next().write_oid(&ObjectIdentifier::from_slice(kind));
+        writer.next().write_set_of(|writer| value(writer.next()));
+    })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use crate::x509::fixtures::{CA, CA_PRIVATE_KEY, LEAF, LEAF_PRIVATE_KEY};
+
+    use super::*;
+
+    #[test]
+    fn attached() {
+        let (ca, leaf) = (Certificate::from_pem(CA).unwrap(), Certificate::from_pem(LEAF).unwrap());
+        let content = "国密文档交换".as_bytes();
+        let time = UNIX_EPOCH + Duration::from_secs(1792193400);
+
+        let signed = SignedDataBuilder::new(content)
+            .with_signing_time(time)
+            .with_certificates(vec![ca.clone()])
+            .sign(&leaf, LEAF_PRIVATE_KEY)
+            .unwrap();
+        let signed = SignedData::from_pem(&signed.to_pem()).unwrap();
+        assert_eq!(signed.content(), Some(content));
+        assert_eq!(signed.certificates().len(), 2);
+        assert!(signed.verify(&[]).unwrap());
+
+        let signer = &signed.signers()[0];
+        assert_eq!(signer.issuer(), Some(ca.subject()));
+        assert_eq!(signer.serial_number(), Some(leaf.serial_number()));
+        assert_eq!(signer.signing_time(), Some(time));
+        assert_eq!(signed.signer_certificate(signer, &[]).unwrap().to_der(), leaf.to_der());
+
+        // 篡改内容
+        let mut der = signed.to_der();
+        let i = der.windows(content.len()).position(|e| e == content).unwrap();
+        der[i] ^= 1;
+        assert!(!SignedData::from_der(&der).unwrap().verify(&[]).unwrap());
+    }
+
+    #[test]
+    fn detached() {
+        let (ca, leaf) = (Certificate::from_pem(CA).unwrap(), Certificate::from_pem(LEAF).unwrap());
+        let content = b"detached content";
+
+        let signed = SignedDataBuilder::new(content).sign_detached(&leaf, LEAF_PRIVATE_KEY).unwrap();
+        let signed = SignedData::from_der(&signed.to_der()).unwrap();
+        assert!(signed.is_detached());
+        assert!(signed.verify(&[]).is_err());
+        assert!(signed.verify_detached(content, &[]).unwrap());
+        assert!(!signed.verify_detached(b"detached contenT", &[]).unwrap());
+
+        // 私钥与证书不匹配、证书不允许签名
+        assert!(SignedDataBuilder::new(content).sign(&leaf, CA_PRIVATE_KEY).is_err());
+        assert!(SignedDataBuilder::new(content).sign(&ca, CA_PRIVATE_KEY).is_err());
+        assert!(SignedData::from_der(&signed.to_der()[1..]).is_err());
+    }
+}
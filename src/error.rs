@@ -0,0 +1,94 @@
+use std::fmt::{Display, Formatter};
+
+/// 错误类型
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// 数据不是由十六进制字符组成
+    InvalidHex,
+    /// 公钥格式错误或不是曲线上的有效点
+    InvalidPublicKey(&'static str),
+    /// 私钥格式错误或不在区间\[1, n-2]内
+    InvalidPrivateKey(&'static str),
+    /// 用户身份标识IDA的长度超过8191字节
+    InvalidUserId,
+    /// 杂凑值长度不是32字节
+    InvalidDigest,
+    /// 密文格式错误或完整性校验失败
+    InvalidCipher(&'static str),
+    /// 签名格式错误
+    InvalidSignature(&'static str),
+    /// SM4秘钥长度不是16字节
+    InvalidKey,
+    /// SM4初始化向量长度不是16字节
+    InvalidIv,
+    /// 解密后的填充数据不符合PKCS#7规则
+    InvalidPadding,
+    /// DER编码的数据结构错误，或包含不支持的算法
+    InvalidDer(&'static str),
+    /// PEM格式错误
+    InvalidPem(&'static str),
+    /// 口令错误或加密的数据已损坏
+    InvalidPassword,
+    /// 密钥交换失败：状态错误、共享点为无穷远点或确认杂凑值不一致
+    KeyExchange(&'static str),
+    /// 证书内容不符合RFC 5280，或使用了不支持的算法
+    InvalidCertificate(&'static str),
+    /// 证书路径验证失败：找不到信任锚、签名无效、已过期或违反约束
+    CertificatePath(&'static str),
+    /// OCSP请求失败：网络错误、响应不成功、签名无效或内容与请求不符
+    Ocsp(&'static str),
+    /// CMS消息的内容不支持，或找不到签名者、接收者对应的证书
+    Cms(&'static str),
+    /// 两方协同计算失败：状态错误、参数越界或结果无法通过校验
+    Collaborative(&'static str),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::InvalidHex => write!(f, "The data must be composed of hex chars."),
+            Error::InvalidPublicKey(reason) => write!(f, "The public key is invalid: {}", reason),
+            Error::InvalidPrivateKey(reason) => write!(f, "The private key is invalid: {}", reason),
+            Error::InvalidUserId => write!(f, "The user id is too large."),
+            Error::InvalidDigest => write!(f, "The digest must be 32 bytes."),
+            Error::InvalidCipher(reason) => write!(f, "The cipher data is invalid: {}", reason),
+            Error::InvalidSignature(reason) => write!(f, "The signature is invalid: {}", reason),
+            Error::InvalidKey => write!(f, "The Key must be composed of hex chars with a length of 32."),
+            Error::InvalidIv => write!(f, "The IV must be composed of hex chars with a length of 32."),
+            Error::InvalidPadding => write!(f, "The padding of the plain data is invalid."),
+            Error::InvalidDer(reason) => write!(f, "The DER data is invalid: {}", reason),
+            Error::InvalidPem(reason) => write!(f, "The PEM data is invalid: {}", reason),
+            Error::InvalidPassword => write!(f, "The password is incorrect or the encrypted data is corrupted."),
+            Error::KeyExchange(reason) => write!(f, "The key exchange failed: {}", reason),
+            Error::InvalidCertificate(reason) => write!(f, "The certificate is invalid: {}", reason),
+            Error::CertificatePath(reason) => write!(f, "The certificate path validation failed: {}", reason),
+            Error::Ocsp(reason) => write!(f, "The OCSP query failed: {}", reason),
+            Error::Cms(reason) => write!(f, "The CMS message is invalid: {}", reason),
+            Error::Collaborative(reason) => write!(f, "The collaborative computation failed: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<hex::FromHexError> for Error {
+    fn from(_: hex::FromHexError) -> Self {
+        Error::InvalidHex
+    }
+}
+
+/// 兼容原有的接口：出错时以错误信息panic
+pub(crate) trait OrPanic<T> {
+    fn or_panic(self) -> T;
+}
+
+impl<T> OrPanic<T> for Result<T> {
+    fn or_panic(self) -> T {
+        match self {
+            Ok(value) => value,
+            Err(e) => panic!("{}", e)
+        }
+    }
+}
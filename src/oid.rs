@@ -0,0 +1,80 @@
+//! 对象标识符（OID）
+
+/// id-ecPublicKey（RFC 5480）
+pub(crate) const EC_PUBLIC_KEY: &[u64] = &[1, 2, 840, 10045, 2, 1];
+
+/// SM2椭圆曲线（GM/T 0006），部分实现也将其用作公钥算法标识
+pub(crate) const SM2: &[u64] = &[1, 2, 156, 10197, 1, 301];
+
+/// PBES2（RFC 8018）
+pub(crate) const PBES2: &[u64] = &[1, 2, 840, 113549, 1, 5, 13];
+
+/// PBKDF2（RFC 8018）
+pub(crate) const PBKDF2: &[u64] = &[1, 2, 840, 113549, 1, 5, 12];
+
+/// HMAC-SM3，GmSSL使用的标识
+pub(crate) const HMAC_SM3: &[u64] = &[1, 2, 156, 10197, 1, 401, 2];
+
+/// hmacWithSM3，OpenSSL使用的标识
+pub(crate) const HMAC_WITH_SM3: &[u64] = &[1, 2, 156, 10197, 1, 401, 3, 1];
+
+/// SM4-CBC
+pub(crate) const SM4_CBC: &[u64] = &[1, 2, 156, 10197, 1, 104, 2];
+
+/// SM4-GCM（RFC 8998）
+pub(crate) const SM4_GCM: &[u64] = &[1, 2, 156, 10197, 1, 104, 8];
+
+/// SM2-with-SM3签名算法（GM/T 0006）
+pub(crate) const SM2_WITH_SM3: &[u64] = &[1, 2, 156, 10197, 1, 501];
+
+/// 名称属性（X.520）
+pub(crate) const COMMON_NAME: &[u64] = &[2, 5, 4, 3];
+pub(crate) const SERIAL_NUMBER: &[u64] = &[2, 5, 4, 5];
+pub(crate) const COUNTRY: &[u64] = &[2, 5, 4, 6];
+pub(crate) const LOCALITY: &[u64] = &[2, 5, 4, 7];
+pub(crate) const STATE: &[u64] = &[2, 5, 4, 8];
+pub(crate) const ORGANIZATION: &[u64] = &[2, 5, 4, 10];
+pub(crate) const ORGANIZATIONAL_UNIT: &[u64] = &[2, 5, 4, 11];
+pub(crate) const EMAIL_ADDRESS: &[u64] = &[1, 2, 840, 113549, 1, 9, 1];
+
+/// 证书扩展（RFC 5280 4.2）
+pub(crate) const SUBJECT_KEY_IDENTIFIER: &[u64] = &[2, 5, 29, 14];
+pub(crate) const KEY_USAGE: &[u64] = &[2, 5, 29, 15];
+pub(crate) const SUBJECT_ALT_NAME: &[u64] = &[2, 5, 29, 17];
+pub(crate) const BASIC_CONSTRAINTS: &[u64] = &[2, 5, 29, 19];
+pub(crate) const AUTHORITY_KEY_IDENTIFIER: &[u64] = &[2, 5, 29, 35];
+
+/// PKCS#9 extensionRequest
+pub(crate) const EXTENSION_REQUEST: &[u64] = &[1, 2, 840, 113549, 1, 9, 14];
+
+/// CRL扩展（RFC 5280 5.2、5.3）
+pub(crate) const CRL_NUMBER: &[u64] = &[2, 5, 29, 20];
+pub(crate) const CRL_REASON: &[u64] = &[2, 5, 29, 21];
+
+/// SM3杂凑算法
+pub(crate) const SM3: &[u64] = &[1, 2, 156, 10197, 1, 401];
+
+/// OCSP（RFC 6960）
+pub(crate) const OCSP_BASIC: &[u64] = &[1, 3, 6, 1, 5, 5, 7, 48, 1, 1];
+pub(crate) const OCSP_NONCE: &[u64] = &[1, 3, 6, 1, 5, 5, 7, 48, 1, 2];
+
+/// SM2签名算法sm2-1（GM/T 0006），GM/T 0010中用作digestEncryptionAlgorithm
+pub(crate) const SM2_SIGN: &[u64] = &[1, 2, 156, 10197, 1, 301, 1];
+
+/// SM2加密算法sm2-3（GM/T 0006），GM/T 0010中用作keyEncryptionAlgorithm
+pub(crate) const SM2_ENCRYPT: &[u64] = &[1, 2, 156, 10197, 1, 301, 3];
+
+/// GM/T 0010内容类型
+pub(crate) const GM_DATA: &[u64] = &[1, 2, 156, 10197, 6, 1, 4, 2, 1];
+pub(crate) const GM_SIGNED_DATA: &[u64] = &[1, 2, 156, 10197, 6, 1, 4, 2, 2];
+pub(crate) const GM_ENVELOPED_DATA: &[u64] = &[1, 2, 156, 10197, 6, 1, 4, 2, 3];
+
+/// PKCS#7内容类型（RFC 2315）
+pub(crate) const PKCS7_DATA: &[u64] = &[1, 2, 840, 113549, 1, 7, 1];
+pub(crate) const PKCS7_SIGNED_DATA: &[u64] = &[1, 2, 840, 113549, 1, 7, 2];
+pub(crate) const PKCS7_ENVELOPED_DATA: &[u64] = &[1, 2, 840, 113549, 1, 7, 3];
+
+/// PKCS#9签名属性
+pub(crate) const CONTENT_TYPE: &[u64] = &[1, 2, 840, 113549, 1, 9, 3];
+pub(crate) const MESSAGE_DIGEST: &[u64] = &[1, 2, 840, 113549, 1, 9, 4];
+pub(crate) const SIGNING_TIME: &[u64] = &[1, 2, 840, 113549, 1, 9, 5];
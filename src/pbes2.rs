@@ -0,0 +1,175 @@
+//! 基于口令的加密（PBES2，RFC 8018）
+//!
+//! 秘钥派生函数为PBKDF2，伪随机函数为HMAC-SM3；加密算法为SM4-CBC，使用PKCS#7填充。
+
+use yasna::models::ObjectIdentifier;
+use yasna::{ASN1Error, ASN1ErrorKind, BERReaderSeq};
+
+use crate::error::{Error, Result};
+use crate::oid;
+use crate::sm3;
+use crate::sm4::Cryptographer;
+use crate::sm4::cbc::CryptoMode;
+
+/// 默认迭代次数
+pub(crate) const ITERATIONS: u32 = 65536;
+
+/// 迭代次数的上限，避免解析恶意数据时耗费过多时间
+const MAX_ITERATIONS: u64 = 10_000_000;
+
+/// EncryptedPrivateKeyInfo ::= SEQUENCE {
+///     encryptionAlgorithm  AlgorithmIdentifier {{ PBES2 }},
+///     encryptedData        OCTET STRING
+/// }
+///
+/// PBES2-params ::= SEQUENCE { keyDerivationFunc AlgorithmIdentifier {{ PBKDF2 }}, encryptionScheme AlgorithmIdentifier {{ SM4-CBC }} }
+///
+/// PBKDF2-params ::= SEQUENCE { salt OCTET STRING, iterationCount INTEGER, keyLength INTEGER OPTIONAL, prf AlgorithmIdentifier }
+pub(crate) fn encrypt(data: &[u8], password: &[u8], iterations: u32) -> Vec<u8> {
+    let salt: [u8; 16] = rand::random();
+    let iv: [u8; 16] = rand::random();
+    encrypt_with(data, password, &salt, &iv, iterations)
+}
+
+pub(crate) fn encrypt_with(data: &[u8], password: &[u8], salt: &[u8], iv: &[u8], iterations: u32) -> Vec<u8> {
+    let key = sm3::pbkdf2(password, salt, iterations, 16);
+    let cipher = CryptoMode::new(&key, iv).unwrap().encrypt_bytes(data);
+
+    yasna::construct_der(|writer| {
+        writer.write_sequence(|writer| {
+            writer.next().write_sequence(|writer| {
+                writer.next().write_oid(&ObjectIdentifier::from_slice(oid::PBES2));
+                writer.next().write_sequence(|writer| {
+                    writer.next().write_sequence(|writer| {
+                        writer.next().write_oid(&ObjectIdentifier::from_slice(oid::PBKDF2));
+                        writer.next().write_sequence(|writer| {
+                            writer.next().write_bytes(salt);
+                            writer.next().write_u32(iterations);
+                            writer.next().write_sequence(|writer| {
+                                writer.next().write_oid(&ObjectIdentifier::from_slice(oid::HMAC_SM3));
+                                writer.next().write_null();
+                            });
+                        });
+                    });
+                    writer.next().write_sequence(|writer| {
+                        writer.next().write_oid(&ObjectIdentifier::from_slice(oid::SM4_CBC));
+                        writer.next().write_bytes(iv);
+                    });
+                });
+            });
+            writer.next().write_bytes(&cipher);
+        })
+    })
+}
+
+/// 解密EncryptedPrivateKeyInfo，伪随机函数接受GmSSL与OpenSSL两种HMAC-SM3标识
+pub(crate) fn decrypt(der: &[u8], password: &[u8]) -> Result<Vec<u8>> {
+    let (params, cipher) = yasna::parse_der(der, |reader| {
+        reader.read_sequence(|reader| {
+            let params = reader.next().read_sequence(|reader| {
+                if reader.next().read_oid()? != ObjectIdentifier::from_slice(oid::PBES2) {
+                    return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+                }
+                reader.next().read_sequence(|reader| {
+                    let (salt, iterations, length) = reader.next().read_sequence(read_pbkdf2)?;
+                    let iv = reader.next().read_sequence(|reader| {
+                        if reader.next().read_oid()? != ObjectIdentifier::from_slice(oid::SM4_CBC) {
+                            return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+                        }
+                        reader.next().read_bytes()
+                    })?;
+                    Ok((salt, iterations, length, iv))
+                })
+            })?;
+            let cipher = reader.next().read_bytes()?;
+            Ok((params, cipher))
+        })
+    }).map_err(|_| Error::InvalidDer("The data is not an EncryptedPrivateKeyInfo using PBKDF2-HMAC-SM3 and SM4-CBC."))?;
+
+    let (salt, iterations, length, iv) = params;
+    if iterations == 0 || iterations > MAX_ITERATIONS {
+        return Err(Error::InvalidDer("The iteration count is out of range."));
+    }
+    if length.unwrap_or(16) != 16 {
+        return Err(Error::InvalidDer("The key length of SM4 must be 16."));
+    }
+
+    let key = sm3::pbkdf2(password, &salt, iterations as u32, 16);
+    CryptoMode::new(&key, &iv)?
+        .try_decrypt_bytes(&cipher)
+        .map_err(|_| Error::InvalidPassword)
+}
+
+/// 读取PBKDF2算法标识，返回盐值、迭代次数以及可选的秘钥长度
+fn read_pbkdf2(reader: &mut BERReaderSeq) -> std::result::Result<(Vec<u8>, u64, Option<u64>), ASN1Error> {
+    if reader.next().read_oid()? != ObjectIdentifier::from_slice(oid::PBKDF2) {
+        return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+    }
+    reader.next().read_sequence(|reader| {
+        let salt = reader.next().read_bytes()?;
+        let iterations = reader.next().read_u64()?;
+        let length = reader.read_optional(|reader| reader.read_u64())?;
+        reader.next().read_sequence(read_prf)?;
+        Ok((salt, iterations, length))
+    })
+}
+
+/// 伪随机函数须为HMAC-SM3，参数缺省或为NULL
+fn read_prf(reader: &mut BERReaderSeq) -> std::result::Result<(), ASN1Error> {
+    let prf = reader.next().read_oid()?;
+    if prf != ObjectIdentifier::from_slice(oid::HMAC_SM3) && prf != ObjectIdentifier::from_slice(oid::HMAC_WITH_SM3) {
+        return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+    }
+    reader.read_optional(|reader| reader.read_null())?;
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pbes2() {
+        let data = b"PrivateKeyInfo";
+        let salt = hex::decode("4e4810252a4412dcc8f640e45fb48fcc").unwrap();
+        let iv = hex::decode("023a5284c70874a229d6fc1e4fa470c3").unwrap();
+
+        let der = encrypt_with(data, b"secret", &salt, &iv, 2048);
+        assert_eq!(decrypt(&der, b"secret").unwrap(), data);
+        assert_ne!(encrypt(data, b"secret", 1), encrypt(data, b"secret", 1));
+
+        // 错误的口令导致填充校验失败
+        let der = encrypt_with(&[0x30; 32], b"secret", &salt, &iv, 1);
+        assert_eq!(decrypt(&der, b"Secret").unwrap_err(), Error::InvalidPassword);
+        assert!(decrypt(&der[1..], b"secret").is_err());
+    }
+
+    /// 由OpenSSL 3.5生成：openssl pkcs8 -topk8 -v2 sm4-cbc -v2prf hmacWithSM3 -iter 2048 -passout pass:secret
+    #[test]
+    fn openssl() {
+        let der = hex::decode(
+            "3081f5306006092a864886f70d01050d3053303306092a864886f70d01050c30260410d3bbea87a327f2fc4c8ca3883019\
+            b3a502020800300e060a2a811ccf5501831103010500301c06082a811ccf550168020410a9080fd761f8c49afae4590d3c\
+            beffd20481909994c56528ce01f3017f39046fabb27c994c3333a3318a68634cfe07fc0f0a64bc6efcd065bbccb731b7f9\
+            d5fca2e6d958e40f3ae57945426da60dfdc7caf634b0976753492862a3654ba1fe5a27268967d75091cbad1c1856e7d970\
+            2ac5fee90e9ee859f8d76e230c87a10c4db8d116aee5554f50283cf291aa06ba95963cf3dfa1db5255fa4504a576292b29\
+            473436"
+        ).unwrap();
+        // openssl pkcs8 -topk8 -nocrypt输出的PrivateKeyInfo
+        let info = hex::decode(
+            "308187020100301306072a8648ce3d020106082a811ccf5501822d046d306b02010104208c93aad8335cf243d21809eab7\
+            0d21b3efa6b5de850c217c8a44d740a20acc71a144034200047df299aa701a44a342d7d3df529bccbd7359921811445a18\
+            0eceac102ac2875f95dad19d4dd3ab71e3e127c574478f4af3934e4fd95763ca7ee7743f34822858"
+        ).unwrap();
+
+        assert_eq!(decrypt(&der, b"secret").unwrap(), info);
+        assert_eq!(decrypt(&der, b"Secret").unwrap_err(), Error::InvalidPassword);
+
+        // 相同的盐值、IV与迭代次数下密文须与OpenSSL一致，伪随机函数使用GmSSL的标识，OpenSSL同样可以解密
+        let salt = hex::decode("d3bbea87a327f2fc4c8ca3883019b3a5").unwrap();
+        let iv = hex::decode("a9080fd761f8c49afae4590d3cbeffd2").unwrap();
+        let encrypted = encrypt_with(&info, b"secret", &salt, &iv, 2048);
+        assert!(encrypted.ends_with(&der[der.len() - 147..]));
+    }
+}
@@ -0,0 +1,59 @@
+//! PEM编码（RFC 7468）
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+
+use crate::error::{Error, Result};
+
+/// 将DER数据编码为PEM格式，每行64个字符
+pub(crate) fn encode(label: &str, der: &[u8]) -> String {
+    let data = STANDARD.encode(der);
+    let mut pem = format!("-----BEGIN {}-----\n", label);
+    for line in data.as_bytes().chunks(64) {
+        pem.push_str(&String::from_utf8_lossy(line));
+        pem.push('\n');
+    }
+    pem.push_str(&format!("-----END {}-----\n", label));
+    pem
+}
+
+/// 解码PEM格式的数据，label须与封装边界中的标签一致
+///
+/// 封装边界之前的说明文字会被忽略，不支持带有头部字段（如Proc-Type）的旧格式。
+pub(crate) fn decode(label: &str, pem: &str) -> Result<Vec<u8>> {
+    let begin = format!("-----BEGIN {}-----", label);
+    let end = format!("-----END {}-----", label);
+
+    let start = match pem.find(&begin) {
+        Some(i) => i + begin.len(),
+        None => return Err(Error::InvalidPem("The begin boundary is missing.")),
+    };
+    let stop = match pem[start..].find(&end) {
+        Some(i) => start + i,
+        None => return Err(Error::InvalidPem("The end boundary is missing.")),
+    };
+
+    let data: String = pem[start..stop].chars().filter(|c| !c.is_whitespace()).collect();
+    if data.contains(':') {
+        return Err(Error::InvalidPem("The encapsulated headers are not supported."));
+    }
+    STANDARD.decode(data).map_err(|_| Error::InvalidPem("The data is not base64 encoded."))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pem() {
+        let data: Vec<u8> = (0..=255u8).collect();
+        let pem = encode("TEST", &data);
+        assert!(pem.lines().all(|line| line.len() <= 64));
+        assert_eq!(decode("TEST", &format!("说明文字\r\n{}", pem.replace('\n', "\r\n"))).unwrap(), data);
+
+        assert_eq!(decode("OTHER", &pem).unwrap_err(), Error::InvalidPem("The begin boundary is missing."));
+        assert!(decode("TEST", &pem.replace('A', "*")).is_err());
+        assert!(decode("TEST", &pem[..pem.len() - 10]).is_err());
+    }
+}
@@ -1,11 +1,32 @@
+//! SM2的字符串与字节串接口
+//!
+//! 可能失败的函数均以try_为前缀并返回[Result]；不带前缀的encrypt、decrypt、sign、verify等函数
+//! 保留原有的签名以兼容旧版本，遇到错误时panic。
 use std::rc::Rc;
-use crate::sm2::ecc::{Crypto, Decryption, Encryption, Signature};
+use crate::error::{Error, OrPanic, Result};
+use crate::{pbes2, pem};
+use crate::sm2::ecc::{Ciphertext, Crypto, Decryption, Encryption, Mode, Signature};
 use crate::sm2::key::{HexKey, KeyGenerator, KeyPair, PrivateKey, PublicKey};
+use crate::sm2::nonce::Nonce;
 use crate::sm2::p256::P256Elliptic;
 
-mod key;
-mod ecc;
-mod p256;
+pub use crate::sm2::collaborative::{
+    CollaborativeClient, CollaborativeServer, DecryptRequest, DecryptResponse, KeyShare, SignRequest, SignResponse,
+};
+pub use crate::sm2::exchange::KeyExchange;
+pub use crate::sm2::homomorphic::{HomomorphicCiphertext, HomomorphicDecryptor};
+
+mod asn1;
+mod collaborative;
+pub(crate) mod key;
+pub(crate) mod ecc;
+mod exchange;
+mod homomorphic;
+mod hybrid;
+mod nonce;
+pub(crate) mod p256;
+#[cfg(test)]
+mod vectors;
 
 
 pub fn generate_keypair() -> (String, String) {
@@ -15,34 +36,388 @@ pub fn generate_keypair() -> (String, String) {
     (pair.prk().encode(), pair.puk().encode())
 }
 
+/// 将公钥转换为压缩格式（02 | 03 ∥ x），输入可以是非压缩、压缩或混合格式
+pub fn try_compress_public_key(public_key: &str) -> Result<String> {
+    Ok(PublicKey::decode(public_key)?.encode_compressed())
+}
+
+/// 将公钥转换为非压缩格式（04 ∥ x ∥ y），输入可以是非压缩、压缩或混合格式
+pub fn try_decompress_public_key(public_key: &str) -> Result<String> {
+    Ok(PublicKey::decode(public_key)?.encode())
+}
+
+/// 校验公钥：格式正确，且是曲线上阶为n的点
+pub fn validate_public_key(public_key: &str) -> bool {
+    PublicKey::decode(public_key).is_ok()
+}
+
+/// 将公钥编码为DER格式的SubjectPublicKeyInfo
+pub fn try_public_key_to_spki(public_key: &str) -> Result<Vec<u8>> {
+    Ok(PublicKey::decode(public_key)?.encode_spki())
+}
+
+/// 将公钥编码为PEM格式的SubjectPublicKeyInfo（PUBLIC KEY）
+pub fn try_public_key_to_spki_pem(public_key: &str) -> Result<String> {
+    Ok(pem::encode("PUBLIC KEY", &try_public_key_to_spki(public_key)?))
+}
+
+/// 解码DER格式的SubjectPublicKeyInfo，返回非压缩格式的公钥
+pub fn try_public_key_from_spki(der: &[u8]) -> Result<String> {
+    Ok(PublicKey::decode_spki(der)?.encode())
+}
+
+/// 解码PEM格式的SubjectPublicKeyInfo（PUBLIC KEY），返回非压缩格式的公钥
+pub fn try_public_key_from_spki_pem(pem: &str) -> Result<String> {
+    try_public_key_from_spki(&pem::decode("PUBLIC KEY", pem)?)
+}
+
+/// 将私钥编码为DER格式的PKCS#8 PrivateKeyInfo，public_key不为空时一并嵌入公钥
+pub fn try_private_key_to_pkcs8(private_key: &str, public_key: Option<&str>) -> Result<Vec<u8>> {
+    let (prk, puk) = decode_keypair(private_key, public_key)?;
+    Ok(prk.encode_pkcs8(puk.as_ref()))
+}
+
+/// 将私钥编码为PEM格式的PKCS#8 PrivateKeyInfo（PRIVATE KEY）
+pub fn try_private_key_to_pkcs8_pem(private_key: &str, public_key: Option<&str>) -> Result<String> {
+    Ok(pem::encode("PRIVATE KEY", &try_private_key_to_pkcs8(private_key, public_key)?))
+}
+
+/// 解码DER格式的PKCS#8 PrivateKeyInfo，返回私钥以及嵌入的公钥
+pub fn try_private_key_from_pkcs8(der: &[u8]) -> Result<(String, Option<String>)> {
+    let (prk, puk) = PrivateKey::decode_pkcs8(der)?;
+    Ok((prk.encode(), puk.map(|key| key.encode())))
+}
+
+/// 解码PEM格式的PKCS#8 PrivateKeyInfo（PRIVATE KEY），返回私钥以及嵌入的公钥
+pub fn try_private_key_from_pkcs8_pem(pem: &str) -> Result<(String, Option<String>)> {
+    try_private_key_from_pkcs8(&pem::decode("PRIVATE KEY", pem)?)
+}
+
+/// 使用口令加密私钥，返回DER格式的PKCS#8 EncryptedPrivateKeyInfo
+///
+/// 加密方案为PBES2：PBKDF2-HMAC-SM3派生秘钥（迭代65536次），SM4-CBC加密PrivateKeyInfo。
+pub fn try_private_key_to_encrypted_pkcs8(private_key: &str, public_key: Option<&str>, password: &[u8]) -> Result<Vec<u8>> {
+    let info = try_private_key_to_pkcs8(private_key, public_key)?;
+    Ok(pbes2::encrypt(&info, password, pbes2::ITERATIONS))
+}
+
+/// 使用口令加密私钥，返回PEM格式的PKCS#8 EncryptedPrivateKeyInfo（ENCRYPTED PRIVATE KEY）
+pub fn try_private_key_to_encrypted_pkcs8_pem(private_key: &str, public_key: Option<&str>, password: &[u8]) -> Result<String> {
+    Ok(pem::encode("ENCRYPTED PRIVATE KEY", &try_private_key_to_encrypted_pkcs8(private_key, public_key, password)?))
+}
+
+/// 使用口令解密DER格式的PKCS#8 EncryptedPrivateKeyInfo，返回私钥以及嵌入的公钥
+pub fn try_private_key_from_encrypted_pkcs8(der: &[u8], password: &[u8]) -> Result<(String, Option<String>)> {
+    let info = pbes2::decrypt(der, password)?;
+    try_private_key_from_pkcs8(&info).map_err(|_| Error::InvalidPassword)
+}
+
+/// 使用口令解密PEM格式的PKCS#8 EncryptedPrivateKeyInfo（ENCRYPTED PRIVATE KEY），返回私钥以及嵌入的公钥
+pub fn try_private_key_from_encrypted_pkcs8_pem(pem: &str, password: &[u8]) -> Result<(String, Option<String>)> {
+    try_private_key_from_encrypted_pkcs8(&pem::decode("ENCRYPTED PRIVATE KEY", pem)?, password)
+}
+
+/// 将私钥编码为DER格式的SEC1 ECPrivateKey，包含SM2曲线参数，public_key不为空时一并嵌入公钥
+pub fn try_private_key_to_sec1(private_key: &str, public_key: Option<&str>) -> Result<Vec<u8>> {
+    let (prk, puk) = decode_keypair(private_key, public_key)?;
+    Ok(prk.encode_sec1(puk.as_ref(), true))
+}
+
+/// 将私钥编码为PEM格式的SEC1 ECPrivateKey（EC PRIVATE KEY）
+pub fn try_private_key_to_sec1_pem(private_key: &str, public_key: Option<&str>) -> Result<String> {
+    Ok(pem::encode("EC PRIVATE KEY", &try_private_key_to_sec1(private_key, public_key)?))
+}
+
+/// 解码DER格式的SEC1 ECPrivateKey，返回私钥以及嵌入的公钥
+pub fn try_private_key_from_sec1(der: &[u8]) -> Result<(String, Option<String>)> {
+    let (prk, puk) = PrivateKey::decode_sec1(der)?;
+    Ok((prk.encode(), puk.map(|key| key.encode())))
+}
+
+/// 解码PEM格式的SEC1 ECPrivateKey，返回私钥以及嵌入的公钥
+///
+/// 除EC PRIVATE KEY外，也接受OpenSSL 3为SM2秘钥输出的SM2 PRIVATE KEY标签。
+pub fn try_private_key_from_sec1_pem(pem: &str) -> Result<(String, Option<String>)> {
+    let label = if pem.contains("-----BEGIN SM2 PRIVATE KEY-----") { "SM2 PRIVATE KEY" } else { "EC PRIVATE KEY" };
+    try_private_key_from_sec1(&pem::decode(label, pem)?)
+}
+
 pub fn encrypt(public_key: &str, plain: &str) -> String {
-    let crypto = Crypto::default();
-    crypto.encryptor(PublicKey::decode(public_key)).execute(plain)
+    try_encrypt(public_key, plain).or_panic()
 }
 
 pub fn decrypt(private_key: &str, cipher: &str) -> String {
-    let crypto = Crypto::default();
-    crypto.decryptor(PrivateKey::decode(private_key)).execute(cipher)
+    try_decrypt(private_key, cipher).or_panic()
 }
 
 pub fn encrypt_c1c2c3(public_key: &str, plain: &str) -> String {
-    let crypto = Crypto::c1c2c3(Rc::new(P256Elliptic::init()));
-    crypto.encryptor(PublicKey::decode(public_key)).execute(plain)
+    try_encrypt_c1c2c3(public_key, plain).or_panic()
 }
 
 pub fn decrypt_c1c2c3(private_key: &str, cipher: &str) -> String {
-    let crypto = Crypto::c1c2c3(Rc::new(P256Elliptic::init()));
-    crypto.decryptor(PrivateKey::decode(private_key)).execute(cipher)
+    try_decrypt_c1c2c3(private_key, cipher).or_panic()
 }
 
 pub fn sign(private_key: &str, public_key: &str, plain: &str) -> String {
-    let crypto = Crypto::default();
-    let keypair = KeyPair::new(PrivateKey::decode(private_key), PublicKey::decode(public_key));
-    hex::encode(crypto.signer(keypair).sign(&plain).encode())
+    try_sign(private_key, public_key, plain).or_panic()
 }
 
 pub fn verify(public_key: &str, plain: &str, signature: &str) -> bool {
+    try_verify(public_key, plain, signature).or_panic()
+}
+
+pub fn try_encrypt(public_key: &str, plain: &str) -> Result<String> {
+    let crypto = Crypto::default();
+    Ok(crypto.encryptor(PublicKey::decode(public_key)?).execute(plain))
+}
+
+pub fn try_decrypt(private_key: &str, cipher: &str) -> Result<String> {
+    let crypto = Crypto::default();
+    crypto.decryptor(PrivateKey::decode(private_key)?).execute(cipher)
+}
+
+pub fn try_encrypt_c1c2c3(public_key: &str, plain: &str) -> Result<String> {
+    let crypto = Crypto::c1c2c3(Rc::new(P256Elliptic::init()));
+    Ok(crypto.encryptor(PublicKey::decode(public_key)?).execute(plain))
+}
+
+pub fn try_decrypt_c1c2c3(private_key: &str, cipher: &str) -> Result<String> {
+    let crypto = Crypto::c1c2c3(Rc::new(P256Elliptic::init()));
+    crypto.decryptor(PrivateKey::decode(private_key)?).execute(cipher)
+}
+
+pub fn try_sign(private_key: &str, public_key: &str, plain: &str) -> Result<String> {
+    let crypto = Crypto::default();
+    let keypair = KeyPair::new(PrivateKey::decode(private_key)?, PublicKey::decode(public_key)?);
+    Ok(hex::encode(crypto.signer(keypair).sign(plain).encode()))
+}
+
+pub fn try_verify(public_key: &str, plain: &str, signature: &str) -> Result<bool> {
+    let crypto = Crypto::default();
+    let s = Signature::decode(&hex::decode(signature)?)?;
+    Ok(crypto.verifier(PublicKey::decode(public_key)?).verify(plain, &s))
+}
+
+/// 使用确定性随机数签名（RFC 6979，HMAC-SM3），相同的秘钥与明文总是得到相同的签名
+pub fn try_sign_deterministic(private_key: &str, public_key: &str, plain: &str) -> Result<String> {
+    Ok(hex::encode(try_sign_bytes_deterministic(private_key, public_key, plain.as_bytes())?))
+}
+
+/// 使用指定的用户身份标识（IDA）签名，id长度不能超过8191字节
+pub fn try_sign_with_id(private_key: &str, public_key: &str, id: &[u8], plain: &str) -> Result<String> {
+    let crypto = Crypto::default();
+    let keypair = KeyPair::new(PrivateKey::decode(private_key)?, PublicKey::decode(public_key)?);
+    Ok(hex::encode(crypto.signer_with_id(keypair, id)?.sign(plain).encode()))
+}
+
+/// 使用指定的用户身份标识（IDA）验签，id须与签名时一致
+pub fn try_verify_with_id(public_key: &str, id: &[u8], plain: &str, signature: &str) -> Result<bool> {
+    let crypto = Crypto::default();
+    let s = Signature::decode(&hex::decode(signature)?)?;
+    Ok(crypto.verifier_with_id(PublicKey::decode(public_key)?, id)?.verify(plain, &s))
+}
+
+/// 加密任意字节串，返回C1C3C2格式的密文字节串
+pub fn try_encrypt_bytes(public_key: &str, plain: &[u8]) -> Result<Vec<u8>> {
     let crypto = Crypto::default();
-    let s = Signature::decode(hex::decode(signature).unwrap().as_slice());
-    crypto.verifier(PublicKey::decode(public_key)).verify(plain, &s)
-}
\ No newline at end of file
+    Ok(crypto.encryptor(PublicKey::decode(public_key)?).execute_bytes(plain))
+}
+
+/// 解密C1C3C2格式的密文字节串
+pub fn try_decrypt_bytes(private_key: &str, cipher: &[u8]) -> Result<Vec<u8>> {
+    let crypto = Crypto::default();
+    crypto.decryptor(PrivateKey::decode(private_key)?).execute_bytes(cipher)
+}
+
+/// 加密任意字节串，返回C1C2C3格式的密文字节串
+pub fn try_encrypt_c1c2c3_bytes(public_key: &str, plain: &[u8]) -> Result<Vec<u8>> {
+    let crypto = Crypto::c1c2c3(Rc::new(P256Elliptic::init()));
+    Ok(crypto.encryptor(PublicKey::decode(public_key)?).execute_bytes(plain))
+}
+
+/// 解密C1C2C3格式的密文字节串
+pub fn try_decrypt_c1c2c3_bytes(private_key: &str, cipher: &[u8]) -> Result<Vec<u8>> {
+    let crypto = Crypto::c1c2c3(Rc::new(P256Elliptic::init()));
+    crypto.decryptor(PrivateKey::decode(private_key)?).execute_bytes(cipher)
+}
+
+/// 加密任意字节串，返回GM/T 0009定义的DER编码密文
+pub fn try_encrypt_der_bytes(public_key: &str, plain: &[u8]) -> Result<Vec<u8>> {
+    let crypto = Crypto::der(Rc::new(P256Elliptic::init()));
+    Ok(crypto.encryptor(PublicKey::decode(public_key)?).execute_bytes(plain))
+}
+
+/// 解密GM/T 0009定义的DER编码密文
+pub fn try_decrypt_der_bytes(private_key: &str, cipher: &[u8]) -> Result<Vec<u8>> {
+    let crypto = Crypto::der(Rc::new(P256Elliptic::init()));
+    crypto.decryptor(PrivateKey::decode(private_key)?).execute_bytes(cipher)
+}
+
+/// 加密字符串，返回十六进制编码的DER密文
+pub fn try_encrypt_der(public_key: &str, plain: &str) -> Result<String> {
+    let crypto = Crypto::der(Rc::new(P256Elliptic::init()));
+    Ok(crypto.encryptor(PublicKey::decode(public_key)?).execute(plain))
+}
+
+/// 解密十六进制编码的DER密文
+pub fn try_decrypt_der(private_key: &str, cipher: &str) -> Result<String> {
+    let crypto = Crypto::der(Rc::new(P256Elliptic::init()));
+    crypto.decryptor(PrivateKey::decode(private_key)?).execute(cipher)
+}
+
+/// 多接收者混合加密：以随机的SM4秘钥（GCM模式）加密明文，再以SM2为每个公钥加密该秘钥
+///
+/// 返回自描述的DER编码密文，任一接收者都可以用自己的私钥通过[try_hybrid_decrypt]解密。
+/// 明文较大或接收者较多时，比逐个调用[try_encrypt_bytes]快得多，密文也只比明文多出每个接收者约160字节。
+pub fn try_hybrid_encrypt(public_keys: &[&str], plain: &[u8]) -> Result<Vec<u8>> {
+    let public_keys = public_keys.iter().map(|e| PublicKey::decode(e)).collect::<Result<Vec<_>>>()?;
+    hybrid::encrypt(&public_keys, plain)
+}
+
+/// 解密[try_hybrid_encrypt]生成的密文
+pub fn try_hybrid_decrypt(private_key: &str, cipher: &[u8]) -> Result<Vec<u8>> {
+    hybrid::decrypt(&PrivateKey::decode(private_key)?, cipher)
+}
+
+/// 以加法同态的EC-ElGamal加密整数，密文可以直接相加，解密结果为明文之和
+pub fn try_homomorphic_encrypt(public_key: &str, value: u64) -> Result<HomomorphicCiphertext> {
+    Ok(homomorphic::encrypt(&PublicKey::decode(public_key)?, value))
+}
+
+/// 解密[try_homomorphic_encrypt]生成或相加得到的密文，明文须在\[0, max]内，耗时与√max成正比
+///
+/// max不能超过2^32 - 1，此时小步表有2^16项，超过时返回错误。每次调用都会重新计算小步表，
+/// 需要解密多个密文时应复用[HomomorphicDecryptor]。
+pub fn try_homomorphic_decrypt(private_key: &str, cipher: &HomomorphicCiphertext, max: u64) -> Result<u64> {
+    homomorphic::decrypt(&PrivateKey::decode(private_key)?, cipher, max)
+}
+
+/// 将C1C3C2格式的密文转换为DER编码
+pub fn try_c1c3c2_to_der(cipher: &[u8]) -> Result<Vec<u8>> {
+    Ok(Ciphertext::decode(cipher, Mode::C1C3C2)?.encode(Mode::Der))
+}
+
+/// 将C1C2C3格式的密文转换为DER编码
+pub fn try_c1c2c3_to_der(cipher: &[u8]) -> Result<Vec<u8>> {
+    Ok(Ciphertext::decode(cipher, Mode::C1C2C3)?.encode(Mode::Der))
+}
+
+/// 将DER编码的密文转换为C1C3C2格式
+pub fn try_der_to_c1c3c2(cipher: &[u8]) -> Result<Vec<u8>> {
+    Ok(Ciphertext::decode(cipher, Mode::Der)?.encode(Mode::C1C3C2))
+}
+
+/// 将DER编码的密文转换为C1C2C3格式
+pub fn try_der_to_c1c2c3(cipher: &[u8]) -> Result<Vec<u8>> {
+    Ok(Ciphertext::decode(cipher, Mode::Der)?.encode(Mode::C1C2C3))
+}
+
+/// 对任意字节串签名，返回DER编码的签名
+pub fn try_sign_bytes(private_key: &str, public_key: &str, plain: &[u8]) -> Result<Vec<u8>> {
+    let crypto = Crypto::default();
+    let keypair = KeyPair::new(PrivateKey::decode(private_key)?, PublicKey::decode(public_key)?);
+    Ok(crypto.signer(keypair).sign_bytes(plain).encode())
+}
+
+/// 使用确定性随机数对任意字节串签名，返回DER编码的签名
+pub fn try_sign_bytes_deterministic(private_key: &str, public_key: &str, plain: &[u8]) -> Result<Vec<u8>> {
+    let crypto = Crypto::default().with_nonce(Nonce::Deterministic);
+    let keypair = KeyPair::new(PrivateKey::decode(private_key)?, PublicKey::decode(public_key)?);
+    Ok(crypto.signer(keypair).sign_bytes(plain).encode())
+}
+
+/// 使用DER编码的签名对任意字节串验签
+pub fn try_verify_bytes(public_key: &str, plain: &[u8], signature: &[u8]) -> Result<bool> {
+    let crypto = Crypto::default();
+    let s = Signature::decode(signature)?;
+    Ok(crypto.verifier(PublicKey::decode(public_key)?).verify_bytes(plain, &s))
+}
+
+/// 使用指定的用户身份标识（IDA）对任意字节串签名
+pub fn try_sign_bytes_with_id(private_key: &str, public_key: &str, id: &[u8], plain: &[u8]) -> Result<Vec<u8>> {
+    let crypto = Crypto::default();
+    let keypair = KeyPair::new(PrivateKey::decode(private_key)?, PublicKey::decode(public_key)?);
+    Ok(crypto.signer_with_id(keypair, id)?.sign_bytes(plain).encode())
+}
+
+/// 使用指定的用户身份标识（IDA）对任意字节串验签
+pub fn try_verify_bytes_with_id(public_key: &str, id: &[u8], plain: &[u8], signature: &[u8]) -> Result<bool> {
+    let crypto = Crypto::default();
+    let s = Signature::decode(signature)?;
+    Ok(crypto.verifier_with_id(PublicKey::decode(public_key)?, id)?.verify_bytes(plain, &s))
+}
+
+/// 将DER编码的签名转换为64字节的 r ∥ s 格式
+pub fn try_signature_der_to_raw(signature: &[u8]) -> Result<Vec<u8>> {
+    Ok(Signature::decode(signature)?.encode_raw())
+}
+
+/// 将64字节的 r ∥ s 格式签名转换为DER编码
+pub fn try_signature_raw_to_der(signature: &[u8]) -> Result<Vec<u8>> {
+    Ok(Signature::decode_raw(signature)?.encode())
+}
+
+/// 计算用户的杂凑值 ZA = SM3(ENTLA ∥ IDA ∥ a ∥ b ∥ xG ∥ yG ∥ xA ∥ yA)
+///
+/// 签名方可在其他环境中流式计算 e = SM3(ZA ∥ M)，再调用[try_sign_digest]完成签名，无需将消息整体载入内存。
+pub fn try_za(public_key: &str, id: &[u8]) -> Result<Vec<u8>> {
+    let crypto = Crypto::default();
+    crypto.digest(id, PublicKey::decode(public_key)?)
+}
+
+/// 对预先计算的32字节杂凑值 e = SM3(ZA ∥ M) 签名，返回DER编码的签名
+pub fn try_sign_digest(private_key: &str, public_key: &str, digest: &[u8]) -> Result<Vec<u8>> {
+    let crypto = Crypto::default();
+    let keypair = KeyPair::new(PrivateKey::decode(private_key)?, PublicKey::decode(public_key)?);
+    Ok(crypto.signer(keypair).sign_digest(&to_digest(digest)?).encode())
+}
+
+/// 使用预先计算的32字节杂凑值 e = SM3(ZA ∥ M) 验签
+pub fn try_verify_digest(public_key: &str, digest: &[u8], signature: &[u8]) -> Result<bool> {
+    let crypto = Crypto::default();
+    let s = Signature::decode(signature)?;
+    Ok(crypto.verifier(PublicKey::decode(public_key)?).verify_digest(&to_digest(digest)?, &s))
+}
+
+/// 创建密钥交换（GB/T 32918.3）的发起方A
+///
+/// private_key、public_key、id为己方的秘钥与用户身份标识，peer_public_key、peer_id为响应方B的公钥与用户身份标识。
+/// 通过[KeyExchange::point]得到临时公钥RA发送给B，收到RB后调用[KeyExchange::exchange]派生共享秘钥。
+pub fn try_key_exchange_initiator(
+    private_key: &str, public_key: &str, id: &[u8], peer_public_key: &str, peer_id: &[u8],
+) -> Result<KeyExchange> {
+    let crypto = Crypto::default();
+    let keypair = KeyPair::new(PrivateKey::decode(private_key)?, PublicKey::decode(public_key)?);
+    crypto.initiator(keypair, id, PublicKey::decode(peer_public_key)?, peer_id)
+}
+
+/// 创建密钥交换（GB/T 32918.3）的响应方B
+///
+/// private_key、public_key、id为己方的秘钥与用户身份标识，peer_public_key、peer_id为发起方A的公钥与用户身份标识。
+pub fn try_key_exchange_responder(
+    private_key: &str, public_key: &str, id: &[u8], peer_public_key: &str, peer_id: &[u8],
+) -> Result<KeyExchange> {
+    let crypto = Crypto::default();
+    let keypair = KeyPair::new(PrivateKey::decode(private_key)?, PublicKey::decode(public_key)?);
+    crypto.responder(keypair, id, PublicKey::decode(peer_public_key)?, peer_id)
+}
+
+fn to_digest(data: &[u8]) -> Result<[u8; 32]> {
+    <[u8; 32]>::try_from(data).map_err(|_| Error::InvalidDigest)
+}
+
+/// 解码私钥以及可选的公钥，公钥须与私钥匹配
+pub(crate) fn decode_keypair(private_key: &str, public_key: Option<&str>) -> Result<(PrivateKey, Option<PublicKey>)> {
+    let prk = PrivateKey::decode(private_key)?;
+    let puk = match public_key {
+        Some(key) => {
+            let puk = PublicKey::decode(key)?;
+            if puk.value() != prk.public_key().value() {
+                return Err(Error::InvalidPrivateKey("The public key does not match the private key."));
+            }
+            Some(puk)
+        }
+        None => None,
+    };
+    Ok((prk, puk))
+}
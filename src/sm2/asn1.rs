@@ -0,0 +1,303 @@
+//! SM2秘钥的ASN.1编码
+//!
+//! 1. SEC1 ECPrivateKey（RFC 5915）；
+//! 2. PKCS#8 PrivateKeyInfo（RFC 5208），算法为id-ecPublicKey，参数为SM2曲线OID；
+//! 3. SubjectPublicKeyInfo（RFC 5480），算法标识与PKCS#8相同。
+
+use num_bigint::BigUint;
+use yasna::models::ObjectIdentifier;
+use yasna::{ASN1Error, ASN1ErrorKind, BERReader, DERWriter, Tag};
+
+use crate::error::{Error, Result};
+use crate::oid;
+use crate::sm2::key::{HexKey, PrivateKey, PublicKey, to_32_bytes};
+
+impl PrivateKey {
+    /// ECPrivateKey ::= SEQUENCE {
+    ///     version        INTEGER { ecPrivkeyVer1(1) },
+    ///     privateKey     OCTET STRING,
+    ///     parameters [0] ECParameters {{ NamedCurve }} OPTIONAL,
+    ///     publicKey  [1] BIT STRING OPTIONAL
+    /// }
+    ///
+    /// 单独使用时包含曲线参数，嵌入PKCS#8时省略（曲线由算法标识给出）。
+    pub(crate) fn encode_sec1(&self, puk: Option<&PublicKey>, parameters: bool) -> Vec<u8> {
+        yasna::construct_der(|writer| {
+            writer.write_sequence(|writer| {
+                writer.next().write_u8(1);
+                writer.next().write_bytes(&to_32_bytes(self.value().to_bytes_be()));
+                if parameters {
+                    writer.next().write_tagged(Tag::context(0), |writer| {
+                        writer.write_oid(&ObjectIdentifier::from_slice(oid::SM2))
+                    });
+                }
+                if let Some(puk) = puk {
+                    writer.next().write_tagged(Tag::context(1), |writer| write_public_key(writer, puk));
+                }
+            })
+        })
+    }
+
+    /// 解码ECPrivateKey，若包含公钥，则须与私钥匹配
+    pub(crate) fn decode_sec1(der: &[u8]) -> Result<(PrivateKey, Option<PublicKey>)> {
+        let (d, curve, puk) = yasna::parse_der(der, |reader| {
+            reader.read_sequence(|reader| {
+                if reader.next().read_u8()? != 1 {
+                    return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+                }
+                let d = reader.next().read_bytes()?;
+                let curve = reader.read_optional(|reader| reader.read_tagged(Tag::context(0), |reader| reader.read_oid()))?;
+                let puk = reader.read_optional(|reader| reader.read_tagged(Tag::context(1), read_public_key))?;
+                Ok((d, curve, puk))
+            })
+        }).map_err(|_| Error::InvalidDer("The data is not a DER-encoded ECPrivateKey."))?;
+
+        if let Some(curve) = curve {
+            if curve != ObjectIdentifier::from_slice(oid::SM2) {
+                return Err(Error::InvalidDer("The curve is not SM2."));
+            }
+        }
+
+        keypair(&d, puk)
+    }
+
+    /// PrivateKeyInfo ::= SEQUENCE {
+    ///     version                   INTEGER,
+    ///     privateKeyAlgorithm       AlgorithmIdentifier,
+    ///     privateKey                OCTET STRING,
+    ///     attributes           [0]  IMPLICIT Attributes OPTIONAL
+    /// }
+    pub(crate) fn encode_pkcs8(&self, puk: Option<&PublicKey>) -> Vec<u8> {
+        yasna::construct_der(|writer| {
+            writer.write_sequence(|writer| {
+                writer.next().write_u8(0);
+                write_algorithm(writer.next());
+                writer.next().write_bytes(&self.encode_sec1(puk, false));
+            })
+        })
+    }
+
+    /// 解码PrivateKeyInfo，忽略其中的属性
+    pub(crate) fn decode_pkcs8(der: &[u8]) -> Result<(PrivateKey, Option<PublicKey>)> {
+        let (algorithm, key) = yasna::parse_der(der, |reader| {
+            reader.read_sequence(|reader| {
+                if reader.next().read_u8()? != 0 {
+                    return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+                }
+                let algorithm = read_algorithm(reader.next())?;
+                let key = reader.next().read_bytes()?;
+                reader.read_optional(|reader| reader.read_tagged_implicit(Tag::context(0), |reader| reader.read_der()))?;
+                Ok((algorithm, key))
+            })
+        }).map_err(|_| Error::InvalidDer("The data is not a DER-encoded PrivateKeyInfo."))?;
+
+        if !algorithm {
+            return Err(Error::InvalidDer("The key algorithm is not SM2."));
+        }
+        PrivateKey::decode_sec1(&key)
+    }
+}
+
+impl PublicKey {
+    /// SubjectPublicKeyInfo ::= SEQUENCE {
+    ///     algorithm         AlgorithmIdentifier,
+    ///     subjectPublicKey  BIT STRING
+    /// }
+    pub(crate) fn encode_spki(&self) -> Vec<u8> {
+        yasna::construct_der(|writer| write_spki(writer, self))
+    }
+
+    /// 解码SubjectPublicKeyInfo，公钥可以是非压缩、压缩或混合格式
+    pub(crate) fn decode_spki(der: &[u8]) -> Result<PublicKey> {
+        let (algorithm, key) = yasna::parse_der(der, read_spki)
+            .map_err(|_| Error::InvalidDer("The data is not a DER-encoded SubjectPublicKeyInfo."))?;
+
+        if !algorithm {
+            return Err(Error::InvalidDer("The key algorithm is not SM2."));
+        }
+        PublicKey::decode(&hex::encode(key))
+    }
+}
+
+pub(crate) fn write_spki(writer: DERWriter, puk: &PublicKey) {
+    writer.write_sequence(|writer| {
+        write_algorithm(writer.next());
+        write_public_key(writer.next(), puk);
+    })
+}
+
+/// 读取SubjectPublicKeyInfo，返回算法是否为SM2以及公钥的字节串
+pub(crate) fn read_spki(reader: BERReader) -> std::result::Result<(bool, Vec<u8>), ASN1Error> {
+    reader.read_sequence(|reader| {
+        let algorithm = read_algorithm(reader.next())?;
+        let key = read_public_key(reader.next())?;
+        Ok((algorithm, key))
+    })
+}
+
+/// AlgorithmIdentifier ::= SEQUENCE { algorithm id-ecPublicKey, parameters sm2 }
+pub(crate) fn write_algorithm(writer: DERWriter) {
+    writer.write_sequence(|writer| {
+        writer.next().write_oid(&ObjectIdentifier::from_slice(oid::EC_PUBLIC_KEY));
+        writer.next().write_oid(&ObjectIdentifier::from_slice(oid::SM2));
+    })
+}
+
+/// 读取AlgorithmIdentifier，返回是否为SM2算法
+///
+/// 除了标准的id-ecPublicKey加SM2曲线参数外，也接受部分早期实现直接使用SM2 OID作为算法、参数缺省或为NULL的形式。
+pub(crate) fn read_algorithm(reader: BERReader) -> std::result::Result<bool, ASN1Error> {
+    reader.read_sequence(|reader| {
+        let algorithm = reader.next().read_oid()?;
+        let parameters = reader.read_optional(|reader| {
+            match reader.lookahead_tag()? {
+                yasna::tags::TAG_OID => reader.read_oid().map(Some),
+                _ => reader.read_null().map(|_| None),
+            }
+        })?.flatten();
+
+        let (ec, sm2) = (ObjectIdentifier::from_slice(oid::EC_PUBLIC_KEY), ObjectIdentifier::from_slice(oid::SM2));
+        Ok(match (algorithm, parameters) {
+            (algorithm, Some(curve)) if algorithm == ec => curve == sm2,
+            (algorithm, Some(curve)) => algorithm == sm2 && curve == sm2,
+            (algorithm, None) => algorithm == sm2,
+        })
+    })
+}
+
+/// 公钥以非压缩格式写入BIT STRING
+pub(crate) fn write_public_key(writer: DERWriter, puk: &PublicKey) {
+    let key = hex::decode(puk.encode()).unwrap();
+    writer.write_bitvec_bytes(&key, key.len() * 8);
+}
+
+/// 读取BIT STRING中的公钥，支持非压缩、压缩以及混合格式
+pub(crate) fn read_public_key(reader: BERReader) -> std::result::Result<Vec<u8>, ASN1Error> {
+    let (key, bits) = reader.read_bitvec_bytes()?;
+    if bits != key.len() * 8 {
+        return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+    }
+    Ok(key)
+}
+
+/// 私钥为32字节的OCTET STRING，部分实现会省略前导0，此处予以兼容
+fn keypair(d: &[u8], puk: Option<Vec<u8>>) -> Result<(PrivateKey, Option<PublicKey>)> {
+    if d.is_empty() || d.len() > 32 {
+        return Err(Error::InvalidPrivateKey("The length of the private key must be 32 bytes."));
+    }
+    let prk = PrivateKey::new(BigUint::from_bytes_be(d))?;
+
+    let puk = match puk {
+        Some(key) => {
+            let puk = PublicKey::decode(&hex::encode(key))?;
+            if puk.value() != prk.public_key().value() {
+                return Err(Error::InvalidPrivateKey("The public key does not match the private key."));
+            }
+            Some(puk)
+        }
+        None => None,
+    };
+    Ok((prk, puk))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PRK: &str = "6aea1ccf610488aaa7fddba3dd6d76d3bdfd50f957d847be3d453defb695f28e";
+    const PUK: &str = "04a8af64e38eea41c254df769b5b41fbaa2d77b226b301a2636d463c52b46c777230ad1714e686dd641b9e04596530b38f6a64215b0ed3b081f8641724c5443a6e";
+
+    /// 由OpenSSL 3生成：openssl ec -in key.pem -outform DER
+    const SEC1: &str = "307702010104206aea1ccf610488aaa7fddba3dd6d76d3bdfd50f957d847be3d453defb695f28ea00a06082a811ccf5501822d\
+    a14403420004a8af64e38eea41c254df769b5b41fbaa2d77b226b301a2636d463c52b46c777230ad1714e686dd641b9e04596530b38f6a6421\
+    5b0ed3b081f8641724c5443a6e";
+
+    /// 由OpenSSL 3生成：openssl pkcs8 -topk8 -nocrypt -in key.pem -outform DER
+    const PKCS8: &str = "308187020100301306072a8648ce3d020106082a811ccf5501822d046d306b02010104206aea1ccf610488aaa7fddba3dd6d\
+    76d3bdfd50f957d847be3d453defb695f28ea14403420004a8af64e38eea41c254df769b5b41fbaa2d77b226b301a2636d463c52b46c777230\
+    ad1714e686dd641b9e04596530b38f6a64215b0ed3b081f8641724c5443a6e";
+
+    #[test]
+    fn sec1() {
+        let prk = PrivateKey::decode(PRK).unwrap();
+        let puk = PublicKey::decode(PUK).unwrap();
+
+        assert_eq!(hex::encode(prk.encode_sec1(Some(&puk), true)), SEC1);
+
+        let (key, public) = PrivateKey::decode_sec1(&hex::decode(SEC1).unwrap()).unwrap();
+        assert_eq!(key.encode(), PRK);
+        assert_eq!(public.unwrap().encode(), PUK);
+
+        let (key, public) = PrivateKey::decode_sec1(&prk.encode_sec1(None, false)).unwrap();
+        assert_eq!(key.encode(), PRK);
+        assert!(public.is_none());
+    }
+
+    #[test]
+    fn pkcs8() {
+        let prk = PrivateKey::decode(PRK).unwrap();
+        let puk = PublicKey::decode(PUK).unwrap();
+
+        assert_eq!(hex::encode(prk.encode_pkcs8(Some(&puk))), PKCS8);
+
+        let (key, public) = PrivateKey::decode_pkcs8(&hex::decode(PKCS8).unwrap()).unwrap();
+        assert_eq!(key.encode(), PRK);
+        assert_eq!(public.unwrap().encode(), PUK);
+
+        // SEC1结构不是PKCS#8
+        assert!(PrivateKey::decode_pkcs8(&hex::decode(SEC1).unwrap()).is_err());
+    }
+
+    #[test]
+    fn spki() {
+        let puk = PublicKey::decode(PUK).unwrap();
+
+        // 由OpenSSL 3生成：openssl pkey -pubin -in pub.pem -outform DER
+        let spki = "3059301306072a8648ce3d020106082a811ccf5501822d03420004a8af64e38eea41c254df769b5b41fbaa2d77b226b301a2636d4\
+        63c52b46c777230ad1714e686dd641b9e04596530b38f6a64215b0ed3b081f8641724c5443a6e";
+        assert_eq!(hex::encode(puk.encode_spki()), spki);
+        assert_eq!(PublicKey::decode_spki(&hex::decode(spki).unwrap()).unwrap().encode(), PUK);
+
+        // 早期实现直接使用SM2 OID作为算法标识，参数缺省或为NULL
+        let compressed = hex::decode(puk.encode_compressed()).unwrap();
+        for parameters in [vec![], vec![0x05, 0x00]] {
+            let legacy = yasna::construct_der(|writer| {
+                writer.write_sequence(|writer| {
+                    writer.next().write_sequence(|writer| {
+                        writer.next().write_oid(&ObjectIdentifier::from_slice(oid::SM2));
+                        if !parameters.is_empty() {
+                            writer.next().write_der(&parameters);
+                        }
+                    });
+                    writer.next().write_bitvec_bytes(&compressed, compressed.len() * 8);
+                })
+            });
+            assert_eq!(PublicKey::decode_spki(&legacy).unwrap().encode(), PUK);
+        }
+
+        // 其他曲线：prime256v1
+        let p256 = yasna::construct_der(|writer| {
+            writer.write_sequence(|writer| {
+                writer.next().write_sequence(|writer| {
+                    writer.next().write_oid(&ObjectIdentifier::from_slice(oid::EC_PUBLIC_KEY));
+                    writer.next().write_oid(&ObjectIdentifier::from_slice(&[1, 2, 840, 10045, 3, 1, 7]));
+                });
+                write_public_key(writer.next(), &puk);
+            })
+        });
+        assert_eq!(PublicKey::decode_spki(&p256).unwrap_err(), Error::InvalidDer("The key algorithm is not SM2."));
+    }
+
+    #[test]
+    fn mismatch() {
+        let prk = PrivateKey::decode(PRK).unwrap();
+        let other = PrivateKey::new(BigUint::from(2u8)).unwrap().public_key();
+
+        let der = prk.encode_pkcs8(Some(&other));
+        assert_eq!(
+            PrivateKey::decode_pkcs8(&der).unwrap_err(),
+            Error::InvalidPrivateKey("The public key does not match the private key.")
+        );
+    }
+}
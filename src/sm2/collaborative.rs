@@ -0,0 +1,520 @@
+use std::ops::{Add, Mul, Sub};
+use std::rc::Rc;
+
+use num_bigint::{BigUint, ToBigInt};
+use num_integer::Integer;
+use num_traits::{One, Zero};
+use yasna::ASN1Error;
+
+use crate::error::{Error, Result};
+use crate::sm2::ecc::{Ciphertext, Crypto, EllipticBuilder, Mode, Signature, UID};
+use crate::sm2::key::{encode_point, HexKey, PublicKey, to_32_bytes};
+use crate::sm2::p256::P256Elliptic;
+use crate::sm3;
+
+/// 两方协同签名中客户端发送给服务端的公钥分量
+///
+/// KeyShare ::= SEQUENCE { point OCTET STRING }  -- P1 = \[d1⁻¹]G，非压缩格式
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyShare {
+    point: (BigUint, BigUint),
+}
+
+impl KeyShare {
+    pub fn to_der(&self) -> Vec<u8> {
+        write_point(&self.point)
+    }
+
+    pub fn from_der(der: &[u8]) -> Result<KeyShare> {
+        Ok(KeyShare { point: read_point(der, "The KeyShare is not a SEQUENCE of an OCTET STRING.")? })
+    }
+}
+
+/// 两方协同签名中客户端发送给服务端的签名请求
+///
+/// SignRequest ::= SEQUENCE { point OCTET STRING, digest OCTET STRING }
+///
+/// point为Q1 = \[k1]G，digest为e = SM3(ZA ∥ M)，服务端不接触消息原文。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignRequest {
+    point: (BigUint, BigUint),
+    digest: [u8; 32],
+}
+
+impl SignRequest {
+    pub fn to_der(&self) -> Vec<u8> {
+        yasna::construct_der(|writer| {
+            writer.write_sequence(|writer| {
+                writer.next().write_bytes(&encode_point(&self.point.0, &self.point.1));
+                writer.next().write_bytes(&self.digest);
+            })
+        })
+    }
+
+    pub fn from_der(der: &[u8]) -> Result<SignRequest> {
+        let (point, digest) = yasna::parse_der(der, |reader| {
+            reader.read_sequence(|reader| {
+                let point = reader.next().read_bytes()?;
+                let digest = reader.next().read_bytes()?;
+                Ok((point, digest))
+            })
+        }).map_err(|_| Error::InvalidDer("The SignRequest is not a SEQUENCE of two OCTET STRINGs."))?;
+        let digest = <[u8; 32]>::try_from(digest.as_slice()).map_err(|_| Error::InvalidDigest)?;
+        Ok(SignRequest { point: decode_point(&point)?, digest })
+    }
+}
+
+/// 两方协同签名中服务端返回给客户端的部分签名
+///
+/// SignResponse ::= SEQUENCE { r INTEGER, s2 INTEGER, s3 INTEGER }
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignResponse {
+    r: BigUint,
+    s2: BigUint,
+    s3: BigUint,
+}
+
+impl SignResponse {
+    pub fn to_der(&self) -> Vec<u8> {
+        yasna::construct_der(|writer| {
+            writer.write_sequence(|writer| {
+                writer.next().write_biguint(&self.r);
+                writer.next().write_biguint(&self.s2);
+                writer.next().write_biguint(&self.s3);
+            })
+        })
+    }
+
+    pub fn from_der(der: &[u8]) -> Result<SignResponse> {
+        yasna::parse_der(der, |reader| {
+            reader.read_sequence(|reader| {
+                let r = reader.next().read_biguint()?;
+                let s2 = reader.next().read_biguint()?;
+                let s3 = reader.next().read_biguint()?;
+                Ok(SignResponse { r, s2, s3 })
+            })
+        }).map_err(|_: ASN1Error| Error::InvalidDer("The SignResponse is not a SEQUENCE of three INTEGERs."))
+    }
+}
+
+/// 两方协同解密中客户端发送给服务端的部分点
+///
+/// DecryptRequest ::= SEQUENCE { point OCTET STRING }  -- T1 = \[d1⁻¹]C1
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecryptRequest {
+    point: (BigUint, BigUint),
+}
+
+impl DecryptRequest {
+    pub fn to_der(&self) -> Vec<u8> {
+        write_point(&self.point)
+    }
+
+    pub fn from_der(der: &[u8]) -> Result<DecryptRequest> {
+        Ok(DecryptRequest { point: read_point(der, "The DecryptRequest is not a SEQUENCE of an OCTET STRING.")? })
+    }
+}
+
+/// 两方协同解密中服务端返回给客户端的部分点
+///
+/// DecryptResponse ::= SEQUENCE { point OCTET STRING }  -- T2 = \[d2⁻¹]T1
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecryptResponse {
+    point: (BigUint, BigUint),
+}
+
+impl DecryptResponse {
+    pub fn to_der(&self) -> Vec<u8> {
+        write_point(&self.point)
+    }
+
+    pub fn from_der(der: &[u8]) -> Result<DecryptResponse> {
+        Ok(DecryptResponse { point: read_point(der, "The DecryptResponse is not a SEQUENCE of an OCTET STRING.")? })
+    }
+}
+
+/// 两方协同签名与解密（SM2）的客户端
+///
+/// 私钥d被拆分为客户端的d1与服务端的d2，满足 d = (d1·d2)⁻¹ - 1，任何一方都无法单独签名或解密。签名：
+///
+/// 1. 客户端生成d1，发送P1 = \[d1⁻¹]G；服务端生成d2，计算公钥 P = \[d2⁻¹]P1 - G
+/// 2. 客户端生成k1，发送Q1 = \[k1]G与e = SM3(ZA ∥ M)
+/// 3. 服务端生成k2、k3，计算 (x1, y1) = \[k3]Q1 + \[k2]G，r = (e + x1) mod n，
+///    s2 = d2·k3 mod n，s3 = d2·(r + k2) mod n，返回(r, s2, s3)
+/// 4. 客户端计算 s = (d1·k1)·s2 + d1·s3 - r mod n
+///
+/// 此时 s = (1 + d)⁻¹·(k + r) - r，其中k = k1·k3 + k2，与标准的SM2签名一致，可由普通的验签者验证。
+/// 客户端在返回签名前会以公钥验签，防止服务端返回错误的部分签名。
+///
+/// 解密：客户端发送T1 = \[d1⁻¹]C1，服务端返回T2 = \[d2⁻¹]T1，客户端计算 (x2, y2) = T2 - C1 = \[d]C1，
+/// 之后与普通的解密相同，由KDF(x2 ∥ y2, klen)恢复明文并校验C3。密文与普通加密的密文相同，不需要特殊处理。
+pub struct CollaborativeClient {
+    /// 秘钥分量d1 ∈ \[1, n-1]
+    d1: BigUint,
+    public_key: Option<PublicKey>,
+    /// 当前签名请求的随机数k1与杂凑值e，只能使用一次
+    pending: Option<(BigUint, [u8; 32])>,
+    /// 当前解密请求的密文
+    ciphertext: Option<Ciphertext>,
+    builder: Rc<dyn EllipticBuilder>,
+}
+
+impl CollaborativeClient {
+    /// 随机生成秘钥分量
+    pub fn new() -> Self {
+        let builder: Rc<dyn EllipticBuilder> = Rc::new(P256Elliptic::init());
+        CollaborativeClient { d1: random(builder.as_ref()), public_key: None, pending: None, ciphertext: None, builder }
+    }
+
+    /// 由保存的秘钥分量（十六进制编码）与协同生成的公钥恢复客户端
+    pub fn from_share(share: &str, public_key: &str) -> Result<Self> {
+        let builder: Rc<dyn EllipticBuilder> = Rc::new(P256Elliptic::init());
+        let d1 = decode_share(share, builder.as_ref())?;
+        let public_key = Some(PublicKey::decode(public_key)?);
+        Ok(CollaborativeClient { d1, public_key, pending: None, ciphertext: None, builder })
+    }
+
+    /// 十六进制编码的秘钥分量d1，须妥善保存
+    pub fn share(&self) -> String {
+        hex::encode(to_32_bytes(self.d1.to_bytes_be()))
+    }
+
+    /// 发送给服务端的公钥分量P1 = \[d1⁻¹]G
+    pub fn key_share(&self) -> KeyShare {
+        let n = &self.builder.blueprint().n;
+        KeyShare { point: self.builder.scalar_base_multiply(inverse(&self.d1, n)) }
+    }
+
+    /// 设置服务端返回的公钥
+    pub fn with_public_key(mut self, public_key: &str) -> Result<Self> {
+        self.public_key = Some(PublicKey::decode(public_key)?);
+        Ok(self)
+    }
+
+    /// 协同生成的公钥，非压缩格式
+    pub fn public_key(&self) -> Option<String> {
+        self.public_key.as_ref().map(|e| e.encode())
+    }
+
+    /// 使用默认用户身份标识生成签名请求
+    pub fn sign_request(&mut self, plain: &[u8]) -> Result<SignRequest> {
+        self.sign_request_with_id(&UID, plain)
+    }
+
+    /// 使用指定的用户身份标识（IDA）生成签名请求，id长度不能超过8191字节，验签时须使用相同的id
+    pub fn sign_request_with_id(&mut self, id: &[u8], plain: &[u8]) -> Result<SignRequest> {
+        let public_key = self.public_key.clone()
+            .ok_or(Error::Collaborative("The public key has not been set."))?;
+        let za = Crypto::default().digest(id, public_key)?;
+        let digest = sm3::hash(&[za, plain.to_vec()].concat());
+
+        let k1 = random(self.builder.as_ref());
+        let point = self.builder.scalar_base_multiply(k1.clone());
+        self.pending = Some((k1, digest));
+        Ok(SignRequest { point, digest })
+    }
+
+    /// 由服务端的部分签名计算完整签名，返回DER编码的签名
+    pub fn sign(&mut self, response: &SignResponse) -> Result<Vec<u8>> {
+        let (k1, digest) = self.pending.take()
+            .ok_or(Error::Collaborative("There is no pending sign request."))?;
+        let public_key = self.public_key.clone()
+            .ok_or(Error::Collaborative("The public key has not been set."))?;
+        let n = self.builder.blueprint().n.clone();
+
+        let SignResponse { r, s2, s3 } = response;
+        if r.is_zero() || r >= &n || s2 >= &n || s3 >= &n {
+            return Err(Error::Collaborative("The partial signature is out of range."));
+        }
+
+        // s = (d1·k1)·s2 + d1·s3 - r mod n
+        let s = {
+            let n = n.to_bigint().unwrap();
+            let d1 = self.d1.to_bigint().unwrap();
+            let a = d1.clone().mul(k1.to_bigint().unwrap()).mul(s2.to_bigint().unwrap());
+            let b = d1.mul(s3.to_bigint().unwrap());
+            a.add(b).sub(r.to_bigint().unwrap()).mod_floor(&n).to_biguint().unwrap()
+        };
+        if s.is_zero() || s.clone().add(r) == n {
+            return Err(Error::Collaborative("The signature is degenerate, a new request is required."));
+        }
+
+        let signature = Signature::new(r.clone(), s);
+        if !Crypto::default().verifier(public_key).verify_digest(&digest, &signature) {
+            return Err(Error::Collaborative("The signature does not verify with the public key."));
+        }
+        Ok(signature.encode())
+    }
+
+    /// 生成解密请求，密文为C1C3C2格式或GM/T 0009定义的DER编码
+    pub fn decrypt_request(&mut self, cipher: &[u8]) -> Result<DecryptRequest> {
+        let mode = if cipher.first() == Some(&0x30) { Mode::Der } else { Mode::C1C3C2 };
+        let ciphertext = Ciphertext::decode(cipher, mode)?;
+
+        let (x1, y1) = ciphertext.point();
+        if !self.builder.blueprint().contains(&x1, &y1) {
+            return Err(Error::InvalidCipher("C1 is not a point on the curve."));
+        }
+        let n = &self.builder.blueprint().n;
+        let point = self.builder.scalar_multiply(x1, y1, inverse(&self.d1, n));
+        self.ciphertext = Some(ciphertext);
+        Ok(DecryptRequest { point })
+    }
+
+    /// 由服务端返回的部分点完成解密
+    pub fn decrypt(&mut self, response: &DecryptResponse) -> Result<Vec<u8>> {
+        let ciphertext = self.ciphertext.take()
+            .ok_or(Error::Collaborative("There is no pending decrypt request."))?;
+        let elliptic = self.builder.blueprint();
+
+        // [d]C1 = T2 - C1
+        let (x1, y1) = ciphertext.point();
+        let (tx, ty) = response.point.clone();
+        let (x2, y2) = self.builder.point_add(tx, ty, x1, elliptic.p.clone().sub(y1));
+        if !elliptic.contains(&x2, &y2) {
+            return Err(Error::Collaborative("The partial point of the server is invalid."));
+        }
+        ciphertext.recover(&x2, &y2)
+    }
+}
+
+impl Default for CollaborativeClient {
+    fn default() -> Self {
+        CollaborativeClient::new()
+    }
+}
+
+/// 两方协同签名与解密（SM2）的服务端，协议参见[CollaborativeClient]
+pub struct CollaborativeServer {
+    /// 秘钥分量d2 ∈ \[1, n-1]
+    d2: BigUint,
+    public_key: PublicKey,
+    builder: Rc<dyn EllipticBuilder>,
+}
+
+impl CollaborativeServer {
+    /// 随机生成秘钥分量，由客户端的公钥分量计算公钥 P = \[d2⁻¹]P1 - G
+    pub fn new(key_share: &KeyShare) -> Result<Self> {
+        let builder: Rc<dyn EllipticBuilder> = Rc::new(P256Elliptic::init());
+        loop {
+            let d2 = random(builder.as_ref());
+            // P为无穷远点（d1·d2 = 1）时不满足曲线方程，更换d2重新计算
+            if let Ok(public_key) = public_key(builder.as_ref(), &key_share.point, &d2) {
+                break Ok(CollaborativeServer { d2, public_key, builder });
+            }
+        }
+    }
+
+    /// 由保存的秘钥分量（十六进制编码）与公钥恢复服务端
+    pub fn from_share(share: &str, public_key: &str) -> Result<Self> {
+        let builder: Rc<dyn EllipticBuilder> = Rc::new(P256Elliptic::init());
+        let d2 = decode_share(share, builder.as_ref())?;
+        let public_key = PublicKey::decode(public_key)?;
+        Ok(CollaborativeServer { d2, public_key, builder })
+    }
+
+    /// 十六进制编码的秘钥分量d2，须妥善保存
+    pub fn share(&self) -> String {
+        hex::encode(to_32_bytes(self.d2.to_bytes_be()))
+    }
+
+    /// 协同生成的公钥，非压缩格式，发送给客户端
+    pub fn public_key(&self) -> String {
+        self.public_key.encode()
+    }
+
+    /// 计算部分签名，Q1须是曲线上的点
+    pub fn respond(&self, request: &SignRequest) -> Result<SignResponse> {
+        let elliptic = self.builder.blueprint();
+        let n = &elliptic.n;
+        let e = BigUint::from_bytes_be(&request.digest);
+        let (qx, qy) = request.point.clone();
+
+        loop {
+            let (k2, k3) = (random(self.builder.as_ref()), random(self.builder.as_ref()));
+
+            // (x1, y1) = [k3]Q1 + [k2]G
+            let (x1, y1) = {
+                let (ax, ay) = self.builder.scalar_multiply(qx.clone(), qy.clone(), k3.clone());
+                let (bx, by) = self.builder.scalar_base_multiply(k2.clone());
+                self.builder.point_add(ax, ay, bx, by)
+            };
+            // 两点相同或互为相反数时点加的结果无效，更换随机数重新计算
+            if !elliptic.contains(&x1, &y1) {
+                continue;
+            }
+
+            let r = e.clone().add(x1).mod_floor(n);
+            if r.is_zero() {
+                continue;
+            }
+            let s2 = self.d2.clone().mul(k3).mod_floor(n);
+            let s3 = self.d2.clone().mul(r.clone().add(k2)).mod_floor(n);
+            break Ok(SignResponse { r, s2, s3 });
+        }
+    }
+
+    /// 计算解密的部分点T2 = \[d2⁻¹]T1，T1已在解码时校验为曲线上的点
+    pub fn respond_decrypt(&self, request: &DecryptRequest) -> Result<DecryptResponse> {
+        let n = &self.builder.blueprint().n;
+        let (x, y) = request.point.clone();
+        Ok(DecryptResponse { point: self.builder.scalar_multiply(x, y, inverse(&self.d2, n)) })
+    }
+}
+
+/// P = [d2⁻¹]P1 - G
+fn public_key(builder: &dyn EllipticBuilder, p1: &(BigUint, BigUint), d2: &BigUint) -> Result<PublicKey> {
+    let elliptic = builder.blueprint();
+    let (x, y) = builder.scalar_multiply(p1.0.clone(), p1.1.clone(), inverse(d2, &elliptic.n));
+    let (x, y) = builder.point_add(x, y, elliptic.gx.clone(), elliptic.p.clone().sub(&elliptic.gy));
+    PublicKey::decode(&hex::encode(encode_point(&x, &y)))
+}
+
+/// 随机数 ∈ \[1, n-1]
+fn random(builder: &dyn EllipticBuilder) -> BigUint {
+    let elliptic = builder.blueprint();
+    let from = BigUint::one();
+    elliptic.random(from.clone(), elliptic.n.clone().sub(&from))
+}
+
+/// 模n的乘法逆元，n为素数
+fn inverse(x: &BigUint, n: &BigUint) -> BigUint {
+    x.modpow(&n.clone().sub(BigUint::from(2u8)), n)
+}
+
+fn decode_share(share: &str, builder: &dyn EllipticBuilder) -> Result<BigUint> {
+    let bytes = hex::decode(share)?;
+    let d = BigUint::from_bytes_be(&bytes);
+    if bytes.len() != 32 || d.is_zero() || d >= builder.blueprint().n {
+        return Err(Error::InvalidPrivateKey("The key share must be 32 bytes in [1, n-1]."));
+    }
+    Ok(d)
+}
+
+/// SEQUENCE { point OCTET STRING }
+fn write_point(point: &(BigUint, BigUint)) -> Vec<u8> {
+    yasna::construct_der(|writer| {
+        writer.write_sequence(|writer| {
+            writer.next().write_bytes(&encode_point(&point.0, &point.1));
+        })
+    })
+}
+
+fn read_point(der: &[u8], reason: &'static str) -> Result<(BigUint, BigUint)> {
+    let point = yasna::parse_der(der, |reader| reader.read_sequence(|reader| reader.next().read_bytes()))
+        .map_err(|_| Error::InvalidDer(reason))?;
+    decode_point(&point)
+}
+
+/// 解码非压缩格式的点，须是曲线上的点
+fn decode_point(point: &[u8]) -> Result<(BigUint, BigUint)> {
+    Ok(PublicKey::decode(&hex::encode(point))?.value())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sm2;
+
+    fn setup() -> (CollaborativeClient, CollaborativeServer) {
+        let client = CollaborativeClient::new();
+        let key_share = KeyShare::from_der(&client.key_share().to_der()).unwrap();
+        let server = CollaborativeServer::new(&key_share).unwrap();
+        let client = client.with_public_key(&server.public_key()).unwrap();
+        (client, server)
+    }
+
+    #[test]
+    fn sign() {
+        let (mut client, server) = setup();
+        let plain = "为了艾泽拉斯！".as_bytes();
+
+        for _ in 0..5 {
+            let request = SignRequest::from_der(&client.sign_request(plain).unwrap().to_der()).unwrap();
+            let response = SignResponse::from_der(&server.respond(&request).unwrap().to_der()).unwrap();
+            let signature = client.sign(&response).unwrap();
+            assert!(sm2::try_verify_bytes(&server.public_key(), plain, &signature).unwrap());
+            assert!(!sm2::try_verify_bytes(&server.public_key(), &plain[1..], &signature).unwrap());
+        }
+
+        // d = (d1·d2)⁻¹ - 1
+        let n = &P256Elliptic::init().ec.n;
+        let d = inverse(&client.d1.clone().mul(&server.d2).mod_floor(n), n).add(n).sub(BigUint::one()).mod_floor(n);
+        let (x, y) = P256Elliptic::init().scalar_base_multiply(d);
+        assert_eq!(hex::encode(encode_point(&x, &y)), server.public_key());
+
+        // 指定用户身份标识
+        let id = b"ALICE123@YAHOO.COM";
+        let request = client.sign_request_with_id(id, plain).unwrap();
+        let signature = client.sign(&server.respond(&request).unwrap()).unwrap();
+        assert!(sm2::try_verify_bytes_with_id(&server.public_key(), id, plain, &signature).unwrap());
+        assert!(!sm2::try_verify_bytes(&server.public_key(), plain, &signature).unwrap());
+        assert_eq!(client.sign_request_with_id(&[0; 8192], plain).unwrap_err(), Error::InvalidUserId);
+    }
+
+    #[test]
+    fn restore() {
+        let (client, server) = setup();
+        let public_key = server.public_key();
+        let mut client = CollaborativeClient::from_share(&client.share(), &public_key).unwrap();
+        let server = CollaborativeServer::from_share(&server.share(), &public_key).unwrap();
+
+        let request = client.sign_request(b"data").unwrap();
+        let signature = client.sign(&server.respond(&request).unwrap()).unwrap();
+        assert!(sm2::try_verify_bytes(&public_key, b"data", &signature).unwrap());
+    }
+
+    #[test]
+    fn decrypt() {
+        let (mut client, server) = setup();
+        let public_key = client.public_key().unwrap();
+        let plain = "巫妖王的秘密".repeat(10);
+
+        for (cipher, expected) in [
+            (sm2::try_encrypt_bytes(&public_key, plain.as_bytes()).unwrap(), plain.as_bytes()),
+            (sm2::try_encrypt_der_bytes(&public_key, plain.as_bytes()).unwrap(), plain.as_bytes()),
+            (sm2::try_encrypt_bytes(&public_key, &[]).unwrap(), &[][..]),
+        ] {
+            let request = DecryptRequest::from_der(&client.decrypt_request(&cipher).unwrap().to_der()).unwrap();
+            let response = DecryptResponse::from_der(&server.respond_decrypt(&request).unwrap().to_der()).unwrap();
+            assert_eq!(client.decrypt(&response).unwrap(), expected);
+        }
+
+        // 其他服务端的部分点无法通过C3校验
+        let (_, other) = setup();
+        let cipher = sm2::try_encrypt_bytes(&public_key, plain.as_bytes()).unwrap();
+        let request = client.decrypt_request(&cipher).unwrap();
+        assert!(client.decrypt(&other.respond_decrypt(&request).unwrap()).is_err());
+        assert!(client.decrypt(&server.respond_decrypt(&request).unwrap()).is_err());
+
+        // C1 = (0, 0)不是曲线上的点
+        let mut tampered = cipher.clone();
+        tampered[1..65].fill(0);
+        assert!(client.decrypt_request(&tampered).is_err());
+    }
+
+    #[test]
+    fn invalid() {
+        let (mut client, server) = setup();
+        assert!(matches!(client.sign(&SignResponse { r: BigUint::one(), s2: BigUint::one(), s3: BigUint::one() }), Err(Error::Collaborative(_))));
+
+        // 其他服务端的部分签名无法通过验签
+        let (_, other) = setup();
+        let request = client.sign_request(b"data").unwrap();
+        assert!(client.sign(&other.respond(&request).unwrap()).is_err());
+
+        // 随机数k1只能使用一次
+        let request = client.sign_request(b"data").unwrap();
+        let response = server.respond(&request).unwrap();
+        assert!(client.sign(&response).is_ok());
+        assert!(client.sign(&response).is_err());
+
+        assert!(CollaborativeClient::new().sign_request(b"data").is_err());
+        assert!(SignRequest::from_der(&KeyShare::from_der(&client.key_share().to_der()).unwrap().to_der()).is_err());
+        let mut der = request.to_der();
+        der[10] ^= 0xff;
+        assert!(SignRequest::from_der(&der).is_err());
+        assert!(CollaborativeServer::from_share("00", &server.public_key()).is_err());
+    }
+}
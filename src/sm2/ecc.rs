@@ -7,11 +7,15 @@ use num_bigint::{BigInt, BigUint, ToBigInt};
 use num_integer::Integer;
 use num_traits::{One, Zero};
 
+use crate::error::{Error, Result};
+use crate::sm2::exchange::{KeyExchange, Role};
 use crate::sm2::key::{KeyPair, PrivateKey, PublicKey, to_32_bytes};
+use crate::sm2::nonce::{DeterministicNonce, Nonce};
 use crate::sm2::p256::P256Elliptic;
 use crate::sm3;
 
-const UID: [u8; 16] = [
+/// 默认用户身份标识 IDA = "1234567812345678"
+pub(crate) const UID: [u8; 16] = [
     0x31, 0x32, 0x33, 0x34, 0x35, 0x36, 0x37, 0x38, 0x31, 0x32, 0x33, 0x34, 0x35, 0x36, 0x37, 0x38,
 ];
 
@@ -64,6 +68,51 @@ impl Elliptic {
         k.mod_floor(&temp).add(&from)
     }
 
+    /// 判断(x, y)是否为曲线上的点：x, y ∈ \[0, p-1]，且 y^2 ≡ x^3 + ax + b (mod p)
+    ///
+    /// 由于b ≠ 0，无穷远点的仿射表示(0, 0)不满足曲线方程。
+    pub fn contains(&self, x: &BigUint, y: &BigUint) -> bool {
+        if x >= &self.p || y >= &self.p {
+            return false;
+        }
+
+        let left = y.modpow(&BigUint::from(2u8), &self.p);
+        let right = x.modpow(&BigUint::from(3u8), &self.p)
+            .add(self.a.clone().mul(x))
+            .add(&self.b)
+            .mod_floor(&self.p);
+
+        left == right
+    }
+
+    /// 由x坐标恢复y坐标，odd表示y是否为奇数；x不是曲线上的点的横坐标时返回None
+    ///
+    /// α = x^3 + ax + b mod p，y = √α mod p。
+    /// SM2推荐曲线的素数p ≡ 3 (mod 4)，因此 √α = α^((p+1)/4) mod p，求得后须验证 y^2 ≡ α。
+    pub fn recover_y(&self, x: &BigUint, odd: bool) -> Option<BigUint> {
+        if x >= &self.p || self.p.mod_floor(&BigUint::from(4u8)) != BigUint::from(3u8) {
+            return None;
+        }
+
+        let alpha = x.modpow(&BigUint::from(3u8), &self.p)
+            .add(self.a.clone().mul(x))
+            .add(&self.b)
+            .mod_floor(&self.p);
+
+        let exp = self.p.clone().add(BigUint::one()) >> 2;
+        let y = alpha.modpow(&exp, &self.p);
+
+        if y.modpow(&BigUint::from(2u8), &self.p) != alpha {
+            return None;
+        }
+
+        if y.is_odd() == odd {
+            Some(y)
+        } else {
+            Some(self.p.clone().sub(y).mod_floor(&self.p))
+        }
+    }
+
     pub fn scalar_reduce(&self, scalar: BigUint) -> BigUint {
         // compare scalar and order, n = (scalar mod order) if scalar > order else scalar
         if let Ordering::Greater = scalar.cmp(&self.n) {
@@ -74,14 +123,20 @@ impl Elliptic {
     }
 }
 
+/// 密文格式
 #[derive(Debug, Copy, Clone)]
-enum Mode {
+pub(crate) enum Mode {
+    /// 04 ∥ C1 ∥ C2 ∥ C3
     C1C2C3,
+    /// 04 ∥ C1 ∥ C3 ∥ C2
     C1C3C2,
+    /// GM/T 0009 SM2Cipher：SEQUENCE { x INTEGER, y INTEGER, hash OCTET STRING, ciphertext OCTET STRING }
+    Der,
 }
 
 pub struct Crypto {
     mode: Mode,
+    nonce: Nonce,
     builder: Rc<dyn EllipticBuilder>,
 }
 
@@ -91,11 +146,21 @@ impl Crypto {
     }
 
     pub fn c1c2c3(builder: Rc<dyn EllipticBuilder>) -> Self {
-        Crypto { mode: Mode::C1C2C3, builder }
+        Crypto { mode: Mode::C1C2C3, nonce: Nonce::Random, builder }
     }
 
     pub fn c1c3c2(builder: Rc<dyn EllipticBuilder>) -> Self {
-        Crypto { mode: Mode::C1C3C2, builder }
+        Crypto { mode: Mode::C1C3C2, nonce: Nonce::Random, builder }
+    }
+
+    /// 密文使用GM/T 0009定义的DER编码
+    pub fn der(builder: Rc<dyn EllipticBuilder>) -> Self {
+        Crypto { mode: Mode::Der, nonce: Nonce::Random, builder }
+    }
+
+    /// 指定签名随机数k的生成方式，默认为[Nonce::Random]
+    pub fn with_nonce(self, nonce: Nonce) -> Self {
+        Crypto { nonce, ..self }
     }
 
     pub fn encryptor(&self, key: PublicKey) -> Encryptor {
@@ -106,30 +171,71 @@ impl Crypto {
         Decryptor { key, mode: self.mode, builder: self.builder.clone() }
     }
 
+    /// 使用默认用户身份标识的签名者
     pub fn signer(&self, keypair: KeyPair) -> Signer {
-        let za = self.digest(keypair.puk().clone());
-        Signer { hash: za, keypair, builder: self.builder.clone() }
+        let za = self.za(&UID, keypair.puk());
+        Signer { hash: za, nonce: self.nonce, keypair, builder: self.builder.clone() }
+    }
+
+    /// 使用指定用户身份标识IDA的签名者
+    pub fn signer_with_id(&self, keypair: KeyPair, id: &[u8]) -> Result<Signer> {
+        let za = self.digest(id, keypair.puk().clone())?;
+        Ok(Signer { hash: za, nonce: self.nonce, keypair, builder: self.builder.clone() })
     }
 
+    /// 使用默认用户身份标识的验签者
     pub fn verifier(&self, key: PublicKey) -> Verifier {
-        let za = self.digest(key.clone());
+        let za = self.za(&UID, &key);
         Verifier { hash: za, key, builder: self.builder.clone() }
     }
 
+    /// 使用指定用户身份标识IDA的验签者
+    pub fn verifier_with_id(&self, key: PublicKey, id: &[u8]) -> Result<Verifier> {
+        let za = self.digest(id, key.clone())?;
+        Ok(Verifier { hash: za, key, builder: self.builder.clone() })
+    }
+
+    /// 密钥交换的发起方A，id为己方的用户身份标识，peer、peer_id为响应方B的公钥与用户身份标识
+    pub fn initiator(&self, keypair: KeyPair, id: &[u8], peer: PublicKey, peer_id: &[u8]) -> Result<KeyExchange> {
+        let za = self.digest(id, keypair.puk().clone())?;
+        let zb = self.digest(peer_id, peer.clone())?;
+        Ok(KeyExchange::new(Role::Initiator, keypair, peer, za, zb, self.ephemeral(), self.builder.clone()))
+    }
+
+    /// 密钥交换的响应方B，id为己方的用户身份标识，peer、peer_id为发起方A的公钥与用户身份标识
+    pub fn responder(&self, keypair: KeyPair, id: &[u8], peer: PublicKey, peer_id: &[u8]) -> Result<KeyExchange> {
+        let za = self.digest(peer_id, peer.clone())?;
+        let zb = self.digest(id, keypair.puk().clone())?;
+        Ok(KeyExchange::new(Role::Responder, keypair, peer, za, zb, self.ephemeral(), self.builder.clone()))
+    }
+
+    /// 密钥交换的临时秘钥 r ∈ \[1, n-1]
+    fn ephemeral(&self) -> BigUint {
+        let elliptic = self.builder.blueprint();
+        let from = BigUint::one();
+        elliptic.random(from.clone(), elliptic.n.clone().sub(&from))
+    }
+
     /// ZA=H256(ENTLA ∥ IDA ∥ a ∥ b ∥ xG ∥ yG ∥xA ∥yA)
-    fn digest(&self, puk: PublicKey) -> Vec<u8> {
+    ///
+    /// ENTLA为IDA的比特长度，占两个字节，因此IDA的长度不能超过8191字节。
+    pub(crate) fn digest(&self, id: &[u8], puk: PublicKey) -> Result<Vec<u8>> {
+        if id.len() >= 8192 {
+            return Err(Error::InvalidUserId);
+        }
+        Ok(self.za(id, &puk))
+    }
+
+    fn za(&self, id: &[u8], puk: &PublicKey) -> Vec<u8> {
         let ent = {
-            if UID.len() >= 8192 {
-                panic!("UID is too large.");
-            }
-            let r = UID.len() * 8;
+            let r = id.len() * 8;
             [((r >> 8) & 0xFF) as u8, (r & 0xFF) as u8].to_vec()
         };
 
-        let id = UID.to_vec();
+        let id = id.to_vec();
         let e = self.builder.blueprint();
-        let (a, b) = (e.a.to_bytes_be(), e.a.to_bytes_be());
-        let (gx, gy) = (e.gx.to_bytes_be(), e.gy.to_bytes_be());
+        let (a, b) = (to_32_bytes(e.a.to_bytes_be()).to_vec(), to_32_bytes(e.b.to_bytes_be()).to_vec());
+        let (gx, gy) = (to_32_bytes(e.gx.to_bytes_be()).to_vec(), to_32_bytes(e.gy.to_bytes_be()).to_vec());
 
         let (px, py) = {
             let key = puk.value();
@@ -142,11 +248,20 @@ impl Crypto {
 }
 
 pub trait Encryption {
-    fn execute(&self, plain: &str) -> String;
+    fn execute_bytes(&self, plain: &[u8]) -> Vec<u8>;
+
+    fn execute(&self, plain: &str) -> String {
+        hex::encode(self.execute_bytes(plain.as_bytes()))
+    }
 }
 
 pub trait Decryption {
-    fn execute(&self, cipher: &str) -> String;
+    fn execute_bytes(&self, cipher: &[u8]) -> Result<Vec<u8>>;
+
+    fn execute(&self, cipher: &str) -> Result<String> {
+        let plain = self.execute_bytes(&hex::decode(cipher)?)?;
+        Ok(String::from_utf8_lossy(plain.as_slice()).to_string())
+    }
 }
 
 pub struct Encryptor {
@@ -155,55 +270,51 @@ pub struct Encryptor {
     builder: Rc<dyn EllipticBuilder>,
 }
 
-impl Encryption for Encryptor {
-    /// 加密
-    fn execute(&self, plain: &str) -> String {
-        let data = plain.as_bytes();
-        let cipher = loop {
-            let k = {
-                let elliptic = self.builder.blueprint();
-                let from = BigUint::one();
-                elliptic.random(from.clone(), elliptic.n.clone().sub(&from.clone()))
-            };
+impl Encryptor {
+    /// 使用给定的随机数k ∈ \[1, n-1]加密，若KDF派生结果全为0则返回None，需更换k重新计算
+    pub(crate) fn encrypt_with(&self, data: &[u8], k: BigUint) -> Option<Vec<u8>> {
+        // C1: [k]G
+        let c1 = {
+            let (x1, y1) = self.builder.scalar_base_multiply(k.clone());
+            [to_32_bytes(x1.to_bytes_be()).to_vec(), to_32_bytes(y1.to_bytes_be()).to_vec()].concat()
+        };
 
-            // C1: [k]G
-            let c1 = {
-                let (x1, y1) = self.builder.scalar_base_multiply(k.clone());
-                [vec![0x04], x1.to_bytes_be(), y1.to_bytes_be()].concat()
-            };
+        let (x2, y2) = {
+            let key = self.key.value();
+            let (x, y) = self.builder.scalar_multiply(key.0, key.1, k);
+            (to_32_bytes(x.to_bytes_be()).to_vec(), to_32_bytes(y.to_bytes_be()).to_vec())
+        };
 
-            let (x2, y2) = {
-                let key = self.key.value();
-                let (x, y) = (key.0.clone(), key.1.clone());
-                self.builder.scalar_multiply(x, y, k.clone())
-            };
+        let t = kdf([x2.clone(), y2.clone()].concat(), data.len());
 
-            let temp = [x2.to_bytes_be(), y2.to_bytes_be()].concat();
-            let t = kdf(temp, data.len());
+        if !t.is_empty() && is_all_zero(t.clone()) {
+            return None;
+        }
 
-            if is_all_zero(t.clone()) {
-                continue;
-            }
+        // C2: M ^ KDF(x2 ‖ y2, len(M))
+        let c2: Vec<u8> = data.iter().zip(t.iter()).map(|(m, t)| m ^ t).collect();
 
-            // C2: M ^ KDF(x2 ‖ γ2, len(M))
-            let mut c2 = vec![];
-            for i in 0..data.len() {
-                c2.push(data[i] ^ t.clone()[i]);
-            }
+        // C3: hash(x2 ‖ M ‖ y2)
+        let c3 = sm3::hash([x2, data.to_vec(), y2].concat().as_slice()).to_vec();
 
-            // C3: hash(x2 ‖ M ‖ γ2)
-            let c3 = {
-                let data = [x2.to_bytes_be(), data.to_vec(), y2.to_bytes_be()].concat();
-                sm3::hash(data.as_slice()).to_vec()
-            };
+        Some(Ciphertext { c1, c3, c2 }.encode(self.mode))
+    }
+}
 
-            break match self.mode {
-                Mode::C1C3C2 => [c1, c3, c2].concat(),
-                Mode::C1C2C3 => [c1, c2, c3].concat()
+impl Encryption for Encryptor {
+    /// 加密
+    fn execute_bytes(&self, data: &[u8]) -> Vec<u8> {
+        loop {
+            let k = {
+                let elliptic = self.builder.blueprint();
+                let from = BigUint::one();
+                elliptic.random(from.clone(), elliptic.n.clone().sub(&from.clone()))
             };
-        };
 
-        hex::encode(cipher)
+            if let Some(cipher) = self.encrypt_with(data, k) {
+                break cipher;
+            }
+        }
     }
 }
 
@@ -215,73 +326,132 @@ pub struct Decryptor {
 
 impl Decryption for Decryptor {
     /// 解密
-    fn execute(&self, cipher: &str) -> String {
-        let data = {
-            if !cipher.starts_with("04") {
-                panic!("The cipher data is invalid.")
-            }
-            match hex::decode(cipher) {
-                Ok(data) => data[1..].to_vec(),
-                Err(_) => panic!("The cipher data must be composed of hex chars.")
-            }
-        };
-        let (c1, c2, c3) = {
-            let len = data.len();
-            match self.mode {
-                Mode::C1C3C2 => {
-                    (data.clone()[..64].to_vec(), data.clone()[96..].to_vec(), data.clone()[64..96].to_vec())
-                }
-                Mode::C1C2C3 => {
-                    (data.clone()[..64].to_vec(), data.clone()[64..len - 32].to_vec(), data.clone()[len - 32..].to_vec())
-                }
-            }
-        };
+    fn execute_bytes(&self, cipher: &[u8]) -> Result<Vec<u8>> {
+        let ciphertext = Ciphertext::decode(cipher, self.mode)?;
 
+        let (x1, y1) = ciphertext.point();
+        // C1须满足曲线方程，SM2推荐曲线的余因子h = 1，因此[h]C1 ≠ O
+        if !self.builder.blueprint().contains(&x1, &y1) {
+            return Err(Error::InvalidCipher("C1 is not a point on the curve."));
+        }
+        let (x2, y2) = self.builder.scalar_multiply(x1, y1, self.key.value());
 
-        let (x2, y2) = {
-            let (x1, y1) = (
-                BigUint::from_bytes_be(&c1.clone()[..32]),
-                BigUint::from_bytes_be(&c1.clone()[32..])
-            );
-            self.builder.scalar_multiply(x1, y1, self.key.value())
-        };
+        ciphertext.recover(&x2, &y2)
+    }
+}
 
 
+/// 密文的组成部分
+pub(crate) struct Ciphertext {
+    /// C1 = x1 ∥ y1，各32字节，不含04前缀
+    c1: Vec<u8>,
+    /// C3 = Hash(x2 ∥ M ∥ y2)
+    c3: Vec<u8>,
+    /// C2 = M ^ t
+    c2: Vec<u8>,
+}
+
+impl Ciphertext {
+    /// C1 = (x1, y1)
+    pub(crate) fn point(&self) -> (BigUint, BigUint) {
+        (BigUint::from_bytes_be(&self.c1[..32]), BigUint::from_bytes_be(&self.c1[32..]))
+    }
+
+    /// 由(x2, y2) = \[d]C1恢复明文：M = C2 ^ KDF(x2 ∥ y2, klen)，并校验C3 = Hash(x2 ∥ M ∥ y2)
+    pub(crate) fn recover(&self, x2: &BigUint, y2: &BigUint) -> Result<Vec<u8>> {
+        let (x2, y2) = (to_32_bytes(x2.to_bytes_be()).to_vec(), to_32_bytes(y2.to_bytes_be()).to_vec());
+
         let plain = {
-            let temp = [x2.to_bytes_be(), y2.to_bytes_be()].concat();
-            let t = kdf(temp, c2.len());
+            let temp = [x2.clone(), y2.clone()].concat();
+            let t = kdf(temp, self.c2.len());
 
-            if is_all_zero(t.clone()) {
-                panic!("The cipher data is invalid.")
+            if !t.is_empty() && is_all_zero(t.clone()) {
+                return Err(Error::InvalidCipher("The derived key is all zero."));
             }
 
-            let mut plain = vec![];
-            for i in 0..c2.len() {
-                plain.push(c2.clone()[i] ^ t.clone()[i]);
-            }
-            plain
+            self.c2.iter().zip(t.iter()).map(|(c, t)| c ^ t).collect::<Vec<u8>>()
         };
 
         let hash = {
-            let temp = [x2.to_bytes_be(), plain.clone(), y2.to_bytes_be()].concat();
+            let temp = [x2, plain.clone(), y2].concat();
             sm3::hash(&temp).to_vec()
         };
 
-        if hash != c3 {
-            panic!("The cipher data hash validation failed.");
+        if hash != self.c3 {
+            return Err(Error::InvalidCipher("The cipher data hash validation failed."));
         }
 
-        String::from_utf8_lossy(plain.as_slice()).to_string()
+        Ok(plain)
     }
-}
 
+    /// 按指定格式编码
+    pub(crate) fn encode(self, mode: Mode) -> Vec<u8> {
+        match mode {
+            Mode::C1C3C2 => [vec![0x04], self.c1, self.c3, self.c2].concat(),
+            Mode::C1C2C3 => [vec![0x04], self.c1, self.c2, self.c3].concat(),
+            Mode::Der => {
+                let (x, y) = (BigUint::from_bytes_be(&self.c1[..32]), BigUint::from_bytes_be(&self.c1[32..]));
+                yasna::construct_der(|writer| {
+                    writer.write_sequence(|writer| {
+                        writer.next().write_biguint(&x);
+                        writer.next().write_biguint(&y);
+                        writer.next().write_bytes(&self.c3);
+                        writer.next().write_bytes(&self.c2);
+                    })
+                })
+            }
+        }
+    }
+
+    /// 按指定格式解码，仅校验格式，不校验C1是否为曲线上的点
+    pub(crate) fn decode(cipher: &[u8], mode: Mode) -> Result<Self> {
+        match mode {
+            Mode::C1C3C2 | Mode::C1C2C3 => {
+                // 04 ∥ C1(64) ∥ C3(32) ∥ C2
+                if cipher.len() < 97 || cipher[0] != 0x04 {
+                    return Err(Error::InvalidCipher("The cipher data is too short or not starts with 04."));
+                }
+                let (c1, data) = (cipher[1..65].to_vec(), &cipher[65..]);
+                Ok(match mode {
+                    Mode::C1C3C2 => Ciphertext { c1, c3: data[..32].to_vec(), c2: data[32..].to_vec() },
+                    _ => {
+                        let len = data.len();
+                        Ciphertext { c1, c3: data[len - 32..].to_vec(), c2: data[..len - 32].to_vec() }
+                    }
+                })
+            }
+            Mode::Der => {
+                let (x, y, c3, c2) = yasna::parse_der(cipher, |reader| {
+                    reader.read_sequence(|reader| {
+                        let x = reader.next().read_biguint()?;
+                        let y = reader.next().read_biguint()?;
+                        let c3 = reader.next().read_bytes()?;
+                        let c2 = reader.next().read_bytes()?;
+                        Ok((x, y, c3, c2))
+                    })
+                }).map_err(|_| Error::InvalidCipher("The cipher data is not a DER-encoded SM2Cipher."))?;
+
+                if x.bits() > 256 || y.bits() > 256 {
+                    return Err(Error::InvalidCipher("The coordinates of C1 are out of range."));
+                }
+                if c3.len() != 32 {
+                    return Err(Error::InvalidCipher("The hash of the cipher data must be 32 bytes."));
+                }
+
+                let c1 = [to_32_bytes(x.to_bytes_be()).to_vec(), to_32_bytes(y.to_bytes_be()).to_vec()].concat();
+                Ok(Ciphertext { c1, c3, c2 })
+            }
+        }
+    }
+}
 
 /// 秘钥派生函数
 #[inline(always)]
-fn kdf(data: Vec<u8>, len: usize) -> Vec<u8> {
+pub(crate) fn kdf(data: Vec<u8>, len: usize) -> Vec<u8> {
     let mut counter: usize = 0x00000001;
     let mut result: Vec<u8> = vec![];
-    let k = data.len() + 31 / 32;
+    // 派生ceil(len / 32)个分组，最后一个分组按需截断
+    let k = len.div_ceil(32);
     for i in 0..k {
         let temp = [data.as_slice(), to_bytes(counter).as_slice()].concat();
         let hash = sm3::hash(&temp);
@@ -355,21 +525,44 @@ impl Signature {
     }
 
     /// Decodes the DER-encoded ASN.1 data to Signature.
-    pub(crate) fn decode(signature: &[u8]) -> Self {
+    pub(crate) fn decode(signature: &[u8]) -> Result<Self> {
         let (r, s) = yasna::parse_der(signature, |reader| {
             reader.read_sequence(|reader| {
                 let r = reader.next().read_biguint()?;
                 let s = reader.next().read_biguint()?;
                 Ok((r, s))
             })
-        }).unwrap();
+        }).map_err(|_| Error::InvalidSignature("The signature is not a DER-encoded SEQUENCE of two INTEGERs."))?;
+
+        Signature::checked(r, s)
+    }
 
-        Signature::new(r, s)
+    /// Encodes the signature to the fixed 64-byte r ∥ s form.
+    pub(crate) fn encode_raw(&self) -> Vec<u8> {
+        [to_32_bytes(self.r.to_bytes_be()).to_vec(), to_32_bytes(self.s.to_bytes_be()).to_vec()].concat()
+    }
+
+    /// Decodes the fixed 64-byte r ∥ s form to Signature.
+    pub(crate) fn decode_raw(signature: &[u8]) -> Result<Self> {
+        if signature.len() != 64 {
+            return Err(Error::InvalidSignature("The raw signature's length must be 64."));
+        }
+        Signature::checked(BigUint::from_bytes_be(&signature[..32]), BigUint::from_bytes_be(&signature[32..]))
+    }
+
+    /// r, s ∈ \[1, n-1]
+    fn checked(r: BigUint, s: BigUint) -> Result<Self> {
+        let n = P256Elliptic::init().ec.n;
+        if r.is_zero() || r >= n || s.is_zero() || s >= n {
+            return Err(Error::InvalidSignature("The r or s of the signature is out of range."));
+        }
+        Ok(Signature::new(r, s))
     }
 }
 
 pub struct Signer {
     hash: Vec<u8>,
+    nonce: Nonce,
     keypair: KeyPair,
     builder: Rc<dyn EllipticBuilder>,
 }
@@ -377,47 +570,72 @@ pub struct Signer {
 impl Signer {
     /// 签名
     pub(crate) fn sign(&self, plain: &str) -> Signature {
-        let m = [self.hash.clone(), plain.as_bytes().to_vec()].concat();
-        let e = sm3::hash(m.as_slice());
-        let elliptic = self.builder.blueprint();
+        self.sign_bytes(plain.as_bytes())
+    }
 
-        let key = self.keypair.prk();
+    /// 对任意字节串签名
+    pub(crate) fn sign_bytes(&self, plain: &[u8]) -> Signature {
+        let m = [self.hash.clone(), plain.to_vec()].concat();
+        self.sign_digest(&sm3::hash(m.as_slice()))
+    }
 
-        let (r, s) = loop {
-            let k = {
-                let from = BigUint::one();
-                elliptic.random(from.clone(), elliptic.n.clone().sub(&from.clone()))
-            };
+    /// 对预先计算的杂凑值 e = SM3(ZA ∥ M) 签名
+    pub(crate) fn sign_digest(&self, e: &[u8; 32]) -> Signature {
+        let elliptic = self.builder.blueprint();
 
-            let r = {
-                let (x, _) = self.builder.scalar_base_multiply(k.clone());
-                BigUint::from_bytes_be(&e).add(&x).mod_floor(&elliptic.n)
-            };
+        match self.nonce {
+            Nonce::Random => loop {
+                let k = {
+                    let from = BigUint::one();
+                    elliptic.random(from.clone(), elliptic.n.clone().sub(&from.clone()))
+                };
 
-            if r == BigUint::zero() || r.clone().add(k.clone()) == elliptic.n {
-                continue;
+                if let Some(signature) = self.sign_with(e, k) {
+                    break signature;
+                }
+            },
+            Nonce::Deterministic => {
+                let mut generator = DeterministicNonce::new(&self.keypair.prk().value(), e, &elliptic.n);
+                loop {
+                    if let Some(signature) = self.sign_with(e, generator.next()) {
+                        break signature;
+                    }
+                }
             }
+        }
+    }
 
-            let s = {
-                let n = elliptic.n.to_bigint().unwrap();
-                let d = key.value().to_bigint().unwrap();
-                let temp = d.clone().mul(&r.to_bigint().unwrap());
-                // a = k - rd
-                let a = k.to_bigint().unwrap().sub(&temp);
-                let temp = d.clone().add(BigInt::one());
-                // 1 / (1+d)
-                let b = temp.extended_gcd(&n).x.mod_floor(&n);
-                a.mul(b).mod_floor(&n).to_biguint().unwrap()
-            };
+    /// 使用给定的随机数k ∈ \[1, n-1]对杂凑值e签名，若r = 0、r + k = n或s = 0则返回None，需更换k重新计算
+    pub(crate) fn sign_with(&self, e: &[u8], k: BigUint) -> Option<Signature> {
+        let elliptic = self.builder.blueprint();
+        let key = self.keypair.prk();
 
-            if s == BigUint::zero() {
-                continue;
-            }
+        let r = {
+            let (x, _) = self.builder.scalar_base_multiply(k.clone());
+            BigUint::from_bytes_be(e).add(&x).mod_floor(&elliptic.n)
+        };
+
+        if r == BigUint::zero() || r.clone().add(k.clone()) == elliptic.n {
+            return None;
+        }
 
-            break (r, s);
+        let s = {
+            let n = elliptic.n.to_bigint().unwrap();
+            let d = key.value().to_bigint().unwrap();
+            let temp = d.clone().mul(&r.to_bigint().unwrap());
+            // a = k - rd
+            let a = k.to_bigint().unwrap().sub(&temp);
+            let temp = d.clone().add(BigInt::one());
+            // 1 / (1+d)
+            let b = temp.extended_gcd(&n).x.mod_floor(&n);
+            a.mul(b).mod_floor(&n).to_biguint().unwrap()
         };
 
-        Signature::new(r, s)
+        if s == BigUint::zero() {
+            return None;
+        }
+
+        Some(Signature::new(r, s))
     }
 }
 
@@ -431,6 +649,17 @@ pub struct Verifier {
 impl Verifier {
     /// 验签
     pub(crate) fn verify(&self, plain: &str, signature: &Signature) -> bool {
+        self.verify_bytes(plain.as_bytes(), signature)
+    }
+
+    /// 对任意字节串验签
+    pub(crate) fn verify_bytes(&self, plain: &[u8], signature: &Signature) -> bool {
+        let m = [self.hash.clone(), plain.to_vec()].concat();
+        self.verify_digest(&sm3::hash(m.as_slice()), signature)
+    }
+
+    /// 使用预先计算的杂凑值 e = SM3(ZA ∥ M) 验签
+    pub(crate) fn verify_digest(&self, e: &[u8; 32], signature: &Signature) -> bool {
         let elliptic = self.builder.blueprint();
         let n1 = elliptic.n.clone().sub(BigUint::one());
         let (r, s) = (signature.r.clone(), signature.s.clone());
@@ -443,11 +672,7 @@ impl Verifier {
             return false;
         }
 
-        let e = {
-            let m = [self.hash.clone(), plain.as_bytes().to_vec()].concat();
-            let h = sm3::hash(m.as_slice());
-            BigUint::from_bytes_be(h.as_slice())
-        };
+        let e = BigUint::from_bytes_be(e);
 
         let t = r.clone().add(&s).mod_floor(&elliptic.n);
 
@@ -482,4 +707,95 @@ mod tests {
     fn demo() {
         println!("BigUint::one() = {:?}", BigUint::one());
     }
+
+    #[test]
+    fn invalid_cipher() {
+        use crate::sm2::key::HexKey;
+
+        let prk = "6aea1ccf610488aaa7fddba3dd6d76d3bdfd50f957d847be3d453defb695f28e";
+        let puk = "04a8af64e38eea41c254df769b5b41fbaa2d77b226b301a2636d463c52b46c777230ad1714e686dd641b9e04596530b38f6a64215b0ed3b081f8641724c5443a6e";
+
+        let crypto = Crypto::default();
+        let decryptor = crypto.decryptor(PrivateKey::decode(prk).unwrap());
+        let cipher = crypto.encryptor(PublicKey::decode(puk).unwrap()).execute_bytes(b"invalid curve attack");
+
+        // 将C1替换为不在曲线上的点
+        let mut data = cipher.clone();
+        data[64] ^= 0x01;
+        assert_eq!(decryptor.execute_bytes(&data).unwrap_err(), Error::InvalidCipher("C1 is not a point on the curve."));
+
+        // 篡改C2
+        let mut data = cipher.clone();
+        data[100] ^= 0x01;
+        assert_eq!(decryptor.execute_bytes(&data).unwrap_err(), Error::InvalidCipher("The cipher data hash validation failed."));
+
+        assert!(decryptor.execute_bytes(&cipher[..96]).is_err());
+        assert!(decryptor.execute_bytes(&[]).is_err());
+        assert_eq!(decryptor.execute("04zz").unwrap_err(), Error::InvalidHex);
+        assert_eq!(decryptor.execute_bytes(&cipher).unwrap(), b"invalid curve attack");
+    }
+
+    #[test]
+    fn signature() {
+        let n = P256Elliptic::init().ec.n;
+        let raw = [vec![0x00; 31], vec![0x01], vec![0xff; 32]].concat();
+
+        // s = 2^256 - 1 > n
+        assert_eq!(Signature::decode_raw(&raw).unwrap_err(), Error::InvalidSignature("The r or s of the signature is out of range."));
+        assert!(Signature::decode_raw(&raw[1..]).is_err());
+
+        let s = Signature::new(BigUint::one(), n.clone().sub(BigUint::one()));
+        let raw = s.encode_raw();
+        assert_eq!(raw.len(), 64);
+        assert_eq!(Signature::decode_raw(&raw).unwrap().encode(), s.encode());
+        assert_eq!(Signature::decode(&s.encode()).unwrap().encode_raw(), raw);
+
+        let invalid = [
+            // r = 0
+            vec![0x30, 0x06, 0x02, 0x01, 0x00, 0x02, 0x01, 0x02],
+            // 非最小编码的整数
+            vec![0x30, 0x07, 0x02, 0x02, 0x00, 0x01, 0x02, 0x01, 0x02],
+            // 负数
+            vec![0x30, 0x06, 0x02, 0x01, 0x81, 0x02, 0x01, 0x02],
+            // 尾随数据
+            vec![0x30, 0x06, 0x02, 0x01, 0x01, 0x02, 0x01, 0x02, 0x00],
+            // 非最短长度编码
+            vec![0x30, 0x81, 0x06, 0x02, 0x01, 0x01, 0x02, 0x01, 0x02],
+            // 多余的元素
+            vec![0x30, 0x09, 0x02, 0x01, 0x01, 0x02, 0x01, 0x02, 0x02, 0x01, 0x03],
+        ];
+        for data in invalid.iter() {
+            assert!(Signature::decode(data).is_err());
+        }
+        assert!(Signature::decode(&[0x30, 0x06, 0x02, 0x01, 0x01, 0x02, 0x01, 0x02]).is_ok());
+    }
+
+    #[test]
+    fn der() {
+        use crate::sm2::key::HexKey;
+
+        let prk = "6aea1ccf610488aaa7fddba3dd6d76d3bdfd50f957d847be3d453defb695f28e";
+        let crypto = Crypto::der(Rc::new(P256Elliptic::init()));
+        let decryptor = crypto.decryptor(PrivateKey::decode(prk).unwrap());
+
+        // 由OpenSSL 3加密得到，y坐标最高位为1，编码时需要前导0x00
+        let cipher = "307c02204ddc01b28609bdd8138c142062a44980d0612fc4b10a139e2ac5e86a8920ca25022100e50a09f1c2f4ef\
+        928c74b6419a94ca4cacdbe0b1f39a55a1204d31b38f1d40f10420757474cf8d547691ecddf95558f2ee03da3cedb0d1cf497daa9974f0bc\
+        661d4a0413a0245cd759ff6cbeaf33e7e6267f94c3784255";
+        assert_eq!(decryptor.execute(cipher).unwrap(), "encryption standard");
+
+        let data = hex::decode(cipher).unwrap();
+        let raw = Ciphertext::decode(&data, Mode::Der).unwrap().encode(Mode::C1C3C2);
+        assert_eq!(raw.len(), 65 + 32 + 19);
+        assert_eq!(Ciphertext::decode(&raw, Mode::C1C3C2).unwrap().encode(Mode::Der), data);
+
+        // 尾随数据、非最小编码的整数、杂凑值长度错误
+        assert!(decryptor.execute_bytes(&[data.clone(), vec![0x00]].concat()).is_err());
+        let padded = [vec![0x30, 0x7d, 0x02, 0x21, 0x00], data[4..].to_vec()].concat();
+        assert!(Ciphertext::decode(&padded, Mode::Der).is_err());
+        assert_eq!(
+            Ciphertext::decode(&data[..data.len() - 1], Mode::Der).err(),
+            Some(Error::InvalidCipher("The cipher data is not a DER-encoded SM2Cipher."))
+        );
+    }
 }
\ No newline at end of file
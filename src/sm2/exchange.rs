@@ -0,0 +1,234 @@
+use std::ops::{Add, Mul, Sub};
+use std::rc::Rc;
+
+use num_bigint::BigUint;
+use num_integer::Integer;
+use num_traits::One;
+
+use crate::ct_eq;
+use crate::error::{Error, Result};
+use crate::sm2::ecc::{EllipticBuilder, kdf};
+use crate::sm2::key::{encode_point, HexKey, KeyPair, PublicKey, to_32_bytes};
+use crate::sm3;
+
+/// 密钥交换中的角色
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Role {
+    /// 发起方A
+    Initiator,
+    /// 响应方B
+    Responder,
+}
+
+/// 协商完成后保存的中间结果，用于计算与校验确认杂凑值
+struct Shared {
+    /// 共享点U（V）的y坐标
+    y: Vec<u8>,
+    /// Hash(xU ∥ ZA ∥ ZB ∥ x1 ∥ y1 ∥ x2 ∥ y2)
+    hash: Vec<u8>,
+}
+
+/// SM2密钥交换协议（GB/T 32918.3）
+///
+/// 双方各自生成临时秘钥r与临时公钥R = \[r]G，交换R后计算共享点
+/// U = \[t](P' + \[x̄']R')，其中 t = (d + x̄·r) mod n，P'、R'为对方的公钥与临时公钥，
+/// 再由 KDF(xU ∥ yU ∥ ZA ∥ ZB, klen) 派生共享秘钥。
+///
+/// 可选的确认步骤：响应方发送SB = Hash(0x02 ∥ yV ∥ Hash(...))，发起方发送SA = Hash(0x03 ∥ yU ∥ Hash(...))，
+/// 双方校验对方的确认杂凑值，以确认对方持有相同的共享秘钥。
+///
+/// 临时秘钥只能使用一次，每次协商都须创建新的实例。
+pub struct KeyExchange {
+    role: Role,
+    keypair: KeyPair,
+    peer: PublicKey,
+    za: Vec<u8>,
+    zb: Vec<u8>,
+    /// 临时秘钥r ∈ \[1, n-1]
+    r: BigUint,
+    /// 临时公钥R = \[r]G
+    point: (BigUint, BigUint),
+    shared: Option<Shared>,
+    builder: Rc<dyn EllipticBuilder>,
+}
+
+impl KeyExchange {
+    /// za、zb分别为发起方与响应方的杂凑值，与自身的角色无关
+    pub(crate) fn new(
+        role: Role, keypair: KeyPair, peer: PublicKey, za: Vec<u8>, zb: Vec<u8>, r: BigUint, builder: Rc<dyn EllipticBuilder>,
+    ) -> Self {
+        let point = builder.scalar_base_multiply(r.clone());
+        KeyExchange { role, keypair, peer, za, zb, r, point, shared: None, builder }
+    }
+
+    /// 己方的临时公钥R，非压缩格式，发送给对方
+    pub fn point(&self) -> String {
+        hex::encode(encode_point(&self.point.0, &self.point.1))
+    }
+
+    /// 使用对方的临时公钥计算共享秘钥，klen为秘钥的字节长度，返回十六进制编码的共享秘钥
+    ///
+    /// 对方的临时公钥须是曲线上阶为n的点，否则协商失败。
+    pub fn exchange(&mut self, point: &str, klen: usize) -> Result<String> {
+        if self.shared.is_some() {
+            return Err(Error::KeyExchange("The key exchange has already been completed."));
+        }
+
+        let peer_point = PublicKey::decode(point)?.value();
+        let elliptic = self.builder.blueprint();
+
+        // t = (d + x̄·r) mod n
+        let t = {
+            let d = self.keypair.prk().value();
+            d.add(self.x_bar(&self.point.0).mul(&self.r)).mod_floor(&elliptic.n)
+        };
+
+        // U = [h·t](P' + [x̄']R')，推荐曲线的余因子h = 1
+        let (x, y) = {
+            let (px, py) = self.peer.value();
+            let (rx, ry) = self.builder.scalar_multiply(peer_point.0.clone(), peer_point.1.clone(), self.x_bar(&peer_point.0));
+            let (x, y) = self.builder.point_add(px, py, rx, ry);
+            self.builder.scalar_multiply(x, y, t)
+        };
+
+        // 无穷远点的仿射表示不满足曲线方程
+        if !elliptic.contains(&x, &y) {
+            return Err(Error::KeyExchange("The shared point is the point at infinity."));
+        }
+
+        let (x, y) = (to_32_bytes(x.to_bytes_be()).to_vec(), to_32_bytes(y.to_bytes_be()).to_vec());
+        let key = kdf([x.clone(), y.clone(), self.za.clone(), self.zb.clone()].concat(), klen);
+
+        // (x1, y1)为发起方的临时公钥RA，(x2, y2)为响应方的临时公钥RB
+        let (ra, rb) = match self.role {
+            Role::Initiator => (self.point.clone(), peer_point),
+            Role::Responder => (peer_point, self.point.clone()),
+        };
+        let hash = sm3::hash([
+            x,
+            self.za.clone(),
+            self.zb.clone(),
+            to_32_bytes(ra.0.to_bytes_be()).to_vec(),
+            to_32_bytes(ra.1.to_bytes_be()).to_vec(),
+            to_32_bytes(rb.0.to_bytes_be()).to_vec(),
+            to_32_bytes(rb.1.to_bytes_be()).to_vec(),
+        ].concat().as_slice()).to_vec();
+
+        self.shared = Some(Shared { y, hash });
+        Ok(hex::encode(key))
+    }
+
+    /// 己方的确认杂凑值，发送给对方：响应方为SB，发起方为SA
+    pub fn confirmation(&self) -> Result<String> {
+        let prefix = match self.role {
+            Role::Initiator => 0x03,
+            Role::Responder => 0x02,
+        };
+        Ok(hex::encode(self.confirmation_hash(prefix)?))
+    }
+
+    /// 校验对方的确认杂凑值：发起方校验S1 = SB，响应方校验S2 = SA
+    pub fn confirm(&self, confirmation: &str) -> Result<()> {
+        let prefix = match self.role {
+            Role::Initiator => 0x02,
+            Role::Responder => 0x03,
+        };
+        if !ct_eq(&hex::decode(confirmation)?, &self.confirmation_hash(prefix)?) {
+            return Err(Error::KeyExchange("The confirmation hash of the peer does not match."));
+        }
+        Ok(())
+    }
+
+    /// Hash(prefix ∥ yU ∥ Hash(xU ∥ ZA ∥ ZB ∥ x1 ∥ y1 ∥ x2 ∥ y2))
+    fn confirmation_hash(&self, prefix: u8) -> Result<Vec<u8>> {
+        match &self.shared {
+            Some(shared) => Ok(sm3::hash([vec![prefix], shared.y.clone(), shared.hash.clone()].concat().as_slice()).to_vec()),
+            None => Err(Error::KeyExchange("The key exchange has not been completed.")),
+        }
+    }
+
+    /// x̄ = 2^w + (x & (2^w - 1))，w = ⌈⌈log2(n)⌉ / 2⌉ - 1
+    fn x_bar(&self, x: &BigUint) -> BigUint {
+        let bits = self.builder.blueprint().n.bits();
+        let w = bits.div_ceil(2) - 1;
+        let power = BigUint::one() << w;
+        let mask = power.clone().sub(BigUint::one());
+        power.add(x & mask)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use num_traits::Num;
+
+    use crate::sm2::ecc::Crypto;
+    use crate::sm2::key::PrivateKey;
+    use crate::sm2::p256::P256Elliptic;
+
+    use super::*;
+
+    fn keypair(d: &str) -> KeyPair {
+        let prk = PrivateKey::decode(d).unwrap();
+        let (x, y) = P256Elliptic::init().scalar_base_multiply(prk.value());
+        let puk = PublicKey::decode(&hex::encode(encode_point(&x, &y))).unwrap();
+        KeyPair::new(prk, puk)
+    }
+
+    /// 结果由独立的参考实现计算得到
+    #[test]
+    fn exchange() {
+        let builder: Rc<dyn EllipticBuilder> = Rc::new(P256Elliptic::init());
+        let crypto = Crypto::default();
+
+        let a = keypair("81eb26e941bb5af16df116495f90695272ae2cd63d6c4ae1678418be48230029");
+        let b = keypair("785129917d45a9ea5437a59356b82338eaadda6ceb199088f14ae10defa229b5");
+        let ra = BigUint::from_str_radix("d4de15474db74d06491c440d305e012400990f3e390c7e87153c12db2ea60bb3", 16).unwrap();
+        let rb = BigUint::from_str_radix("7e07124814b309489125eaed101113164ebf0f3458c5bd88335c1f9d596243d6", 16).unwrap();
+
+        let za = crypto.digest("ALICE123@YAHOO.COM".as_bytes(), a.puk().clone()).unwrap();
+        let zb = crypto.digest("BILL456@YAHOO.COM".as_bytes(), b.puk().clone()).unwrap();
+        assert_eq!(hex::encode(&za), "dd302b546d31a872ad6bbe778e3683929ac79580635b48fb9f55fe2858fdd286");
+        assert_eq!(hex::encode(&zb), "47036267a259c02d39ad201f708830a9cd4e567f48db8134e729b497a32e5d33");
+
+        let (pa, pb) = (a.puk().clone(), b.puk().clone());
+        let mut initiator = KeyExchange::new(Role::Initiator, a, pb, za.clone(), zb.clone(), ra, builder.clone());
+        let mut responder = KeyExchange::new(Role::Responder, b, pa, za, zb, rb, builder);
+
+        assert_eq!(
+            initiator.point(),
+            "0464ced1bdbc99d590049b434d0fd73428cf608a5db8fe5ce07f15026940bae40e376629c7ab21e7db260922499ddb118f07ce8eaae3e7720afef6a5cc062070c0"
+        );
+
+        let kb = responder.exchange(&initiator.point(), 16).unwrap();
+        let sb = responder.confirmation().unwrap();
+        assert_eq!(kb, "3c1362830b075a6f891dbf651997bf50");
+        assert_eq!(sb, "2ef96317f3b78f65f5b838dd3500bf720893766b325aa26f9731c37377bd1144");
+
+        let ka = initiator.exchange(&responder.point(), 16).unwrap();
+        assert_eq!(ka, kb);
+        initiator.confirm(&sb).unwrap();
+        assert!(initiator.confirm(&sb[..62]).is_err());
+
+        let sa = initiator.confirmation().unwrap();
+        assert_eq!(sa, "e8982435be4345c5b53f2b858974234f3bde4ff6a74e98bd355667b353bdb0cf");
+        responder.confirm(&sa).unwrap();
+
+        assert!(responder.confirm(&sb).is_err());
+        assert!(initiator.exchange(&responder.point(), 16).is_err());
+    }
+
+    #[test]
+    fn invalid_point() {
+        let crypto = Crypto::default();
+        let a = keypair("81eb26e941bb5af16df116495f90695272ae2cd63d6c4ae1678418be48230029");
+        let b = keypair("785129917d45a9ea5437a59356b82338eaadda6ceb199088f14ae10defa229b5");
+
+        let mut initiator = crypto.initiator(a, "ALICE".as_bytes(), b.puk().clone(), "BILL".as_bytes()).unwrap();
+        assert!(initiator.confirmation().is_err());
+
+        // (1, 1)不是曲线上的点
+        let point = format!("04{:0>64}{:0>64}", 1, 1);
+        assert!(matches!(initiator.exchange(&point, 16), Err(Error::InvalidPublicKey(_))));
+    }
+}
@@ -0,0 +1,287 @@
+use std::collections::HashMap;
+use std::ops::{Add, Sub};
+
+use num_bigint::BigUint;
+use num_integer::{Integer, Roots};
+use num_traits::{One, Zero};
+
+use crate::error::{Error, Result};
+use crate::sm2::ecc::EllipticBuilder;
+use crate::sm2::key::{self, HexKey, PrivateKey, PublicKey};
+use crate::sm2::p256::P256Elliptic;
+
+/// 解密时明文上界max的最大值，小步表最多2^16项
+pub(crate) const MAX_PLAINTEXT: u64 = u32::MAX as u64;
+
+/// 仿射坐标表示的点，无穷远点O表示为(0, 0)
+type Point = (BigUint, BigUint);
+
+/// 加法同态的EC-ElGamal密文
+///
+/// HomomorphicCiphertext ::= SEQUENCE { c1 OCTET STRING, c2 OCTET STRING }
+///
+/// 对整数m加密：C1 = \[k]G，C2 = \[m]G + \[k]P。两个密文逐点相加得到的密文对应明文之和，
+/// 解密时先计算 \[m]G = C2 - \[d]C1，再以小步大步法求离散对数，因此明文须在较小的范围内。
+/// 点以非压缩格式编码，无穷远点编码为单字节00。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HomomorphicCiphertext {
+    c1: Point,
+    c2: Point,
+}
+
+impl HomomorphicCiphertext {
+    pub fn to_der(&self) -> Vec<u8> {
+        yasna::construct_der(|writer| {
+            writer.write_sequence(|writer| {
+                writer.next().write_bytes(&encode_point(&self.c1));
+                writer.next().write_bytes(&encode_point(&self.c2));
+            })
+        })
+    }
+
+    pub fn from_der(der: &[u8]) -> Result<HomomorphicCiphertext> {
+        let (c1, c2) = yasna::parse_der(der, |reader| {
+            reader.read_sequence(|reader| {
+                let c1 = reader.next().read_bytes()?;
+                let c2 = reader.next().read_bytes()?;
+                Ok((c1, c2))
+            })
+        }).map_err(|_| Error::InvalidCipher("The cipher data is not a SEQUENCE of two OCTET STRINGs."))?;
+
+        let builder = P256Elliptic::init();
+        Ok(HomomorphicCiphertext { c1: decode_point(&builder, &c1)?, c2: decode_point(&builder, &c2)? })
+    }
+}
+
+impl Add for &HomomorphicCiphertext {
+    type Output = HomomorphicCiphertext;
+
+    /// 明文相加：(C1 + C1', C2 + C2')
+    fn add(self, other: &HomomorphicCiphertext) -> HomomorphicCiphertext {
+        let builder = P256Elliptic::init();
+        HomomorphicCiphertext {
+            c1: point_add(&builder, &self.c1, &other.c1),
+            c2: point_add(&builder, &self.c2, &other.c2),
+        }
+    }
+}
+
+impl Add for HomomorphicCiphertext {
+    type Output = HomomorphicCiphertext;
+
+    fn add(self, other: HomomorphicCiphertext) -> HomomorphicCiphertext {
+        &self + &other
+    }
+}
+
+/// 加密整数m，k ∈ \[1, n-1]随机生成
+pub(crate) fn encrypt(public_key: &PublicKey, m: u64) -> HomomorphicCiphertext {
+    let builder = P256Elliptic::init();
+    let elliptic = builder.blueprint();
+    let k = elliptic.random(BigUint::one(), elliptic.n.clone().sub(BigUint::one()));
+
+    let c1 = builder.scalar_base_multiply(k.clone());
+    let (px, py) = public_key.value();
+    let c2 = point_add(&builder, &base_multiply(&builder, m), &builder.scalar_multiply(px, py, k));
+    HomomorphicCiphertext { c1, c2 }
+}
+
+/// 以小步大步法解密的解密器
+///
+/// 构造时预先计算小步表{x(\[j]G) : 0 ≤ j < s}，s = ⌈√(max + 1)⌉，表中只保存横坐标的低64位；
+/// 之后每次解密只需至多s次大步，命中时再以一次点乘确认结果。大量解密时应复用同一个解密器。
+/// max不能超过2^32 - 1，此时小步表有2^16项。
+pub struct HomomorphicDecryptor {
+    private_key: PrivateKey,
+    max: u64,
+    step: u64,
+    table: HashMap<u64, u64>,
+}
+
+impl HomomorphicDecryptor {
+    /// private_key为十六进制编码的私钥，解密结果须在\[0, max]内
+    pub fn new(private_key: &str, max: u64) -> Result<Self> {
+        HomomorphicDecryptor::with_key(PrivateKey::decode(private_key)?, max)
+    }
+
+    pub(crate) fn with_key(private_key: PrivateKey, max: u64) -> Result<Self> {
+        if max > MAX_PLAINTEXT {
+            return Err(Error::InvalidCipher("The search range of the plaintext must not exceed 2^32 - 1."));
+        }
+        let builder = P256Elliptic::init();
+
+        // s = ⌈√(max + 1)⌉
+        let step = {
+            let count = max + 1;
+            let s = count.sqrt();
+            if s * s < count { s + 1 } else { s }
+        };
+        let mut table = HashMap::new();
+        let mut point = (BigUint::zero(), BigUint::zero());
+        let g = base_multiply(&builder, 1);
+        for j in 0..step {
+            table.entry(key(&point)).or_insert(j);
+            point = point_add(&builder, &point, &g);
+        }
+        Ok(HomomorphicDecryptor { private_key, max, step, table })
+    }
+
+    /// 解密并返回\[0, max]内的明文
+    pub fn decrypt(&self, cipher: &HomomorphicCiphertext) -> Result<u64> {
+        let builder = P256Elliptic::init();
+
+        // [m]G = C2 - [d]C1
+        let shared = match is_infinity(&cipher.c1) {
+            true => cipher.c1.clone(),
+            false => builder.scalar_multiply(cipher.c1.0.clone(), cipher.c1.1.clone(), self.private_key.value()),
+        };
+        let target = point_add(&builder, &cipher.c2, &negate(&builder, &shared));
+
+        // 大步：依次检查 [m]G - [i·s]G 的横坐标是否在表中，表中的j对应±[j]G，需确认m = i·s + j
+        let step = negate(&builder, &base_multiply(&builder, self.step));
+        let mut giant = target.clone();
+        for i in 0..self.step {
+            if let Some(j) = self.table.get(&key(&giant)) {
+                let m = i * self.step + j;
+                if m <= self.max && base_multiply(&builder, m) == target {
+                    return Ok(m);
+                }
+            }
+            giant = point_add(&builder, &giant, &step);
+        }
+        Err(Error::InvalidCipher("The plaintext is out of the search range or the private key is wrong."))
+    }
+}
+
+/// 解密并以小步大步法在\[0, max]内求m，时间与空间复杂度均为O(√max)，max不能超过[MAX_PLAINTEXT]
+pub(crate) fn decrypt(private_key: &PrivateKey, cipher: &HomomorphicCiphertext, max: u64) -> Result<u64> {
+    HomomorphicDecryptor::with_key(private_key.clone(), max)?.decrypt(cipher)
+}
+
+/// 点加，处理无穷远点、倍点与互为相反数的情况
+fn point_add(builder: &dyn EllipticBuilder, a: &Point, b: &Point) -> Point {
+    if is_infinity(a) {
+        return b.clone();
+    }
+    if is_infinity(b) {
+        return a.clone();
+    }
+    if a.0 == b.0 {
+        return match a.1 == b.1 {
+            true => builder.scalar_multiply(a.0.clone(), a.1.clone(), BigUint::from(2u8)),
+            false => (BigUint::zero(), BigUint::zero()),
+        };
+    }
+    builder.point_add(a.0.clone(), a.1.clone(), b.0.clone(), b.1.clone())
+}
+
+/// -(x, y) = (x, p - y)
+fn negate(builder: &dyn EllipticBuilder, a: &Point) -> Point {
+    if is_infinity(a) {
+        return a.clone();
+    }
+    let p = &builder.blueprint().p;
+    (a.0.clone(), p.clone().sub(&a.1).mod_floor(p))
+}
+
+/// \[m]G，m = 0时为无穷远点
+fn base_multiply(builder: &dyn EllipticBuilder, m: u64) -> Point {
+    match m {
+        0 => (BigUint::zero(), BigUint::zero()),
+        _ => builder.scalar_base_multiply(BigUint::from(m)),
+    }
+}
+
+/// 小步表的键：横坐标的低64位，无穷远点为0
+fn key(point: &Point) -> u64 {
+    point.0.iter_u64_digits().next().unwrap_or(0)
+}
+
+fn is_infinity(point: &Point) -> bool {
+    point.0.is_zero() && point.1.is_zero()
+}
+
+/// 04 ∥ x ∥ y，无穷远点为00
+fn encode_point(point: &Point) -> Vec<u8> {
+    if is_infinity(point) {
+        return vec![0x00];
+    }
+    key::encode_point(&point.0, &point.1)
+}
+
+fn decode_point(builder: &dyn EllipticBuilder, point: &[u8]) -> Result<Point> {
+    match point {
+        [0x00] => Ok((BigUint::zero(), BigUint::zero())),
+        [0x04, rest @ ..] if rest.len() == 64 => {
+            let (x, y) = (BigUint::from_bytes_be(&rest[..32]), BigUint::from_bytes_be(&rest[32..]));
+            if !builder.blueprint().contains(&x, &y) {
+                return Err(Error::InvalidCipher("The point is not on the curve."));
+            }
+            Ok((x, y))
+        }
+        _ => Err(Error::InvalidCipher("The point must be uncompressed or the point at infinity.")),
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::sm2::key::KeyGenerator;
+
+    use super::*;
+
+    #[test]
+    fn add() {
+        let keypair = KeyGenerator::init(Box::new(P256Elliptic::init())).gen_key_pair();
+        let values = [0u64, 1, 7, 100, 65535, 3];
+
+        let mut sum = encrypt(keypair.puk(), 0);
+        for value in values {
+            let cipher = HomomorphicCiphertext::from_der(&encrypt(keypair.puk(), value).to_der()).unwrap();
+            assert_eq!(decrypt(keypair.prk(), &cipher, 65535).unwrap(), value);
+            sum = sum + cipher;
+        }
+        assert_eq!(decrypt(keypair.prk(), &sum, 1 << 20).unwrap(), values.iter().sum::<u64>());
+
+        // 相同的密文相加时C1、C2均为倍点
+        assert_eq!(decrypt(keypair.prk(), &(&sum + &sum), 1 << 20).unwrap(), 2 * values.iter().sum::<u64>());
+
+        assert!(decrypt(keypair.prk(), &sum, 1000).is_err());
+        assert!(decrypt(keypair.prk(), &sum, MAX_PLAINTEXT + 1).is_err());
+        assert!(decrypt(keypair.prk(), &sum, u64::MAX).is_err());
+        let other = KeyGenerator::init(Box::new(P256Elliptic::init())).gen_key_pair();
+        assert!(decrypt(other.prk(), &sum, 1 << 20).is_err());
+    }
+
+    #[test]
+    fn decryptor() {
+        let keypair = KeyGenerator::init(Box::new(P256Elliptic::init())).gen_key_pair();
+        let decryptor = HomomorphicDecryptor::with_key(keypair.prk().clone(), 9999).unwrap();
+        assert_eq!(decryptor.step, 100);
+        assert_eq!(decryptor.table.len(), 100);
+
+        // 覆盖j = 0、i = 0以及上界
+        for value in [0u64, 1, 99, 100, 101, 5050, 9899, 9999] {
+            assert_eq!(decryptor.decrypt(&encrypt(keypair.puk(), value)).unwrap(), value);
+        }
+        assert!(decryptor.decrypt(&encrypt(keypair.puk(), 10000)).is_err());
+        assert!(HomomorphicDecryptor::with_key(keypair.prk().clone(), MAX_PLAINTEXT + 1).is_err());
+    }
+
+    #[test]
+    fn points() {
+        let builder = P256Elliptic::init();
+        let g = base_multiply(&builder, 1);
+        let infinity = base_multiply(&builder, 0);
+
+        assert_eq!(point_add(&builder, &g, &infinity), g);
+        assert_eq!(point_add(&builder, &g, &g), base_multiply(&builder, 2));
+        assert!(is_infinity(&point_add(&builder, &g, &negate(&builder, &g))));
+        assert_eq!(decode_point(&builder, &encode_point(&infinity)).unwrap(), infinity);
+        assert!(decode_point(&builder, &[0x04; 65]).is_err());
+
+        let cipher = HomomorphicCiphertext { c1: infinity.clone(), c2: g };
+        let prk = PrivateKey::decode("6aea1ccf610488aaa7fddba3dd6d76d3bdfd50f957d847be3d453defb695f28e").unwrap();
+        assert_eq!(decrypt(&prk, &cipher, 10).unwrap(), 1);
+    }
+}
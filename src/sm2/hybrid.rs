@@ -0,0 +1,201 @@
+use std::rc::Rc;
+
+use rand::RngCore;
+use yasna::models::ObjectIdentifier;
+use yasna::{ASN1Error, ASN1ErrorKind, BERReader, DERWriter};
+
+use crate::error::{Error, Result};
+use crate::oid;
+use crate::sm2::ecc::{Crypto, Decryption, Encryption};
+use crate::sm2::key::{HexKey, PrivateKey, PublicKey};
+use crate::sm2::p256::P256Elliptic;
+use crate::sm3;
+use crate::sm4::gcm::{CryptoMode, NONCE_LEN, TAG_LEN};
+
+const VERSION: u8 = 1;
+
+/// 接收者
+///
+/// Recipient ::= SEQUENCE { keyIdentifier OCTET STRING, encryptedKey OCTET STRING }
+///
+/// keyIdentifier为接收者非压缩公钥的SM3杂凑值，encryptedKey为以该公钥加密的数据秘钥（GM/T 0009 DER编码）。
+struct Recipient {
+    key_identifier: Vec<u8>,
+    encrypted_key: Vec<u8>,
+}
+
+impl Recipient {
+    fn read(reader: BERReader) -> std::result::Result<Recipient, ASN1Error> {
+        reader.read_sequence(|reader| {
+            let key_identifier = reader.next().read_bytes()?;
+            let encrypted_key = reader.next().read_bytes()?;
+            Ok(Recipient { key_identifier, encrypted_key })
+        })
+    }
+
+    fn write(&self, writer: DERWriter) {
+        writer.write_sequence(|writer| {
+            writer.next().write_bytes(&self.key_identifier);
+            writer.next().write_bytes(&self.encrypted_key);
+        })
+    }
+}
+
+/// 多接收者混合加密
+///
+/// ```text
+/// HybridCiphertext ::= SEQUENCE {
+///     version INTEGER (1),
+///     recipients SEQUENCE OF Recipient,
+///     contentEncryptionAlgorithm AlgorithmIdentifier,  -- sm4-gcm，参数为GCMParameters { nonce, icvLen }
+///     encryptedContent OCTET STRING }                  -- 密文 ∥ 认证标签
+/// ```
+///
+/// 随机生成16字节的SM4数据秘钥，以SM4-GCM加密明文，再以SM2分别为每个接收者加密数据秘钥。
+/// version、recipients、contentEncryptionAlgorithm的DER编码作为GCM的附加数据，篡改接收者列表或参数都会使认证失败。
+pub(crate) fn encrypt(public_keys: &[PublicKey], plain: &[u8]) -> Result<Vec<u8>> {
+    if public_keys.is_empty() {
+        return Err(Error::InvalidCipher("At least one recipient is required."));
+    }
+    let mut key = [0u8; 16];
+    let mut nonce = [0u8; NONCE_LEN];
+    rand::thread_rng().fill_bytes(&mut key);
+    rand::thread_rng().fill_bytes(&mut nonce);
+
+    let crypto = Crypto::der(Rc::new(P256Elliptic::init()));
+    let mut recipients: Vec<Recipient> = vec![];
+    for public_key in public_keys {
+        let key_identifier = key_identifier(public_key);
+        if recipients.iter().any(|e| e.key_identifier == key_identifier) {
+            continue;
+        }
+        let encrypted_key = crypto.encryptor(public_key.clone()).execute_bytes(&key);
+        recipients.push(Recipient { key_identifier, encrypted_key });
+    }
+
+    let header = [
+        yasna::construct_der(|writer| writer.write_u8(VERSION)),
+        yasna::construct_der(|writer| writer.write_sequence_of(|writer| {
+            for recipient in recipients.iter() {
+                recipient.write(writer.next());
+            }
+        })),
+        yasna::construct_der(|writer| write_algorithm(writer, &nonce)),
+    ].concat();
+    let content = CryptoMode::new(&key)?.seal(&nonce, &header, plain)?;
+
+    Ok(yasna::construct_der(|writer| {
+        writer.write_sequence(|writer| {
+            writer.next().write_der(&header);
+            writer.next().write_bytes(&content);
+        })
+    }))
+}
+
+/// 以任一接收者的私钥解密，按公钥的秘钥标识查找对应的接收者
+pub(crate) fn decrypt(private_key: &PrivateKey, cipher: &[u8]) -> Result<Vec<u8>> {
+    let (header, recipients, nonce, content) = yasna::parse_der(cipher, |reader| {
+        reader.read_sequence(|reader| {
+            let version = reader.next().read_der()?;
+            if version != yasna::construct_der(|writer| writer.write_u8(VERSION)) {
+                return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+            }
+            let recipients = reader.next().read_der()?;
+            let algorithm = reader.next().read_der()?;
+            let nonce = yasna::parse_der(&algorithm, read_algorithm)?;
+            let content = reader.next().read_bytes()?;
+            Ok(([version, recipients.clone(), algorithm].concat(), recipients, nonce, content))
+        })
+    }).map_err(|_| Error::InvalidCipher("The hybrid ciphertext is malformed."))?;
+    let recipients = yasna::parse_der(&recipients, |reader| reader.collect_sequence_of(Recipient::read))
+        .map_err(|_| Error::InvalidCipher("The hybrid ciphertext is malformed."))?;
+
+    let key_identifier = key_identifier(&private_key.public_key());
+    let recipient = recipients.iter().find(|e| e.key_identifier == key_identifier)
+        .ok_or(Error::InvalidCipher("The private key is not one of the recipients."))?;
+    let crypto = Crypto::der(Rc::new(P256Elliptic::init()));
+    let key = crypto.decryptor(private_key.clone()).execute_bytes(&recipient.encrypted_key)?;
+    if key.len() != 16 {
+        return Err(Error::InvalidCipher("The data key is not 16 bytes."));
+    }
+    CryptoMode::new(&key)?.open(&nonce, &header, &content)
+}
+
+/// 秘钥标识：SM3(04 ∥ x ∥ y)
+fn key_identifier(public_key: &PublicKey) -> Vec<u8> {
+    sm3::hash(&hex::decode(public_key.encode()).unwrap_or_default()).to_vec()
+}
+
+/// AlgorithmIdentifier ::= SEQUENCE { sm4-gcm, GCMParameters ::= SEQUENCE { nonce OCTET STRING, icvLen INTEGER } }
+fn write_algorithm(writer: DERWriter, nonce: &[u8]) {
+    writer.write_sequence(|writer| {
+        writer.next().write_oid(&ObjectIdentifier::from_slice(oid::SM4_GCM));
+        writer.next().write_sequence(|writer| {
+            writer.next().write_bytes(nonce);
+            writer.next().write_u8(TAG_LEN as u8);
+        });
+    })
+}
+
+fn read_algorithm(reader: BERReader) -> std::result::Result<Vec<u8>, ASN1Error> {
+    reader.read_sequence(|reader| {
+        if reader.next().read_oid()?.components().as_slice() != oid::SM4_GCM {
+            return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+        }
+        reader.next().read_sequence(|reader| {
+            let nonce = reader.next().read_bytes()?;
+            if nonce.len() != NONCE_LEN || reader.next().read_u8()? as usize != TAG_LEN {
+                return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+            }
+            Ok(nonce)
+        })
+    })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::sm2::hybrid::{decrypt, encrypt, Recipient};
+    use crate::sm2::key::{HexKey, KeyGenerator, PrivateKey};
+    use crate::sm2::p256::P256Elliptic;
+
+    #[test]
+    fn multiple_recipients() {
+        let generator = KeyGenerator::init(Box::new(P256Elliptic::init()));
+        let keypairs: Vec<_> = (0..3).map(|_| generator.gen_key_pair()).collect();
+        let public_keys: Vec<_> = keypairs.iter().map(|e| e.puk().clone()).collect();
+        let plain = "部落的配置文件".repeat(100);
+
+        let cipher = encrypt(&[public_keys.clone(), public_keys.clone()].concat(), plain.as_bytes()).unwrap();
+        for keypair in keypairs.iter() {
+            assert_eq!(decrypt(keypair.prk(), &cipher).unwrap(), plain.as_bytes());
+        }
+        // 重复的接收者只保留一份
+        let count = yasna::parse_der(&cipher, |reader| reader.read_sequence(|reader| {
+            reader.next().read_u8()?;
+            let recipients = reader.next().collect_sequence_of(Recipient::read)?;
+            reader.next().read_der()?;
+            reader.next().read_bytes()?;
+            Ok(recipients.len())
+        })).unwrap();
+        assert_eq!(count, 3);
+
+        let other = generator.gen_key_pair();
+        assert_eq!(decrypt(other.prk(), &cipher).unwrap_err(), Error::InvalidCipher("The private key is not one of the recipients."));
+        assert_eq!(encrypt(&[], plain.as_bytes()).unwrap_err(), Error::InvalidCipher("At least one recipient is required."));
+        assert!(decrypt(keypairs[0].prk(), &cipher[1..]).is_err());
+    }
+
+    #[test]
+    fn tampered() {
+        let prk = PrivateKey::decode("6aea1ccf610488aaa7fddba3dd6d76d3bdfd50f957d847be3d453defb695f28e").unwrap();
+        let cipher = encrypt(&[prk.public_key()], b"").unwrap();
+        assert!(decrypt(&prk, &cipher).unwrap().is_empty());
+
+        // 认证标签位于末尾，最后一个字节被改写必然认证失败
+        let mut tampered = cipher.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(decrypt(&prk, &tampered).is_err());
+    }
+}
@@ -1,19 +1,23 @@
 use std::ops::Sub;
 
 use num_bigint::BigUint;
-use num_traits::{Num, One};
+use num_integer::Integer;
+use num_traits::{Num, One, Zero};
 
+use crate::error::{Error, Result};
 use crate::sm2::ecc::EllipticBuilder;
+use crate::sm2::p256::P256Elliptic;
 
-pub trait HexKey {
+pub trait HexKey: Sized {
     fn encode(&self) -> String;
-    fn decode(key: &str) -> Self;
+    fn decode(key: &str) -> Result<Self>;
 }
 
 /// 公钥
-/// 非压缩公钥格式字节串长度为65字节，压缩格式长度为33字节;
+/// 非压缩公钥格式字节串长度为65字节，压缩格式长度为33字节，混合格式长度为65字节;
 /// 非压缩格式公钥首字节为0x04。
 /// 压缩格式公钥，若公钥y坐标最后一位为0，则首字节为0x02，否则为0x03。
+/// 混合格式公钥，若公钥y坐标最后一位为0，则首字节为0x06，否则为0x07。
 /// 签名长度：64字节。
 #[derive(Clone, Debug)]
 pub struct PublicKey(BigUint, BigUint);
@@ -22,36 +26,70 @@ impl PublicKey {
     pub fn value(&self) -> (BigUint, BigUint) {
         (self.0.clone(), self.1.clone())
     }
+
+    /// 公钥验证（GB/T 32918.1 6.2.1）：
+    /// 1. P不是无穷远点O；
+    /// 2. xP、yP是域Fp中的元素，即属于区间\[0, p-1]；
+    /// 3. yP^2 ≡ xP^3 + a·xP + b (mod p)；
+    /// 4. \[n]P = O。
+    pub fn validate(&self) -> bool {
+        let builder = P256Elliptic::init();
+        let elliptic = builder.blueprint();
+
+        if !elliptic.contains(&self.0, &self.1) {
+            return false;
+        }
+
+        // [n]P = O  ⇔  [n-1]P = -P = (xP, p - yP)
+        let (x, y) = builder.scalar_multiply(self.0.clone(), self.1.clone(), elliptic.n.clone().sub(1u8));
+        x == self.0 && y == elliptic.p.clone().sub(&self.1)
+    }
+
+    /// 压缩格式：(02 | 03) ∥ x
+    pub fn encode_compressed(&self) -> String {
+        let pc = if self.1.is_odd() { 0x03 } else { 0x02 };
+        hex::encode([vec![pc], to_32_bytes(self.0.to_bytes_be()).to_vec()].concat())
+    }
 }
 
 impl HexKey for PublicKey {
     fn encode(&self) -> String {
-        let key = {
-            let x = self.0.to_bytes_be();
-            let y = self.1.to_bytes_be();
-            [vec![0x04], to_32_bytes(x).to_vec(), to_32_bytes(y).to_vec()].concat()
-        };
-        hex::encode(key)
+        hex::encode(encode_point(&self.0, &self.1))
     }
 
-    fn decode(key: &str) -> Self {
-        if key.len() != 130 {
-            panic!("The uncompressed public key's length must be 130.")
-        }
-
-        if !key.starts_with("04") {
-            panic!("The compressed public key is invalid.")
-        }
-
-        let key = match hex::decode(key.trim_start_matches("04")) {
-            Ok(data) => data,
-            Err(_) => panic!("The public key must be composed of hex chars.")
+    /// 支持非压缩（04）、压缩（02、03）以及混合（06、07）格式，解码后验证公钥的有效性
+    fn decode(key: &str) -> Result<Self> {
+        let key = hex::decode(key)?;
+
+        let key = match (key.first(), key.len()) {
+            (Some(0x04), 65) => PublicKey(
+                BigUint::from_bytes_be(&key[1..33]),
+                BigUint::from_bytes_be(&key[33..]),
+            ),
+            (Some(pc @ (0x02 | 0x03)), 33) => {
+                let x = BigUint::from_bytes_be(&key[1..]);
+                let elliptic = P256Elliptic::init();
+                match elliptic.blueprint().recover_y(&x, *pc == 0x03) {
+                    Some(y) => PublicKey(x, y),
+                    None => return Err(Error::InvalidPublicKey("The compressed public key is not a point on the curve."))
+                }
+            }
+            (Some(pc @ (0x06 | 0x07)), 65) => {
+                let y = BigUint::from_bytes_be(&key[33..]);
+                if y.is_odd() != (*pc == 0x07) {
+                    return Err(Error::InvalidPublicKey("The hybrid public key is invalid."));
+                }
+                PublicKey(BigUint::from_bytes_be(&key[1..33]), y)
+            }
+            (Some(0x04 | 0x06 | 0x07), _) => return Err(Error::InvalidPublicKey("The uncompressed public key's length must be 130.")),
+            (Some(0x02 | 0x03), _) => return Err(Error::InvalidPublicKey("The compressed public key's length must be 66.")),
+            _ => return Err(Error::InvalidPublicKey("The public key's format is unknown."))
         };
 
-        PublicKey(
-            BigUint::from_bytes_be(&key[..32]),
-            BigUint::from_bytes_be(&key[32..]),
-        )
+        if !key.validate() {
+            return Err(Error::InvalidPublicKey("The public key is not a valid point on the curve."));
+        }
+        Ok(key)
     }
 }
 
@@ -64,6 +102,21 @@ impl PrivateKey {
     pub fn value(&self) -> BigUint {
         self.0.clone()
     }
+
+    /// 私钥 d ∈ \[1, n-2]
+    pub(crate) fn new(d: BigUint) -> Result<Self> {
+        let n = P256Elliptic::init().ec.n;
+        if d.is_zero() || d > n.sub(2u8) {
+            return Err(Error::InvalidPrivateKey("The private key is out of range."));
+        }
+        Ok(PrivateKey(d))
+    }
+
+    /// 由私钥计算公钥 P = \[d]G
+    pub(crate) fn public_key(&self) -> PublicKey {
+        let (x, y) = P256Elliptic::init().scalar_base_multiply(self.0.clone());
+        PublicKey(x, y)
+    }
 }
 
 impl HexKey for PrivateKey {
@@ -71,15 +124,15 @@ impl HexKey for PrivateKey {
         hex::encode(to_32_bytes(self.0.to_bytes_be()))
     }
 
-    fn decode(key: &str) -> Self {
+    fn decode(key: &str) -> Result<Self> {
         if key.len() != 64 {
-            panic!("The length of the private key must be 64.")
+            return Err(Error::InvalidPrivateKey("The length of the private key must be 64."));
         }
-        let key = match BigUint::from_str_radix(&*key, 16) {
+        let key = match BigUint::from_str_radix(key, 16) {
             Ok(data) => data,
-            Err(_) => panic!("The private key must be composed of hex chars.")
+            Err(_) => return Err(Error::InvalidHex)
         };
-        PrivateKey(key)
+        PrivateKey::new(key)
     }
 }
 
@@ -142,6 +195,11 @@ pub fn copy_slice(dst: &mut [u8], src: &[u8]) {
     }
 }
 
+/// 非压缩格式的点：04 ∥ x ∥ y
+pub(crate) fn encode_point(x: &BigUint, y: &BigUint) -> Vec<u8> {
+    [vec![0x04], to_32_bytes(x.to_bytes_be()).to_vec(), to_32_bytes(y.to_bytes_be()).to_vec()].concat()
+}
+
 #[inline(always)]
 pub fn to_32_bytes(data: Vec<u8>) -> [u8; 32] {
     let mut result = [0u8; 32];
@@ -157,6 +215,8 @@ pub fn to_32_bytes(data: Vec<u8>) -> [u8; 32] {
 
 #[cfg(test)]
 mod tests {
+    use std::ops::Add;
+
     use crate::sm2::p256::P256Elliptic;
 
     use super::*;
@@ -195,11 +255,73 @@ mod tests {
         let prk = "6aea1ccf610488aaa7fddba3dd6d76d3bdfd50f957d847be3d453defb695f28e";
         let puk = "04a8af64e38eea41c254df769b5b41fbaa2d77b226b301a2636d463c52b46c777230ad1714e686dd641b9e04596530b38f6a64215b0ed3b081f8641724c5443a6e";
 
-        let private_key = PrivateKey::decode(prk);
+        let private_key = PrivateKey::decode(prk).unwrap();
         assert_eq!(private_key.0.to_string(), "48358803002808206747871163666773640956067045543241775523137833706911222329998");
 
-        let public_key = PublicKey::decode(puk);
+        let public_key = PublicKey::decode(puk).unwrap();
         assert_eq!(public_key.0.to_string(), "76298453107918256108319614943154283626396976993715724710320433578462434588530");
         assert_eq!(public_key.1.to_string(), "22016840577845663905050918262284081863871275223913804750000840645022838962798");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn compressed() {
+        let puk = "04a8af64e38eea41c254df769b5b41fbaa2d77b226b301a2636d463c52b46c777230ad1714e686dd641b9e04596530b38f6a64215b0ed3b081f8641724c5443a6e";
+        let public_key = PublicKey::decode(puk).unwrap();
+
+        let compressed = public_key.encode_compressed();
+        assert_eq!(compressed, "02a8af64e38eea41c254df769b5b41fbaa2d77b226b301a2636d463c52b46c7772");
+        assert_eq!(PublicKey::decode(&compressed).unwrap().encode(), puk);
+
+        let hybrid = format!("06{}", &puk[2..]);
+        assert_eq!(PublicKey::decode(&hybrid).unwrap().encode(), puk);
+
+        // y为奇数
+        let generator = KeyGenerator::init(Box::new(P256Elliptic::init()));
+        for _ in 0..16 {
+            let public_key = generator.gen_key_pair().puk().clone();
+            let compressed = public_key.encode_compressed();
+            assert_eq!(&compressed[..2], if public_key.1.is_odd() { "03" } else { "02" });
+            assert_eq!(PublicKey::decode(&compressed).unwrap().encode(), public_key.encode());
+        }
+    }
+
+    #[test]
+    fn compressed_not_on_curve() {
+        // x = 2 时 x^3 + ax + b 不是模p的二次剩余
+        let key = PublicKey::decode("020000000000000000000000000000000000000000000000000000000000000002");
+        assert_eq!(key.unwrap_err(), Error::InvalidPublicKey("The compressed public key is not a point on the curve."));
+    }
+
+    #[test]
+    fn validate() {
+        let generator = KeyGenerator::init(Box::new(P256Elliptic::init()));
+        let public_key = generator.gen_key_pair().puk().clone();
+        assert!(public_key.validate());
+
+        let (x, y) = public_key.value();
+        let p = P256Elliptic::init().ec.p;
+        assert!(!PublicKey(x.clone(), y.clone().add(1u8)).validate());
+        assert!(!PublicKey(x.clone().add(&p), y.clone()).validate());
+        assert!(!PublicKey(x.clone(), y.clone().add(&p)).validate());
+        assert!(!PublicKey(BigUint::from(0u8), BigUint::from(0u8)).validate());
+    }
+
+    #[test]
+    fn invalid() {
+        // 04a8af...c777230ad...6e 的y坐标末位加1
+        let key = PublicKey::decode("04a8af64e38eea41c254df769b5b41fbaa2d77b226b301a2636d463c52b46c777230ad1714e686dd641b9e04596530b38f6a64215b0ed3b081f8641724c5443a6f");
+        assert_eq!(key.unwrap_err(), Error::InvalidPublicKey("The public key is not a valid point on the curve."));
+
+        let key = PublicKey::decode("07a8af64e38eea41c254df769b5b41fbaa2d77b226b301a2636d463c52b46c777230ad1714e686dd641b9e04596530b38f6a64215b0ed3b081f8641724c5443a6e");
+        assert_eq!(key.unwrap_err(), Error::InvalidPublicKey("The hybrid public key is invalid."));
+
+        assert_eq!(PublicKey::decode("04zz").unwrap_err(), Error::InvalidHex);
+        assert!(PublicKey::decode("").is_err());
+        assert!(PublicKey::decode("05a8af").is_err());
+
+        assert_eq!(PrivateKey::decode("6aea").unwrap_err(), Error::InvalidPrivateKey("The length of the private key must be 64."));
+        assert_eq!(PrivateKey::decode(&"g".repeat(64)).unwrap_err(), Error::InvalidHex);
+        assert!(PrivateKey::decode(&"0".repeat(64)).is_err());
+        assert!(PrivateKey::decode(&"f".repeat(64)).is_err());
+    }
+}
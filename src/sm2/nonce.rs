@@ -0,0 +1,114 @@
+use num_bigint::BigUint;
+use num_integer::Integer;
+use num_traits::Zero;
+
+use crate::sm2::key::to_32_bytes;
+use crate::sm3;
+
+/// 签名随机数k的生成方式
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Nonce {
+    /// 由随机数发生器产生
+    Random,
+    /// 由私钥与待签名的杂凑值确定性派生（RFC 6979，HMAC-SM3），相同输入得到相同的签名
+    Deterministic,
+}
+
+/// RFC 6979 3.2 确定性随机数生成器，使用HMAC-SM3实例化
+///
+/// 曲线阶n与杂凑值长度均为256比特，因此bits2int不需要移位，
+/// int2octets与bits2octets均输出32字节。
+pub(crate) struct DeterministicNonce {
+    n: BigUint,
+    k: [u8; 32],
+    v: [u8; 32],
+    first: bool,
+}
+
+impl DeterministicNonce {
+    /// d: 私钥，e: 待签名的杂凑值，n: 曲线的阶
+    pub(crate) fn new(d: &BigUint, e: &[u8; 32], n: &BigUint) -> Self {
+        let x = to_32_bytes(d.to_bytes_be()).to_vec();
+        let h = to_32_bytes(BigUint::from_bytes_be(e).mod_floor(n).to_bytes_be()).to_vec();
+
+        // V = 0x01 0x01 ... 0x01, K = 0x00 0x00 ... 0x00
+        let v = [0x01u8; 32];
+        let k = [0x00u8; 32];
+
+        // K = HMAC_K(V ∥ 0x00 ∥ int2octets(x) ∥ bits2octets(h1)), V = HMAC_K(V)
+        let k = sm3::hmac(&k, [v.to_vec(), vec![0x00], x.clone(), h.clone()].concat().as_slice());
+        let v = sm3::hmac(&k, &v);
+
+        // K = HMAC_K(V ∥ 0x01 ∥ int2octets(x) ∥ bits2octets(h1)), V = HMAC_K(V)
+        let k = sm3::hmac(&k, [v.to_vec(), vec![0x01], x, h].concat().as_slice());
+        let v = sm3::hmac(&k, &v);
+
+        DeterministicNonce { n: n.clone(), k, v, first: true }
+    }
+
+    /// 生成下一个 k ∈ \[1, n-1]，签名失败需要更换k时继续调用
+    pub(crate) fn next(&mut self) -> BigUint {
+        loop {
+            if !self.first {
+                // K = HMAC_K(V ∥ 0x00), V = HMAC_K(V)
+                self.k = sm3::hmac(&self.k, [self.v.to_vec(), vec![0x00]].concat().as_slice());
+                self.v = sm3::hmac(&self.k, &self.v);
+            }
+            self.first = false;
+
+            self.v = sm3::hmac(&self.k, &self.v);
+            let k = BigUint::from_bytes_be(&self.v);
+
+            if !k.is_zero() && k < self.n {
+                return k;
+            }
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use num_traits::Num;
+
+    use crate::sm2::ecc::{Crypto, Signature};
+    use crate::sm2::key::{HexKey, KeyPair, PrivateKey, PublicKey};
+    use crate::sm2::p256::P256Elliptic;
+
+    use super::*;
+
+    #[test]
+    fn deterministic() {
+        let prk = "6aea1ccf610488aaa7fddba3dd6d76d3bdfd50f957d847be3d453defb695f28e";
+        let puk = "04a8af64e38eea41c254df769b5b41fbaa2d77b226b301a2636d463c52b46c777230ad1714e686dd641b9e04596530b38f6a64215b0ed3b081f8641724c5443a6e";
+        let plain = "圣光会抛弃你的，英雄，就像抛弃我那样。——巫妖王";
+
+        let crypto = Crypto::c1c3c2(Rc::new(P256Elliptic::init())).with_nonce(Nonce::Deterministic);
+        let keypair = KeyPair::new(PrivateKey::decode(prk).unwrap(), PublicKey::decode(puk).unwrap());
+        let signer = crypto.signer(keypair);
+
+        let s1 = signer.sign(plain);
+        let s2 = signer.sign(plain);
+        assert_eq!(s1.encode(), s2.encode());
+        assert_ne!(s1.encode(), signer.sign("巫妖王").encode());
+
+        let r = BigUint::from_str_radix("954d0d43ba7297049b02a89305125004438ba3e630417c10c41fc85b9030ca21", 16).unwrap();
+        let s = BigUint::from_str_radix("e45f41fd2f42fff572a71aa2c9dbebd1ec57ee6d55e84682188b3c51d8cc533d", 16).unwrap();
+        assert_eq!(s1.encode(), Signature::new(r, s).encode());
+
+        assert!(crypto.verifier(PublicKey::decode(puk).unwrap()).verify(plain, &s1));
+    }
+
+    #[test]
+    fn generator() {
+        // GM/T 0003.5 数字签名示例的私钥与杂凑值e
+        let d = BigUint::from_str_radix("3945208f7b2144b13f36e38ac6d39f95889393692860b51a42fb81ef4df7c5b8", 16).unwrap();
+        let e = hex::decode("f0b43e94ba45accaace692ed534382eb17e6ab5a19ce7b31f4486fdfc0d28640").unwrap();
+        let n = P256Elliptic::init().ec.n;
+
+        let mut generator = DeterministicNonce::new(&d, &<[u8; 32]>::try_from(e).unwrap(), &n);
+        assert_eq!(generator.next().to_str_radix(16), "f7d1eea09846e85224fe81ca11453a10827c315a97b924765c3a1e96d9611628");
+    }
+}
@@ -108,15 +108,15 @@ mod tests {
 
         let prk = "6aea1ccf610488aaa7fddba3dd6d76d3bdfd50f957d847be3d453defb695f28e";
         let puk = "04a8af64e38eea41c254df769b5b41fbaa2d77b226b301a2636d463c52b46c777230ad1714e686dd641b9e04596530b38f6a64215b0ed3b081f8641724c5443a6e";
-        let private_key = PrivateKey::decode(prk);
-        let public_key = PublicKey::decode(puk);
+        let private_key = PrivateKey::decode(prk).unwrap();
+        let public_key = PublicKey::decode(puk).unwrap();
 
         let crypto = Crypto::c1c2c3(Rc::new(elliptic.clone()));
         let encryptor = crypto.encryptor(public_key.clone());
         let decryptor = crypto.decryptor(private_key.clone());
         let text = "兽人永不为奴，我们终将成王。——加尔鲁什·地狱咆哮";
         let cipher = encryptor.execute(text);
-        let plain = decryptor.execute(&cipher);
+        let plain = decryptor.execute(&cipher).unwrap();
         assert_eq!(plain, text);
 
         let crypto = Crypto::c1c3c2(Rc::new(elliptic.clone()));
@@ -124,7 +124,7 @@ mod tests {
         let decryptor = crypto.decryptor(private_key.clone());
         let text = "圣光会抛弃你的，英雄，就像抛弃我那样。——巫妖王";
         let cipher = encryptor.execute(text);
-        let plain = decryptor.execute(&cipher);
+        let plain = decryptor.execute(&cipher).unwrap();
         assert_eq!(plain, text);
     }
 
@@ -135,8 +135,8 @@ mod tests {
 
         let prk = "6aea1ccf610488aaa7fddba3dd6d76d3bdfd50f957d847be3d453defb695f28e";
         let puk = "04a8af64e38eea41c254df769b5b41fbaa2d77b226b301a2636d463c52b46c777230ad1714e686dd641b9e04596530b38f6a64215b0ed3b081f8641724c5443a6e";
-        let prk = PrivateKey::decode(prk);
-        let puk = PublicKey::decode(puk);
+        let prk = PrivateKey::decode(prk).unwrap();
+        let puk = PublicKey::decode(puk).unwrap();
         let keypair = KeyPair::new(prk.clone(), puk.clone());
 
         let crypto = Crypto::c1c3c2(Rc::new(elliptic.clone()));
@@ -147,7 +147,7 @@ mod tests {
         let verifier = crypto.verifier(puk);
 
         let ans1 = hex::encode(signer.sign(plain).encode());
-        let s = Signature::decode(hex::decode(ans1).unwrap().as_slice());
+        let s = Signature::decode(hex::decode(ans1).unwrap().as_slice()).unwrap();
 
         let flag = verifier.verify(plain, &s);
         assert_eq!(flag, true);
@@ -0,0 +1,222 @@
+//! SM2已知答案测试向量（KAT）
+//!
+//! 1. GM/T 0003.5-2012（GB/T 32918.5-2017）附录中使用推荐曲线的数字签名与公钥加密示例，随机数k为注入的固定值；
+//! 2. 坐标首字节为0x00的边界用例，用于校验C1、x2、y2、公钥坐标均按32字节定长编码；
+//! 3. 超过2048字节的明文，用于校验KDF派生的分组数量。
+//!
+//! 除标准示例外，其余结果由独立的参考实现计算得到。
+
+use std::rc::Rc;
+
+use num_bigint::BigUint;
+use num_traits::Num;
+
+use crate::sm2::ecc::{Ciphertext, Crypto, Decryption, Mode, Signature};
+use crate::sm2::key::{HexKey, KeyPair, PrivateKey, PublicKey};
+use crate::sm2::p256::P256Elliptic;
+use crate::sm3;
+
+struct SignVector {
+    d: &'static str,
+    px: &'static str,
+    py: &'static str,
+    id: &'static str,
+    message: &'static str,
+    k: &'static str,
+    za: &'static str,
+    e: Option<&'static str>,
+    r: &'static str,
+    s: &'static str,
+}
+
+struct EncryptVector {
+    d: &'static str,
+    message: Message,
+    k: &'static str,
+    /// C1 ∥ C3 ∥ C2
+    cipher: Cipher,
+}
+
+enum Message {
+    Text(&'static str),
+    /// (i mod 128), i ∈ [0, len)
+    Sequence(usize),
+}
+
+enum Cipher {
+    Full(&'static str),
+    /// 仅记录C3以及整个密文的SM3杂凑值
+    Digest { c3: &'static str, hash: &'static str },
+}
+
+impl Message {
+    fn bytes(&self) -> Vec<u8> {
+        match self {
+            Message::Text(text) => text.as_bytes().to_vec(),
+            Message::Sequence(len) => (0..*len).map(|i| (i % 128) as u8).collect(),
+        }
+    }
+}
+
+const SIGN_VECTORS: [SignVector; 2] = [
+    // GM/T 0003.5 附录C 数字签名示例
+    SignVector {
+        d: "3945208f7b2144b13f36e38ac6d39f95889393692860b51a42fb81ef4df7c5b8",
+        px: "09f9df311e5421a150dd7d161e4bc5c672179fad1833fc076bb08ff356f35020",
+        py: "ccea490ce26775a52dc6ea718cc1aa600aed05fbf35e084a6632f6072da9ad13",
+        id: "1234567812345678",
+        message: "message digest",
+        k: "59276e27d506861a16680f3ad9c02dccef3cc1fa3cdbe4ce6d54b80deac1bc21",
+        za: "b2e14c5c79c6df5b85f4fe7ed8db7a262b9da7e07ccb0ea9f4747b8ccda8a4f3",
+        e: Some("f0b43e94ba45accaace692ed534382eb17e6ab5a19ce7b31f4486fdfc0d28640"),
+        r: "f5a03b0648d2c4630eeac513e1bb81a15944da3827d5b74143ac7eaceee720b3",
+        s: "b1b6aa29df212fd8763182bc0d421ca1bb9038fd1f7f42d4840b69c485bbc1aa",
+    },
+    // 公钥x坐标首字节为0x00，非默认IDA
+    SignVector {
+        d: "0000000000000000000000000000000000000000000000000000000000000147",
+        px: "00d062045840b1f4b0a64d6e6c5bc582079fc0af8c366eba632b35f5e217385b",
+        py: "5032f04533c064a41a7616cbb528b168c79a247d46f1c3667e1a2f5921aca9a4",
+        id: "ALICE123@YAHOO.COM",
+        message: "message digest",
+        k: "59276e27d506861a16680f3ad9c02dccef3cc1fa3cdbe4ce6d54b80deac1bc21",
+        za: "4645c5bde325006929ea6802cf56fccc6dad8346f2cbc6c17498f99ef39dd461",
+        e: None,
+        r: "4c26fe78b43186bbedd08c6d4f52fe6f42bef230a24cfcf6ca8b1a88c90985df",
+        s: "5cefe1139d65475a93c25b126c1e3c3c43ce849c4a79714657b22221a6f643aa",
+    },
+];
+
+/// GM/T 0003.5 公钥加密示例的DER编码（GM/T 0009 SM2Cipher）
+const ENCRYPT_DER: &str = "307c\
+    022004ebfc718e8d1798620432268e77feb6415e2ede0e073c0f4f640ecd2e149a73\
+    022100e858f9d81e5430a57b36daab8f950a3c64e6ee6a63094d99283aff767e124df0\
+    042059983c18f809e262923c53aec295d30383b54e39d609d160afcb1908d0bd8766\
+    041321886ca989ca9c7d58087307ca93092d651efa";
+
+const ENCRYPT_VECTORS: [EncryptVector; 3] = [
+    // GM/T 0003.5 附录C 公钥加密示例
+    EncryptVector {
+        d: "3945208f7b2144b13f36e38ac6d39f95889393692860b51a42fb81ef4df7c5b8",
+        message: Message::Text("encryption standard"),
+        k: "59276e27d506861a16680f3ad9c02dccef3cc1fa3cdbe4ce6d54b80deac1bc21",
+        cipher: Cipher::Full(
+            "04\
+            04ebfc718e8d1798620432268e77feb6415e2ede0e073c0f4f640ecd2e149a73\
+            e858f9d81e5430a57b36daab8f950a3c64e6ee6a63094d99283aff767e124df0\
+            59983c18f809e262923c53aec295d30383b54e39d609d160afcb1908d0bd8766\
+            21886ca989ca9c7d58087307ca93092d651efa"
+        ),
+    },
+    // x1、x2首字节均为0x00
+    EncryptVector {
+        d: "3945208f7b2144b13f36e38ac6d39f95889393692860b51a42fb81ef4df7c5b8",
+        message: Message::Text("encryption standard"),
+        k: "0000000000000000000000000000000000000000000000000000000000005abf",
+        cipher: Cipher::Full(
+            "04\
+            00853d6b9da0a697a981b6184df6c863bcefc9b1124f1bea5256f909056ae796\
+            333a99a2073445855ead02ebaf278bcbfef9648a63803c96c53c8c36a34e3971\
+            4be9494269ba914b0777b34c262d57002de02a9ce554ebbc1a44686132c9c5f2\
+            1416cfb906fa1e14ac6d71293495eb5e1f1811"
+        ),
+    },
+    // 3000字节明文，KDF需派生94个分组
+    EncryptVector {
+        d: "3945208f7b2144b13f36e38ac6d39f95889393692860b51a42fb81ef4df7c5b8",
+        message: Message::Sequence(3000),
+        k: "59276e27d506861a16680f3ad9c02dccef3cc1fa3cdbe4ce6d54b80deac1bc21",
+        cipher: Cipher::Digest {
+            c3: "b42c54cd53daa60b69f7d193b8d4f599630530e5af57b840576fc3edc52d3e60",
+            hash: "e059f31e8db48e35ef51431286056cccc03946b9bdafe9622eded8e07d373362",
+        },
+    },
+];
+
+fn number(hex: &str) -> BigUint {
+    BigUint::from_str_radix(hex, 16).unwrap()
+}
+
+fn keypair(d: &str) -> KeyPair {
+    let prk = PrivateKey::decode(d).unwrap();
+    let (x, y) = {
+        use crate::sm2::ecc::EllipticBuilder;
+        P256Elliptic::init().scalar_base_multiply(prk.value())
+    };
+    let puk = PublicKey::decode(&format!("04{:0>64}{:0>64}", x.to_str_radix(16), y.to_str_radix(16))).unwrap();
+    KeyPair::new(prk, puk)
+}
+
+#[test]
+fn sign() {
+    let crypto = Crypto::default();
+    for v in SIGN_VECTORS.iter() {
+        let pair = keypair(v.d);
+        let puk = pair.puk().clone();
+        assert_eq!(puk.encode(), format!("04{}{}", v.px, v.py));
+
+        let za = crypto.digest(v.id.as_bytes(), puk.clone()).unwrap();
+        assert_eq!(hex::encode(&za), v.za);
+
+        let e = sm3::hash([za, v.message.as_bytes().to_vec()].concat().as_slice());
+        if let Some(expected) = v.e {
+            assert_eq!(hex::encode(e), expected);
+        }
+
+        let signature = crypto.signer_with_id(pair, v.id.as_bytes()).unwrap().sign_with(&e, number(v.k)).unwrap();
+        assert_eq!(signature.encode(), Signature::new(number(v.r), number(v.s)).encode());
+
+        let verifier = crypto.verifier_with_id(puk, v.id.as_bytes()).unwrap();
+        assert!(verifier.verify(v.message, &signature));
+        assert!(!verifier.verify("message digesT", &signature));
+    }
+}
+
+#[test]
+fn encrypt() {
+    for mode in 0..3 {
+        let crypto = match mode {
+            0 => Crypto::c1c3c2(Rc::new(P256Elliptic::init())),
+            1 => Crypto::c1c2c3(Rc::new(P256Elliptic::init())),
+            _ => Crypto::der(Rc::new(P256Elliptic::init())),
+        };
+
+        for v in ENCRYPT_VECTORS.iter() {
+            let pair = keypair(v.d);
+            let message = v.message.bytes();
+            let cipher = crypto.encryptor(pair.puk().clone()).encrypt_with(&message, number(v.k)).unwrap();
+
+            // C1 ∥ C3 ∥ C2
+            let c1c3c2 = match mode {
+                0 => cipher.clone(),
+                1 => {
+                    let len = cipher.len();
+                    [&cipher[..65], &cipher[len - 32..], &cipher[65..len - 32]].concat()
+                }
+                _ => Ciphertext::decode(&cipher, Mode::Der).unwrap().encode(Mode::C1C3C2),
+            };
+
+            match v.cipher {
+                Cipher::Full(expected) => assert_eq!(hex::encode(&c1c3c2), expected),
+                Cipher::Digest { c3, hash } => {
+                    assert_eq!(hex::encode(&c1c3c2[65..97]), c3);
+                    assert_eq!(hex::encode(sm3::hash(&c1c3c2)), hash);
+                }
+            }
+
+            let plain = crypto.decryptor(pair.prk().clone()).execute(&hex::encode(&cipher)).unwrap();
+            assert_eq!(plain.as_bytes(), message.as_slice());
+        }
+    }
+}
+
+#[test]
+fn encrypt_der() {
+    let crypto = Crypto::der(Rc::new(P256Elliptic::init()));
+    let v = &ENCRYPT_VECTORS[0];
+    let pair = keypair(v.d);
+
+    let cipher = crypto.encryptor(pair.puk().clone()).encrypt_with(&v.message.bytes(), number(v.k)).unwrap();
+    assert_eq!(hex::encode(&cipher), ENCRYPT_DER);
+    assert_eq!(crypto.decryptor(pair.prk().clone()).execute(ENCRYPT_DER).unwrap(), "encryption standard");
+}
@@ -10,4 +10,69 @@ pub fn hash(data: &[u8]) -> [u8; 32] {
     core::Crypto::new(data).hash()
 }
 
+/// 计算HMAC-SM3消息认证码
+///
+/// HMAC(K, M) = H((K' ⊕ opad) ∥ H((K' ⊕ ipad) ∥ M))，分组长度为64字节，
+/// 长度超过64字节的秘钥K先计算杂凑值，不足64字节的右侧补0得到K'。
+pub fn hmac(key: &[u8], data: &[u8]) -> [u8; 32] {
+    let mut k = [0u8; 64];
+    if key.len() > 64 {
+        k[..32].copy_from_slice(&hash(key));
+    } else {
+        k[..key.len()].copy_from_slice(key);
+    }
 
+    let ipad: Vec<u8> = k.iter().map(|e| e ^ 0x36).collect();
+    let opad: Vec<u8> = k.iter().map(|e| e ^ 0x5c).collect();
+
+    let inner = hash([ipad, data.to_vec()].concat().as_slice());
+    hash([opad, inner.to_vec()].concat().as_slice())
+}
+
+/// PBKDF2-HMAC-SM3（RFC 8018 5.2），由口令与盐值派生len字节的秘钥
+///
+/// T_i = U_1 ⊕ U_2 ⊕ ... ⊕ U_c，U_1 = PRF(P, S ∥ INT(i))，U_j = PRF(P, U_{j-1})，DK = T_1 ∥ T_2 ∥ ...
+pub fn pbkdf2(password: &[u8], salt: &[u8], iterations: u32, len: usize) -> Vec<u8> {
+    let mut key = Vec::with_capacity(len);
+    let mut i: u32 = 1;
+    while key.len() < len {
+        let mut u = hmac(password, [salt, &i.to_be_bytes()].concat().as_slice());
+        let mut t = u;
+        for _ in 1..iterations {
+            u = hmac(password, &u);
+            t.iter_mut().zip(u.iter()).for_each(|(t, u)| *t ^= u);
+        }
+        key.extend_from_slice(&t[..(len - key.len()).min(32)]);
+        i += 1;
+    }
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hmac_sm3() {
+        let mac = hmac(b"key", b"The quick brown fox jumps over the lazy dog");
+        assert_eq!(hex::encode(mac), "bd4a34077888162b210645b8ebf74b9af357303789357a27c7fc457244ebd398");
+
+        let mac = hmac(&[b'k'; 100], b"abc");
+        assert_eq!(hex::encode(mac), "2d87dd3ffa1452e8e40d9123a02824fb7dd98ae4a52683287245f1736dc610ef");
+
+        let mac = hmac(b"", b"");
+        assert_eq!(hex::encode(mac), "0d23f72ba15e9c189a879aefc70996b06091de6e64d31b7a84004356dd915261");
+    }
+
+    #[test]
+    fn pbkdf2_sm3() {
+        let key = pbkdf2(b"password", b"salt", 1, 32);
+        assert_eq!(hex::encode(key), "4612f922a1fdcefaf4312fc6f8f3322b489cbf24f2ea361b44c2bd8fa2c6dcb0");
+
+        let key = pbkdf2(b"password", b"salt", 2, 32);
+        assert_eq!(hex::encode(key), "fee723a2bc966e11dffb66133f4e8df577383c78ade30e3298edbd3e54ed85b7");
+
+        let key = pbkdf2(b"passwordPASSWORDpassword", b"saltSALTsaltSALTsaltSALTsaltSALTsalt", 4096, 40);
+        assert_eq!(hex::encode(key), "3b6282ac8519f059e465abff0ea37b0dbfe6c672a76e6b805312d53900db630732ccc1a88fa5512a");
+    }
+}
@@ -1,9 +1,12 @@
+use crate::error::{Error, OrPanic, Result};
+
 mod core;
 mod ecb;
-mod cbc;
+pub(crate) mod cbc;
 mod cfb;
 mod ofb;
 mod ctr;
+pub(crate) mod gcm;
 
 
 /// 随机生成秘钥，返回由16进制字符组成的长度为32的字符串
@@ -18,63 +21,103 @@ pub fn generate_iv() -> String {
 }
 
 pub fn encrypt_ecb(key: String, plain: String) -> String {
-    let mode = Mode::ECB { key };
-    let crypto = CryptoFactory::new(mode);
-    crypto.encrypt(plain)
+    try_encrypt_ecb(key, plain).or_panic()
 }
 
 pub fn decrypt_ecb(key: String, cipher: String) -> String {
-    let mode = Mode::ECB { key };
-    let crypto = CryptoFactory::new(mode);
-    crypto.decrypt(cipher)
+    try_decrypt_ecb(key, cipher).or_panic()
 }
 
 pub fn encrypt_cbc(key: String, iv: String, plain: String) -> String {
-    let mode = Mode::CBC { key, iv };
-    let crypto = CryptoFactory::new(mode);
-    crypto.encrypt(plain)
+    try_encrypt_cbc(key, iv, plain).or_panic()
 }
 
 pub fn decrypt_cbc(key: String, iv: String, cipher: String) -> String {
-    let mode = Mode::CBC { key, iv };
-    let crypto = CryptoFactory::new(mode);
-    crypto.decrypt(cipher)
+    try_decrypt_cbc(key, iv, cipher).or_panic()
 }
 
 pub fn encrypt_cfb(key: String, iv: String, plain: String) -> String {
-    let mode = Mode::CFB { key, iv };
-    let crypto = CryptoFactory::new(mode);
-    crypto.encrypt(plain)
+    try_encrypt_cfb(key, iv, plain).or_panic()
 }
 
 pub fn decrypt_cfb(key: String, iv: String, cipher: String) -> String {
-    let mode = Mode::CFB { key, iv };
-    let crypto = CryptoFactory::new(mode);
-    crypto.decrypt(cipher)
+    try_decrypt_cfb(key, iv, cipher).or_panic()
 }
 
 pub fn encrypt_ofb(key: String, iv: String, plain: String) -> String {
-    let mode = Mode::OFB { key, iv };
-    let crypto = CryptoFactory::new(mode);
-    crypto.encrypt(plain)
+    try_encrypt_ofb(key, iv, plain).or_panic()
 }
 
 pub fn decrypt_ofb(key: String, iv: String, cipher: String) -> String {
-    let mode = Mode::OFB { key, iv };
-    let crypto = CryptoFactory::new(mode);
-    crypto.decrypt(cipher)
+    try_decrypt_ofb(key, iv, cipher).or_panic()
 }
 
 pub fn encrypt_ctr(key: String, iv: String, plain: String) -> String {
-    let mode = Mode::CTR { key, iv };
-    let crypto = CryptoFactory::new(mode);
-    crypto.encrypt(plain)
+    try_encrypt_ctr(key, iv, plain).or_panic()
 }
 
 pub fn decrypt_ctr(key: String, iv: String, cipher: String) -> String {
+    try_decrypt_ctr(key, iv, cipher).or_panic()
+}
+
+pub fn try_encrypt_ecb(key: String, plain: String) -> Result<String> {
+    let mode = Mode::ECB { key };
+    let crypto = CryptoFactory::try_new(mode)?;
+    Ok(crypto.encrypt(plain))
+}
+
+pub fn try_decrypt_ecb(key: String, cipher: String) -> Result<String> {
+    let mode = Mode::ECB { key };
+    let crypto = CryptoFactory::try_new(mode)?;
+    crypto.try_decrypt(cipher)
+}
+
+pub fn try_encrypt_cbc(key: String, iv: String, plain: String) -> Result<String> {
+    let mode = Mode::CBC { key, iv };
+    let crypto = CryptoFactory::try_new(mode)?;
+    Ok(crypto.encrypt(plain))
+}
+
+pub fn try_decrypt_cbc(key: String, iv: String, cipher: String) -> Result<String> {
+    let mode = Mode::CBC { key, iv };
+    let crypto = CryptoFactory::try_new(mode)?;
+    crypto.try_decrypt(cipher)
+}
+
+pub fn try_encrypt_cfb(key: String, iv: String, plain: String) -> Result<String> {
+    let mode = Mode::CFB { key, iv };
+    let crypto = CryptoFactory::try_new(mode)?;
+    Ok(crypto.encrypt(plain))
+}
+
+pub fn try_decrypt_cfb(key: String, iv: String, cipher: String) -> Result<String> {
+    let mode = Mode::CFB { key, iv };
+    let crypto = CryptoFactory::try_new(mode)?;
+    crypto.try_decrypt(cipher)
+}
+
+pub fn try_encrypt_ofb(key: String, iv: String, plain: String) -> Result<String> {
+    let mode = Mode::OFB { key, iv };
+    let crypto = CryptoFactory::try_new(mode)?;
+    Ok(crypto.encrypt(plain))
+}
+
+pub fn try_decrypt_ofb(key: String, iv: String, cipher: String) -> Result<String> {
+    let mode = Mode::OFB { key, iv };
+    let crypto = CryptoFactory::try_new(mode)?;
+    crypto.try_decrypt(cipher)
+}
+
+pub fn try_encrypt_ctr(key: String, iv: String, plain: String) -> Result<String> {
     let mode = Mode::CTR { key, iv };
-    let crypto = CryptoFactory::new(mode);
-    crypto.decrypt(cipher)
+    let crypto = CryptoFactory::try_new(mode)?;
+    Ok(crypto.encrypt(plain))
+}
+
+pub fn try_decrypt_ctr(key: String, iv: String, cipher: String) -> Result<String> {
+    let mode = Mode::CTR { key, iv };
+    let crypto = CryptoFactory::try_new(mode)?;
+    crypto.try_decrypt(cipher)
 }
 
 pub enum Mode {
@@ -88,16 +131,24 @@ pub enum Mode {
 pub trait Cryptographer {
     fn encrypt_bytes(&self, plain: &[u8]) -> Vec<u8>;
 
-    fn decrypt_bytes(&self, cipher: &[u8]) -> Vec<u8>;
+    fn try_decrypt_bytes(&self, cipher: &[u8]) -> Result<Vec<u8>>;
+
+    fn decrypt_bytes(&self, cipher: &[u8]) -> Vec<u8> {
+        self.try_decrypt_bytes(cipher).or_panic()
+    }
 
     fn encrypt(&self, data: String) -> String {
         let cipher = self.encrypt_bytes(data.as_bytes());
         hex::encode(cipher)
     }
 
+    fn try_decrypt(&self, data: String) -> Result<String> {
+        let plain = self.try_decrypt_bytes(&hex::decode(data)?)?;
+        Ok(String::from_utf8_lossy(plain.as_ref()).to_string())
+    }
+
     fn decrypt(&self, data: String) -> String {
-        let plain = self.decrypt_bytes(&hex::decode(data).unwrap());
-        String::from_utf8_lossy(plain.as_ref()).to_string()
+        self.try_decrypt(data).or_panic()
     }
 }
 
@@ -106,23 +157,27 @@ pub struct CryptoFactory;
 
 impl CryptoFactory {
     pub fn new(mode: Mode) -> Box<dyn Cryptographer> {
-        match mode {
+        Self::try_new(mode).or_panic()
+    }
+
+    pub fn try_new(mode: Mode) -> Result<Box<dyn Cryptographer>> {
+        Ok(match mode {
             Mode::ECB { key } => {
-                Box::new(ecb::CryptoMode::new(&hex_decode_of_key(&key)))
+                Box::new(ecb::CryptoMode::new(&hex_decode_of_key(&key)?)?)
             }
             Mode::CBC { key, iv } => {
-                Box::new(cbc::CryptoMode::new(&hex_decode_of_key(&key), &hex_decode_of_iv(&iv)))
+                Box::new(cbc::CryptoMode::new(&hex_decode_of_key(&key)?, &hex_decode_of_iv(&iv)?)?)
             }
             Mode::CFB { key, iv } => {
-                Box::new(cfb::CryptoMode::new(&hex_decode_of_key(&key), &hex_decode_of_iv(&iv)))
+                Box::new(cfb::CryptoMode::new(&hex_decode_of_key(&key)?, &hex_decode_of_iv(&iv)?)?)
             }
             Mode::OFB { key, iv } => {
-                Box::new(ofb::CryptoMode::new(&hex_decode_of_key(&key), &hex_decode_of_iv(&iv)))
+                Box::new(ofb::CryptoMode::new(&hex_decode_of_key(&key)?, &hex_decode_of_iv(&iv)?)?)
             }
             Mode::CTR { key, iv } => {
-                Box::new(ctr::CryptoMode::new(&hex_decode_of_key(&key), &hex_decode_of_iv(&iv)))
+                Box::new(ctr::CryptoMode::new(&hex_decode_of_key(&key)?, &hex_decode_of_iv(&iv)?)?)
             }
-        }
+        })
     }
 }
 
@@ -135,18 +190,25 @@ fn xor(a: &[u8], b: &[u8]) -> [u8; 16] {
     out
 }
 
-fn hex_decode_of_key(key: &str) -> Vec<u8> {
-    match hex::decode(key) {
-        Ok(data) => data,
-        Err(_) => panic!("The Key must be composed of hex chars with a length of 32.")
-    }
+fn hex_decode_of_key(key: &str) -> Result<Vec<u8>> {
+    hex::decode(key).map_err(|_| Error::InvalidKey)
+}
+
+fn hex_decode_of_iv(iv: &str) -> Result<Vec<u8>> {
+    hex::decode(iv).map_err(|_| Error::InvalidIv)
 }
 
-fn hex_decode_of_iv(iv: &str) -> Vec<u8> {
-    match hex::decode(iv) {
-        Ok(data) => data,
-        Err(_) => panic!("The IV must be composed of hex chars with a length of 32.")
+/// 去除PKCS#7填充：最后一个字节的值m ∈ \[1, 16]，且最后m个字节均为m
+fn unpad(data: &mut Vec<u8>) -> Result<()> {
+    let m = match data.last() {
+        Some(m) if *m >= 1 && *m <= 16 && (*m as usize) <= data.len() => *m as usize,
+        _ => return Err(Error::InvalidPadding)
+    };
+    if data[data.len() - m..].iter().any(|e| *e as usize != m) {
+        return Err(Error::InvalidPadding);
     }
+    data.truncate(data.len() - m);
+    Ok(())
 }
 
 
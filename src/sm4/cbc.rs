@@ -1,5 +1,6 @@
+use crate::error::{Error, Result};
 use crate::sm4::core::Crypto;
-use crate::sm4::{Cryptographer, xor};
+use crate::sm4::{Cryptographer, unpad, xor};
 
 /// CBC: Cipher Block Chaining
 ///
@@ -23,8 +24,11 @@ pub struct CryptoMode {
 }
 
 impl CryptoMode {
-    pub fn new(key: &[u8], iv: &[u8]) -> Self {
-        crate::sm4::cbc::CryptoMode { crypto: Crypto::init(key), iv: iv.to_vec() }
+    pub fn new(key: &[u8], iv: &[u8]) -> Result<Self> {
+        if iv.len() != 16 {
+            return Err(Error::InvalidIv);
+        }
+        Ok(crate::sm4::cbc::CryptoMode { crypto: Crypto::init(key)?, iv: iv.to_vec() })
     }
 }
 
@@ -62,10 +66,10 @@ impl Cryptographer for CryptoMode {
         out
     }
 
-    fn decrypt_bytes(&self, cipher: &[u8]) -> Vec<u8> {
+    fn try_decrypt_bytes(&self, cipher: &[u8]) -> Result<Vec<u8>> {
         let (quotients, remainder) = (cipher.len() / 16, cipher.len() % 16);
-        if remainder != 0 {
-            panic!("The cipher‘s length must be a multiple of 16 bytes.");
+        if remainder != 0 || quotients == 0 {
+            return Err(Error::InvalidCipher("The cipher‘s length must be a multiple of 16 bytes."));
         }
 
         let mut out: Vec<u8> = Vec::new();
@@ -79,10 +83,8 @@ impl Cryptographer for CryptoMode {
             buf.copy_from_slice(&cipher[i * 16..(i + 1) * 16])
         }
 
-        let last_byte = out[cipher.len() - 1];
-        // assert!(last_byte > 0 && last_byte <= 0x10);
-        out.resize(cipher.len() - last_byte as usize, 0);
-        out
+        unpad(&mut out)?;
+        Ok(out)
     }
 }
 
@@ -99,7 +101,7 @@ mod tests {
 
         let plain = "Hello World, 哈罗，世界";
 
-        let c = CryptoMode::new(&key, &iv);
+        let c = CryptoMode::new(&key, &iv).unwrap();
         let cipher = c.encrypt(String::from(plain));
         let text = c.decrypt(cipher);
 
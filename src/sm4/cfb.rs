@@ -1,3 +1,4 @@
+use crate::error::{Error, Result};
 use crate::sm4::core::Crypto;
 use crate::sm4::{Cryptographer, xor};
 
@@ -22,8 +23,11 @@ pub struct CryptoMode {
 }
 
 impl CryptoMode {
-    pub fn new(key: &[u8], iv: &[u8]) -> Self {
-        crate::sm4::cfb::CryptoMode { crypto: Crypto::init(key), iv: iv.to_vec() }
+    pub fn new(key: &[u8], iv: &[u8]) -> Result<Self> {
+        if iv.len() != 16 {
+            return Err(Error::InvalidIv);
+        }
+        Ok(crate::sm4::cfb::CryptoMode { crypto: Crypto::init(key)?, iv: iv.to_vec() })
     }
 }
 
@@ -51,7 +55,7 @@ impl Cryptographer for CryptoMode {
 
 
     // 解密和加密的结构是一样的
-    fn decrypt_bytes(&self, cipher: &[u8]) -> Vec<u8> {
+    fn try_decrypt_bytes(&self, cipher: &[u8]) -> Result<Vec<u8>> {
         let (quotients, remainder) = (cipher.len() / 16, cipher.len() % 16);
 
         let mut out: Vec<u8> = Vec::new();
@@ -70,7 +74,7 @@ impl Cryptographer for CryptoMode {
         for i in 0..remainder {
             out.push(cipher[quotients * 16 + i] ^ c[i])
         }
-        out
+        Ok(out)
     }
 }
 
@@ -86,7 +90,7 @@ mod tests {
 
         let plain = "Hello World, 哈罗，魔兽世界";
 
-        let c = CryptoMode::new(&key, &iv);
+        let c = CryptoMode::new(&key, &iv).unwrap();
         let cipher = c.encrypt(String::from(plain));
         let text = c.decrypt(cipher);
 
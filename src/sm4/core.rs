@@ -1,3 +1,5 @@
+use crate::error::{Error, Result};
+
 const FK: [u32; 4] = [0xa3b1bac6, 0x56aa3350, 0x677d9197, 0xb27022dc];
 const CK: [u32; 32] = [
     0x00070e15, 0x1c232a31, 0x383f464d, 0x545b6269,
@@ -52,10 +54,9 @@ fn linear_trans_ex(b: u32) -> u32 {
 }
 
 /// 分组长度和密钥长度均为128比特。将16个字节`[u8;16]`转换为四个字`[u32; 4]`
+///
+/// 秘钥长度在[Crypto::init]中校验，分组由各工作模式按16字节切分。
 fn bytes_to_words(input: &[u8]) -> [u32; 4] {
-    if input.len() != 16 {
-        panic!("The block size and Key size must be 16 bytes!");
-    }
     let mut out: [u32; 4] = [0; 4];
     for (i, v) in out.iter_mut().enumerate() {
         *v = bytes_to_word(&input[i * 4..(i + 1) * 4]);
@@ -94,7 +95,10 @@ pub struct Crypto {
 }
 
 impl Crypto {
-    pub fn init(key: &[u8]) -> Self {
+    pub fn init(key: &[u8]) -> Result<Self> {
+        if key.len() != 16 {
+            return Err(Error::InvalidKey);
+        }
         let mut crypto = Crypto { rk: Vec::new() };
         // 秘钥：MK=(MK0, MK1, MK2, MK3)
         let mut k: [u32; 4] = bytes_to_words(key);
@@ -115,7 +119,7 @@ impl Crypto {
             crypto.rk.push(k[2]);
             crypto.rk.push(k[3]);
         }
-        crypto
+        Ok(crypto)
     }
 
     /// 设明文输入为(X0,X1,X2,X3)，密文输出为(Y0,Y1,Y2,Y3)，轮密钥为rki,i=0,1,2,...,31。则本算法的加密变换为:
@@ -158,7 +162,7 @@ mod tests {
         let key = hex::decode(k).unwrap();
         let plain = hex::decode(p).unwrap();
 
-        let crypto = Crypto::init(&key);
+        let crypto = Crypto::init(&key).unwrap();
         assert_eq!(crypto.rk[0], 0xf121_86f9);
         assert_eq!(crypto.rk[31], 0x9124_a012);
 
@@ -185,7 +189,7 @@ mod tests {
         let key = hex::decode(k).unwrap();
         let plain = hex::decode(p).unwrap();
 
-        let crypto = Crypto::init(&key);
+        let crypto = Crypto::init(&key).unwrap();
 
         let mut cipher: [u8; 16] = <[u8; 16]>::try_from(plain).unwrap();
         for _i in 0..1000000 {
@@ -1,3 +1,4 @@
+use crate::error::{Error, Result};
 use crate::sm4::core::Crypto;
 use crate::sm4::{Cryptographer, xor};
 
@@ -22,8 +23,11 @@ pub struct CryptoMode {
 }
 
 impl CryptoMode {
-    pub fn new(key: &[u8], iv: &[u8]) -> Self {
-        crate::sm4::ctr::CryptoMode { crypto: Crypto::init(key), iv: iv.to_vec() }
+    pub fn new(key: &[u8], iv: &[u8]) -> Result<Self> {
+        if iv.len() != 16 {
+            return Err(Error::InvalidIv);
+        }
+        Ok(crate::sm4::ctr::CryptoMode { crypto: Crypto::init(key)?, iv: iv.to_vec() })
     }
 }
 
@@ -56,8 +60,8 @@ impl Cryptographer for CryptoMode {
         out
     }
 
-    fn decrypt_bytes(&self, cipher: &[u8]) -> Vec<u8> {
-        self.encrypt_bytes(cipher)
+    fn try_decrypt_bytes(&self, cipher: &[u8]) -> Result<Vec<u8>> {
+        Ok(self.encrypt_bytes(cipher))
     }
 }
 
@@ -74,7 +78,7 @@ mod tests {
 
         let plain = "Hello World, 哈哈，我的世界！";
 
-        let c = CryptoMode::new(&key, &iv);
+        let c = CryptoMode::new(&key, &iv).unwrap();
         let cipher = c.encrypt(String::from(plain));
         let text = c.decrypt(cipher);
 
@@ -1,5 +1,6 @@
+use crate::error::{Error, Result};
 use crate::sm4::core::Crypto;
-use crate::sm4::Cryptographer;
+use crate::sm4::{Cryptographer, unpad};
 
 /// ECB: Electronic Code Book
 ///
@@ -22,8 +23,8 @@ pub struct CryptoMode {
 }
 
 impl CryptoMode {
-    pub fn new(key: &[u8]) -> Self {
-        crate::sm4::ecb::CryptoMode { crypto: Crypto::init(key) }
+    pub fn new(key: &[u8]) -> Result<Self> {
+        Ok(crate::sm4::ecb::CryptoMode { crypto: Crypto::init(key)? })
     }
 }
 
@@ -54,10 +55,10 @@ impl Cryptographer for CryptoMode {
         out
     }
 
-    fn decrypt_bytes(&self, cipher: &[u8]) -> Vec<u8> {
+    fn try_decrypt_bytes(&self, cipher: &[u8]) -> Result<Vec<u8>> {
         let (quotients, remainder) = (cipher.len() / 16, cipher.len() % 16);
-        if remainder != 0 {
-            panic!("The cipher‘s length must be a multiple of 16 bytes.");
+        if remainder != 0 || quotients == 0 {
+            return Err(Error::InvalidCipher("The cipher‘s length must be a multiple of 16 bytes."));
         }
 
         let mut out: Vec<u8> = Vec::new();
@@ -66,9 +67,8 @@ impl Cryptographer for CryptoMode {
             block.iter().for_each(|e| out.push(*e));
         }
 
-        let last_byte = out[cipher.len() - 1];
-        out.resize(cipher.len() - last_byte as usize, 0);
-        out
+        unpad(&mut out)?;
+        Ok(out)
     }
 }
 
@@ -83,7 +83,7 @@ mod tests {
         let key = hex::decode("0123456789abcdeffedcba9876543210").unwrap();
         let plain = "Hello World, 哈喽，世界";
 
-        let c = CryptoMode::new(&key);
+        let c = CryptoMode::new(&key).unwrap();
         let cipher = c.encrypt(String::from(plain));
         let text = c.decrypt(cipher);
 
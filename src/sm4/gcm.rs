@@ -0,0 +1,173 @@
+use crate::ct_eq;
+use crate::error::{Error, Result};
+use crate::sm4::core::Crypto;
+
+/// GCM: Galois/Counter Mode（NIST SP 800-38D，RFC 8998）
+///
+/// 伽罗瓦/计数器模式，带认证的加密
+///
+/// ### 推荐使用
+///
+/// 优点：
+/// * 同时保证机密性与完整性，可以认证不加密的附加数据
+/// * 不需要填充，支持并行计算
+///
+/// 缺点：
+/// * 同一秘钥下随机数不能重复，否则会泄露认证秘钥
+///
+/// 仅支持12字节的随机数，认证标签为16字节，附加在密文之后。
+pub(crate) struct CryptoMode {
+    crypto: Crypto,
+    h: u128,
+}
+
+/// 随机数长度
+pub(crate) const NONCE_LEN: usize = 12;
+/// 认证标签长度
+pub(crate) const TAG_LEN: usize = 16;
+/// 明文的最大长度：2^32 - 2个分组（2^39 - 256比特），超过后计数器会回绕到J0
+const MAX_LEN: u64 = ((1 << 32) - 2) * 16;
+
+impl CryptoMode {
+    pub(crate) fn new(key: &[u8]) -> Result<Self> {
+        let crypto = Crypto::init(key)?;
+        let h = u128::from_be_bytes(crypto.encrypt(&[0; 16]));
+        Ok(CryptoMode { crypto, h })
+    }
+
+    /// 加密并认证，返回密文 ∥ 认证标签
+    pub(crate) fn seal(&self, nonce: &[u8], aad: &[u8], plain: &[u8]) -> Result<Vec<u8>> {
+        let j0 = j0(nonce)?;
+        check_len(plain.len())?;
+        let mut out = self.ctr(j0, plain);
+        let tag = self.tag(j0, aad, &out);
+        out.extend_from_slice(&tag);
+        Ok(out)
+    }
+
+    /// 校验认证标签并解密
+    pub(crate) fn open(&self, nonce: &[u8], aad: &[u8], data: &[u8]) -> Result<Vec<u8>> {
+        let j0 = j0(nonce)?;
+        if data.len() < TAG_LEN {
+            return Err(Error::InvalidCipher("The data is shorter than the authentication tag."));
+        }
+        let (cipher, tag) = data.split_at(data.len() - TAG_LEN);
+        check_len(cipher.len())?;
+
+        if !ct_eq(&self.tag(j0, aad, cipher), tag) {
+            return Err(Error::InvalidCipher("The authentication tag does not match."));
+        }
+        Ok(self.ctr(j0, cipher))
+    }
+
+    /// 从inc32(J0)开始的计数器模式
+    fn ctr(&self, j0: u128, data: &[u8]) -> Vec<u8> {
+        let mut counter = j0;
+        let mut out = Vec::with_capacity(data.len());
+        for block in data.chunks(16) {
+            counter = inc32(counter);
+            let stream = self.crypto.encrypt(&counter.to_be_bytes());
+            out.extend(block.iter().zip(stream.iter()).map(|(a, b)| a ^ b));
+        }
+        out
+    }
+
+    /// T = E(K, J0) ⊕ GHASH(A ∥ 0* ∥ C ∥ 0* ∥ len(A) ∥ len(C))
+    fn tag(&self, j0: u128, aad: &[u8], cipher: &[u8]) -> [u8; 16] {
+        let mut y = 0u128;
+        for data in [aad, cipher] {
+            for block in data.chunks(16) {
+                let mut buf = [0u8; 16];
+                buf[..block.len()].copy_from_slice(block);
+                y = multiply(y ^ u128::from_be_bytes(buf), self.h);
+            }
+        }
+        let lengths = ((aad.len() as u128 * 8) << 64) | (cipher.len() as u128 * 8);
+        y = multiply(y ^ lengths, self.h);
+
+        let mask = u128::from_be_bytes(self.crypto.encrypt(&j0.to_be_bytes()));
+        (y ^ mask).to_be_bytes()
+    }
+}
+
+/// J0 = IV ∥ 0^31 ∥ 1
+fn j0(nonce: &[u8]) -> Result<u128> {
+    if nonce.len() != NONCE_LEN {
+        return Err(Error::InvalidIv);
+    }
+    let mut buf = [0u8; 16];
+    buf[..NONCE_LEN].copy_from_slice(nonce);
+    buf[15] = 1;
+    Ok(u128::from_be_bytes(buf))
+}
+
+/// 明文或密文不能超过[MAX_LEN]字节
+fn check_len(len: usize) -> Result<()> {
+    match len as u64 > MAX_LEN {
+        true => Err(Error::InvalidCipher("The data exceeds 2^32 - 2 blocks.")),
+        false => Ok(()),
+    }
+}
+
+/// 低32位加一
+fn inc32(counter: u128) -> u128 {
+    let low = (counter as u32).wrapping_add(1);
+    (counter & !0xffff_ffffu128) | low as u128
+}
+
+/// GF(2^128)上的乘法，约化多项式为x^128 + x^7 + x^2 + x + 1，比特序与GCM一致
+fn multiply(x: u128, y: u128) -> u128 {
+    const R: u128 = 0xe1 << 120;
+    let (mut z, mut v) = (0u128, y);
+    for i in 0..128 {
+        if x >> (127 - i) & 1 == 1 {
+            z ^= v;
+        }
+        v = if v & 1 == 1 { (v >> 1) ^ R } else { v >> 1 };
+    }
+    z
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// RFC 8998 附录A.1
+    #[test]
+    fn main() {
+        let key = hex::decode("0123456789abcdeffedcba9876543210").unwrap();
+        let nonce = hex::decode("00001234567800000000abcd").unwrap();
+        let aad = hex::decode("feedfacedeadbeeffeedfacedeadbeefabaddad2").unwrap();
+        let plain = hex::decode(
+            "aaaaaaaaaaaaaaaabbbbbbbbbbbbbbbbccccccccccccccccdddddddddddddddd\
+            eeeeeeeeeeeeeeeeffffffffffffffffeeeeeeeeeeeeeeeeaaaaaaaaaaaaaaaa"
+        ).unwrap();
+        let expected = "17f399f08c67d5ee19d0dc9969c4bb7d5fd46fd3756489069157b282bb200735\
+            d82710ca5c22f0ccfa7cbf93d496ac15a56834cbcf98c397b4024a2691233b8d\
+            83de3541e4c2b58177e065a9bf7b62ec";
+
+        let c = CryptoMode::new(&key).unwrap();
+        let cipher = c.seal(&nonce, &aad, &plain).unwrap();
+        assert_eq!(hex::encode(&cipher), expected);
+        assert_eq!(c.open(&nonce, &aad, &cipher).unwrap(), plain);
+
+        let mut tampered = cipher.clone();
+        tampered[0] ^= 1;
+        assert!(c.open(&nonce, &aad, &tampered).is_err());
+        assert!(c.open(&nonce, &aad[1..], &cipher).is_err());
+        assert!(c.open(&nonce[1..], &aad, &cipher).is_err());
+    }
+
+    #[test]
+    fn limit() {
+        assert!(check_len(0).is_ok());
+        assert!(check_len(MAX_LEN as usize).is_ok());
+        assert!(check_len(MAX_LEN as usize + 1).is_err());
+
+        // 第2^32 - 2个分组使用的计数器为J0的低32位加2^32 - 2，再过两个分组即回绕到J0
+        let j0 = j0(&[0xff; NONCE_LEN]).unwrap();
+        let last = (j0 & !0xffff_ffffu128) | 0xffff_ffff;
+        assert_eq!(inc32(inc32(last)), j0);
+    }
+}
@@ -0,0 +1,171 @@
+//! X.509证书（RFC 5280，GM/T 0015）、PKCS#10证书签名请求（RFC 2986）与OCSP（RFC 6960）
+//!
+//! 仅支持SM2公钥与SM2-with-SM3签名算法（1.2.156.10197.1.501），
+//! 验签时使用默认的用户身份标识1234567812345678。
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use yasna::models::{ObjectIdentifier, TaggedDerValue};
+use yasna::tags::{TAG_GENERALIZEDTIME, TAG_UTCTIME};
+use yasna::{ASN1Error, ASN1ErrorKind, BERReader, DERWriter};
+
+use crate::oid;
+
+pub use crate::x509::builder::{CertificateBuilder, RequestBuilder};
+pub use crate::x509::certificate::Certificate;
+pub use crate::x509::crl::{Crl, CrlBuilder, RevocationReason, RevokedCertificate};
+pub use crate::x509::extension::{BasicConstraints, Extension, GeneralName, KeyUsage};
+pub use crate::x509::name::Name;
+pub use crate::x509::ocsp::{CertId, CertStatus, OcspClient, OcspRequest, OcspResponder, OcspResponse, OcspResponseStatus, SingleResponse};
+pub use crate::x509::request::CertificateRequest;
+pub use crate::x509::trust::{RevocationCheck, TrustStore};
+
+mod builder;
+mod certificate;
+pub(crate) mod crl;
+mod extension;
+mod http;
+mod name;
+mod ocsp;
+mod request;
+mod trust;
+#[cfg(test)]
+pub(crate) mod fixtures;
+
+/// AlgorithmIdentifier ::= SEQUENCE { algorithm sm2sign-with-sm3 }，参数缺省
+pub(crate) fn write_signature_algorithm(writer: DERWriter) {
+    writer.write_sequence(|writer| {
+        writer.next().write_oid(&ObjectIdentifier::from_slice(oid::SM2_WITH_SM3));
+    })
+}
+
+/// 读取签名算法标识，返回是否为SM2-with-SM3，参数可以缺省或为NULL
+pub(crate) fn read_signature_algorithm(reader: BERReader) -> Result<bool, ASN1Error> {
+    reader.read_sequence(|reader| {
+        let algorithm = reader.next().read_oid()?;
+        reader.read_optional(|reader| reader.read_null())?;
+        Ok(algorithm == ObjectIdentifier::from_slice(oid::SM2_WITH_SM3))
+    })
+}
+
+/// Time ::= CHOICE { utcTime UTCTime, generalTime GeneralizedTime }
+///
+/// RFC 5280规定2049年及以前使用UTCTime（YYMMDDHHMMSSZ），之后使用GeneralizedTime（YYYYMMDDHHMMSSZ）。
+pub(crate) fn write_time(writer: DERWriter, time: SystemTime) {
+    let (year, text) = format_time(time);
+    let (tag, text) = if (1950..2050).contains(&year) {
+        (TAG_UTCTIME, format!("{:02}{}", year % 100, text))
+    } else {
+        (TAG_GENERALIZEDTIME, format!("{:04}{}", year, text))
+    };
+    writer.write_tagged_der(&TaggedDerValue::from_tag_and_bytes(tag, text.into_bytes()));
+}
+
+/// GeneralizedTime（YYYYMMDDHHMMSSZ），用于OCSP等只使用GeneralizedTime的结构
+pub(crate) fn write_generalized_time(writer: DERWriter, time: SystemTime) {
+    let (year, text) = format_time(time);
+    let text = format!("{:04}{}", year, text);
+    writer.write_tagged_der(&TaggedDerValue::from_tag_and_bytes(TAG_GENERALIZEDTIME, text.into_bytes()));
+}
+
+/// 返回年份以及MMDDHHMMSSZ
+fn format_time(time: SystemTime) -> (i64, String) {
+    let seconds = time.duration_since(UNIX_EPOCH).map(|e| e.as_secs()).unwrap_or(0);
+    let (days, seconds) = (seconds / 86400, seconds % 86400);
+    let (year, month, day) = civil_from_days(days as i64);
+    (year, format!("{:02}{:02}{:02}{:02}{:02}Z", month, day, seconds / 3600, seconds / 60 % 60, seconds % 60))
+}
+
+/// 读取UTCTime或GeneralizedTime，须为DER要求的以Z结尾、精确到秒的格式，UTCTime的年份50及以上表示19YY
+pub(crate) fn read_time(reader: BERReader) -> Result<SystemTime, ASN1Error> {
+    // 类型不符时不消耗数据，以便用于OPTIONAL的时间字段
+    if ![TAG_UTCTIME, TAG_GENERALIZEDTIME].contains(&reader.lookahead_tag()?) {
+        return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+    }
+    let value = reader.read_tagged_der()?;
+    let text = std::str::from_utf8(value.value()).map_err(|_| ASN1Error::new(ASN1ErrorKind::Invalid))?;
+
+    let (year, rest) = match value.tag() {
+        TAG_UTCTIME if text.len() == 13 => {
+            let year = number(&text[..2])?;
+            (if year < 50 { 2000 + year } else { 1900 + year }, &text[2..])
+        }
+        TAG_GENERALIZEDTIME if text.len() == 15 => (number(&text[..4])?, &text[4..]),
+        _ => return Err(ASN1Error::new(ASN1ErrorKind::Invalid)),
+    };
+    if !rest.ends_with('Z') {
+        return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+    }
+
+    let (month, day) = (number(&rest[0..2])?, number(&rest[2..4])?);
+    let (hour, minute, second) = (number(&rest[4..6])?, number(&rest[6..8])?, number(&rest[8..10])?);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 || second > 59 {
+        return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+    }
+
+    let days = days_from_civil(year as i64, month, day);
+    if days < 0 {
+        return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+    }
+    let seconds = days as u64 * 86400 + hour * 3600 + minute * 60 + second;
+    Ok(UNIX_EPOCH + Duration::from_secs(seconds))
+}
+
+fn number(text: &str) -> Result<u64, ASN1Error> {
+    if !text.bytes().all(|e| e.is_ascii_digit()) {
+        return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+    }
+    text.parse().map_err(|_| ASN1Error::new(ASN1ErrorKind::Invalid))
+}
+
+/// 公历日期距1970-01-01的天数
+fn days_from_civil(year: i64, month: u64, day: u64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let doy = (153 * ((month as i64 + 9) % 12) + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+
+
+/// 距1970-01-01的天数对应的公历日期
+fn civil_from_days(days: i64) -> (i64, u64, u64) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let doe = days - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u64;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u64;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn time() {
+        for (der, seconds) in [
+            ("170d3236313031363233303331395a", 1792191799u64),
+            ("170d3730303130313030303030305a", 0),
+            ("180f32303530303130313030303030305a", 2524608000),
+        ] {
+            let der = hex::decode(der).unwrap();
+            let time = yasna::parse_der(&der, read_time).unwrap();
+            assert_eq!(time, UNIX_EPOCH + Duration::from_secs(seconds));
+            assert_eq!(yasna::construct_der(|writer| write_time(writer, time)), der);
+        }
+
+        // 缺少秒、非UTC时区、月份越界
+        for text in ["2610162303Z", "261016230319+0800", "261316230319Z"] {
+            let der = yasna::construct_der(|writer| writer.write_tagged_der(&TaggedDerValue::from_tag_and_bytes(TAG_UTCTIME, text.as_bytes().to_vec())));
+            assert!(yasna::parse_der(&der, read_time).is_err());
+        }
+    }
+}
@@ -0,0 +1,376 @@
+use std::time::{Duration, SystemTime};
+
+use rand::RngCore;
+use yasna::Tag;
+
+use crate::error::{Error, Result};
+use crate::oid;
+use crate::sm2;
+use crate::sm2::ecc::Crypto;
+use crate::sm2::key::{HexKey, KeyPair, PublicKey};
+use crate::x509::extension::{encode_authority_key_identifier, encode_subject_key_identifier, key_identifier, write_extensions};
+use crate::x509::{write_signature_algorithm, write_time};
+use crate::x509::{BasicConstraints, Certificate, CertificateRequest, Extension, GeneralName, KeyUsage, Name};
+
+/// 默认有效期：365天
+const VALIDITY: Duration = Duration::from_secs(365 * 86400);
+
+/// X.509 v3证书的构造器
+///
+/// 默认使用16字节的随机序列号，有效期为当前时间起365天。
+/// 主体秘钥标识与签发者秘钥标识由SM3计算，签名算法为SM2-with-SM3。
+pub struct CertificateBuilder {
+    subject: Name,
+    public_key: PublicKey,
+    serial: Vec<u8>,
+    not_before: SystemTime,
+    not_after: SystemTime,
+    extensions: Vec<Extension>,
+    subject_alt_names: Option<Vec<GeneralName>>,
+}
+
+impl CertificateBuilder {
+    /// subject为主体名称，public_key为主体的SM2公钥
+    pub fn new(subject: Name, public_key: &str) -> Result<Self> {
+        let mut serial = vec![0u8; 16];
+        rand::thread_rng().fill_bytes(&mut serial);
+        // 最高位为0以保证是正数，次高位为1以保证长度固定
+        serial[0] = serial[0] & 0x7f | 0x40;
+
+        let now = SystemTime::now();
+        Ok(CertificateBuilder {
+            subject,
+            public_key: PublicKey::decode(public_key)?,
+            serial,
+            not_before: now,
+            not_after: now + VALIDITY,
+            extensions: vec![],
+            subject_alt_names: None,
+        })
+    }
+
+    /// 由证书签名请求构造，请求的签名须有效
+    ///
+    /// 只复制请求中的主体备用名称，其余扩展（基本约束、秘钥用途等）由申请者控制，一律忽略。
+    /// 基本约束默认为CA:false，签发CA证书或指定秘钥用途须显式调用[Self::with_basic_constraints]、[Self::with_key_usage]。
+    pub fn from_request(request: &CertificateRequest) -> Result<Self> {
+        if !request.verify()? {
+            return Err(Error::InvalidCertificate("The signature of the certificate request is invalid."));
+        }
+        let mut builder = CertificateBuilder::new(request.subject().clone(), &request.public_key()?)?
+            .with_basic_constraints(BasicConstraints { ca: false, path_len: None });
+        for extension in request.extensions() {
+            if extension.is(oid::SUBJECT_ALT_NAME) {
+                set_extension(&mut builder.extensions, extension.clone());
+            }
+        }
+        Ok(builder)
+    }
+
+    /// 大端序的正整数，长度不超过20字节
+    pub fn with_serial_number(mut self, serial: &[u8]) -> Self {
+        self.serial = serial.to_vec();
+        self
+    }
+
+    pub fn with_validity(mut self, not_before: SystemTime, not_after: SystemTime) -> Self {
+        self.not_before = not_before;
+        self.not_after = not_after;
+        self
+    }
+
+    pub fn with_basic_constraints(mut self, constraints: BasicConstraints) -> Self {
+        set_extension(&mut self.extensions, basic_constraints(constraints));
+        self
+    }
+
+    pub fn with_key_usage(mut self, usage: KeyUsage) -> Self {
+        set_extension(&mut self.extensions, key_usage(usage));
+        self
+    }
+
+    pub fn with_subject_alt_names(mut self, names: Vec<GeneralName>) -> Self {
+        self.subject_alt_names = Some(names);
+        self
+    }
+
+    /// 自签名：签发者为主体自身，private_key须与主体公钥匹配
+    pub fn self_sign(self, private_key: &str) -> Result<Certificate> {
+        let keypair = keypair(private_key, &self.public_key)?;
+        let issuer = self.subject.clone();
+        self.build(&issuer, None, keypair)
+    }
+
+    /// 由CA证书签发，issuer须为CA证书，issuer_private_key须与其公钥匹配
+    pub fn sign(self, issuer: &Certificate, issuer_private_key: &str) -> Result<Certificate> {
+        if !issuer.basic_constraints().map(|e| e.ca).unwrap_or(false) {
+            return Err(Error::InvalidCertificate("The issuer is not a CA certificate."));
+        }
+        if let Some(usage) = issuer.key_usage() {
+            if !usage.contains(KeyUsage::KEY_CERT_SIGN) {
+                return Err(Error::InvalidCertificate("The issuer is not allowed to sign certificates."));
+            }
+        }
+
+        let issuer_key = issuer.sm2_public_key()?;
+        let keypair = keypair(issuer_private_key, &issuer_key)?;
+        let id = match issuer.subject_key_identifier() {
+            Some(id) => id.to_vec(),
+            None => key_identifier(&issuer_key),
+        };
+        self.build(issuer.subject(), Some(id), keypair)
+    }
+
+    fn build(self, issuer: &Name, authority_key_identifier: Option<Vec<u8>>, keypair: KeyPair) -> Result<Certificate> {
+        if self.serial.is_empty() || self.serial.len() > 20 || self.serial.iter().all(|e| *e == 0) {
+            return Err(Error::InvalidCertificate("The serial number must be a positive integer of at most 20 bytes."));
+        }
+        if self.not_before > self.not_after {
+            return Err(Error::InvalidCertificate("The notBefore must not be later than the notAfter."));
+        }
+
+        let mut extensions = self.extensions;
+        if let Some(names) = &self.subject_alt_names {
+            set_extension(&mut extensions, subject_alt_names(&self.subject, names)?);
+        }
+        extensions.push(Extension::new(
+            oid::SUBJECT_KEY_IDENTIFIER, false, encode_subject_key_identifier(&key_identifier(&self.public_key)),
+        ));
+        if let Some(id) = authority_key_identifier {
+            extensions.push(Extension::new(oid::AUTHORITY_KEY_IDENTIFIER, false, encode_authority_key_identifier(&id)));
+        }
+
+        let tbs = yasna::construct_der(|writer| {
+            writer.write_sequence(|writer| {
+                writer.next().write_tagged(Tag::context(0), |writer| writer.write_u8(2));
+                writer.next().write_bigint_bytes(&self.serial, true);
+                write_signature_algorithm(writer.next());
+                issuer.write(writer.next());
+                writer.next().write_sequence(|writer| {
+                    write_time(writer.next(), self.not_before);
+                    write_time(writer.next(), self.not_after);
+                });
+                self.subject.write(writer.next());
+                writer.next().write_der(&self.public_key.encode_spki());
+                writer.next().write_tagged(Tag::context(3), |writer| write_extensions(writer, &extensions));
+            })
+        });
+        Certificate::from_der(&sign(tbs, keypair))
+    }
+}
+
+/// PKCS#10证书签名请求的构造器
+pub struct RequestBuilder {
+    subject: Name,
+    extensions: Vec<Extension>,
+    subject_alt_names: Option<Vec<GeneralName>>,
+}
+
+impl RequestBuilder {
+    pub fn new(subject: Name) -> Self {
+        RequestBuilder { subject, extensions: vec![], subject_alt_names: None }
+    }
+
+    pub fn with_basic_constraints(mut self, constraints: BasicConstraints) -> Self {
+        set_extension(&mut self.extensions, basic_constraints(constraints));
+        self
+    }
+
+    pub fn with_key_usage(mut self, usage: KeyUsage) -> Self {
+        set_extension(&mut self.extensions, key_usage(usage));
+        self
+    }
+
+    pub fn with_subject_alt_names(mut self, names: Vec<GeneralName>) -> Self {
+        self.subject_alt_names = Some(names);
+        self
+    }
+
+    /// 使用申请者的秘钥对签名，公钥写入请求中
+    pub fn sign(self, private_key: &str, public_key: &str) -> Result<CertificateRequest> {
+        let public_key = PublicKey::decode(public_key)?;
+        let keypair = keypair(private_key, &public_key)?;
+
+        let mut extensions = self.extensions;
+        if let Some(names) = &self.subject_alt_names {
+            set_extension(&mut extensions, subject_alt_names(&self.subject, names)?);
+        }
+
+        let info = yasna::construct_der(|writer| {
+            writer.write_sequence(|writer| {
+                writer.next().write_u8(0);
+                self.subject.write(writer.next());
+                writer.next().write_der(&public_key.encode_spki());
+                writer.next().write_tagged_implicit(Tag::context(0), |writer| {
+                    writer.write_set(|writer| {
+                        if extensions.is_empty() {
+                            return;
+                        }
+                        writer.next().write_sequence(|writer| {
+                            writer.next().write_oid(&yasna::models::ObjectIdentifier::from_slice(oid::EXTENSION_REQUEST));
+                            writer.next().write_set(|writer| write_extensions(writer.next(), &extensions));
+                        })
+                    })
+                });
+            })
+        });
+        CertificateRequest::from_der(&sign(info, keypair))
+    }
+}
+
+/// 添加扩展，替换已有的同一扩展
+fn set_extension(extensions: &mut Vec<Extension>, extension: Extension) {
+    match extensions.iter_mut().find(|e| e.oid() == extension.oid()) {
+        Some(e) => *e = extension,
+        None => extensions.push(extension),
+    }
+}
+
+/// CA证书的基本约束须为关键扩展
+fn basic_constraints(constraints: BasicConstraints) -> Extension {
+    Extension::new(oid::BASIC_CONSTRAINTS, constraints.ca, constraints.encode())
+}
+
+fn key_usage(usage: KeyUsage) -> Extension {
+    Extension::new(oid::KEY_USAGE, true, usage.encode())
+}
+
+/// 主体名称为空时，主体备用名称须为关键扩展
+fn subject_alt_names(subject: &Name, names: &[GeneralName]) -> Result<Extension> {
+    if names.is_empty() || !names.iter().all(GeneralName::is_valid) {
+        return Err(Error::InvalidCertificate("The subject alternative names must be non-empty and ASCII."));
+    }
+    Ok(Extension::new(oid::SUBJECT_ALT_NAME, subject.entries().is_empty(), GeneralName::encode_all(names)))
+}
+
+pub(crate) fn keypair(private_key: &str, public_key: &PublicKey) -> Result<KeyPair> {
+    let (prk, _) = sm2::decode_keypair(private_key, Some(&public_key.encode()))?;
+    Ok(KeyPair::new(prk, public_key.clone()))
+}
+
+/// SEQUENCE { tbs, signatureAlgorithm, signature BIT STRING }
+pub(crate) fn sign(tbs: Vec<u8>, keypair: KeyPair) -> Vec<u8> {
+    let signature = Crypto::default().signer(keypair).sign_bytes(&tbs).encode();
+    yasna::construct_der(|writer| {
+        writer.write_sequence(|writer| {
+            writer.next().write_der(&tbs);
+            write_signature_algorithm(writer.next());
+            writer.next().write_bitvec_bytes(&signature, signature.len() * 8);
+        })
+    })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv6Addr};
+    use std::time::UNIX_EPOCH;
+
+    use crate::x509::fixtures::{CA, CA_PRIVATE_KEY, CA_PUBLIC_KEY, LEAF_PRIVATE_KEY, LEAF_PUBLIC_KEY, ROOT, ROOT_PRIVATE_KEY};
+
+    use super::*;
+
+    #[test]
+    fn self_signed() {
+        let (prk, puk) = sm2::generate_keypair();
+        let subject = Name::new(&[("C", "CN"), ("O", "Yarism"), ("CN", "测试根CA")]).unwrap();
+        let (not_before, not_after) = (UNIX_EPOCH + Duration::from_secs(1792191799), UNIX_EPOCH + Duration::from_secs(2524608000));
+
+        let root = CertificateBuilder::new(subject.clone(), &puk).unwrap()
+            .with_serial_number(&[0x01])
+            .with_validity(not_before, not_after)
+            .with_basic_constraints(BasicConstraints { ca: true, path_len: Some(1) })
+            .with_key_usage(KeyUsage::KEY_CERT_SIGN | KeyUsage::CRL_SIGN)
+            .self_sign(&prk)
+            .unwrap();
+
+        let root = Certificate::from_pem(&root.to_pem()).unwrap();
+        assert_eq!(root.serial_number(), "01");
+        assert_eq!(root.subject(), &subject);
+        assert_eq!(root.subject().to_string(), "C=CN, O=Yarism, CN=测试根CA");
+        assert_eq!((root.not_before(), root.not_after()), (not_before, not_after));
+        assert_eq!(root.public_key().unwrap(), puk);
+        assert_eq!(root.subject_key_identifier(), Some(key_identifier(&PublicKey::decode(&puk).unwrap()).as_slice()));
+        assert_eq!(root.authority_key_identifier(), None);
+        assert!(root.extensions().iter().all(|e| !e.is(oid::BASIC_CONSTRAINTS) || e.critical()));
+        assert!(root.verify_issued_by(&root).unwrap());
+
+        // 私钥与公钥不匹配
+        let (other, _) = sm2::generate_keypair();
+        assert!(CertificateBuilder::new(subject, &puk).unwrap().self_sign(&other).is_err());
+    }
+
+    #[test]
+    fn issue() {
+        // 使用根CA重新签发中间CA证书
+        let root = Certificate::from_pem(ROOT).unwrap();
+        let ca = CertificateBuilder::new(Certificate::from_pem(CA).unwrap().subject().clone(), CA_PUBLIC_KEY).unwrap()
+            .with_basic_constraints(BasicConstraints { ca: true, path_len: Some(0) })
+            .with_key_usage(KeyUsage::KEY_CERT_SIGN | KeyUsage::CRL_SIGN)
+            .sign(&root, ROOT_PRIVATE_KEY)
+            .unwrap();
+        assert!(ca.verify_issued_by(&root).unwrap());
+        assert_eq!(ca.authority_key_identifier(), root.subject_key_identifier());
+        let subject = Name::new(&[("C", "CN"), ("O", "Yarism"), ("CN", "api.example.com")]).unwrap();
+        let names = vec![
+            GeneralName::Dns("api.example.com".to_string()),
+            GeneralName::Ip(IpAddr::V6(Ipv6Addr::LOCALHOST)),
+            GeneralName::Uri("https://api.example.com/".to_string()),
+        ];
+
+        let request = RequestBuilder::new(subject.clone())
+            .with_subject_alt_names(names.clone())
+            .with_key_usage(KeyUsage::DIGITAL_SIGNATURE)
+            .sign(LEAF_PRIVATE_KEY, LEAF_PUBLIC_KEY)
+            .unwrap();
+        let request = CertificateRequest::from_pem(&request.to_pem()).unwrap();
+        assert!(request.verify().unwrap());
+        assert_eq!(request.subject(), &subject);
+        assert_eq!(request.public_key().unwrap(), LEAF_PUBLIC_KEY);
+        assert_eq!(request.subject_alt_names(), names.as_slice());
+
+        let leaf = CertificateBuilder::from_request(&request).unwrap()
+            .with_basic_constraints(BasicConstraints { ca: false, path_len: None })
+            .with_key_usage(KeyUsage::DIGITAL_SIGNATURE | KeyUsage::KEY_AGREEMENT)
+            .sign(&ca, CA_PRIVATE_KEY)
+            .unwrap();
+
+        assert_eq!(leaf.version(), 3);
+        assert_eq!(leaf.serial_number().len(), 32);
+        assert_eq!(leaf.issuer(), ca.subject());
+        assert_eq!(leaf.subject(), &subject);
+        assert_eq!(leaf.subject_alt_names(), names.as_slice());
+        assert_eq!(leaf.key_usage(), Some(KeyUsage::DIGITAL_SIGNATURE | KeyUsage::KEY_AGREEMENT));
+        assert_eq!(leaf.basic_constraints(), Some(BasicConstraints { ca: false, path_len: None }));
+        assert_eq!(leaf.authority_key_identifier(), ca.subject_key_identifier());
+        assert!(leaf.verify_issued_by(&ca).unwrap());
+
+        // 终端实体证书不能签发证书
+        let result = CertificateBuilder::new(subject.clone(), LEAF_PUBLIC_KEY).unwrap().sign(&leaf, LEAF_PRIVATE_KEY);
+        assert!(matches!(result, Err(Error::InvalidCertificate(_))));
+
+        // 请求中的基本约束与秘钥用途不会被复制，申请者无法借此获得CA证书
+        let request = RequestBuilder::new(subject)
+            .with_basic_constraints(BasicConstraints { ca: true, path_len: None })
+            .with_key_usage(KeyUsage::KEY_CERT_SIGN)
+            .sign(LEAF_PRIVATE_KEY, LEAF_PUBLIC_KEY)
+            .unwrap();
+        let leaf = CertificateBuilder::from_request(&request).unwrap().sign(&ca, CA_PRIVATE_KEY).unwrap();
+        assert_eq!(leaf.basic_constraints(), Some(BasicConstraints { ca: false, path_len: None }));
+        assert_eq!(leaf.key_usage(), None);
+    }
+
+    #[test]
+    fn invalid() {
+        let subject = Name::new(&[("CN", "example")]).unwrap();
+        let builder = || CertificateBuilder::new(subject.clone(), LEAF_PUBLIC_KEY).unwrap();
+
+        assert!(builder().with_serial_number(&[0; 4]).self_sign(LEAF_PRIVATE_KEY).is_err());
+        assert!(builder().with_serial_number(&[1; 21]).self_sign(LEAF_PRIVATE_KEY).is_err());
+        assert!(builder().with_validity(SystemTime::now(), UNIX_EPOCH).self_sign(LEAF_PRIVATE_KEY).is_err());
+        assert!(builder().with_subject_alt_names(vec![GeneralName::Dns("例子.com".to_string())]).self_sign(LEAF_PRIVATE_KEY).is_err());
+
+        assert!(Name::new(&[("C", "中国")]).is_err());
+        assert!(Name::new(&[("X", "unknown")]).is_err());
+    }
+}
@@ -0,0 +1,373 @@
+use std::time::SystemTime;
+
+use yasna::{ASN1Error, ASN1ErrorKind, BERReader, Tag};
+
+use crate::error::{Error, Result};
+use crate::oid;
+use crate::pem;
+use crate::sm2::ecc::{Crypto, Signature};
+use crate::sm2::key::{HexKey, PublicKey};
+use crate::x509::extension::{decode_authority_key_identifier, decode_subject_key_identifier};
+use crate::x509::{read_signature_algorithm, read_time, BasicConstraints, Extension, GeneralName, KeyUsage, Name};
+
+/// X.509证书
+///
+/// Certificate ::= SEQUENCE {
+///     tbsCertificate       TBSCertificate,
+///     signatureAlgorithm   AlgorithmIdentifier,
+///     signatureValue       BIT STRING
+/// }
+///
+/// 解析时校验结构以及常用扩展的编码，签名由调用方使用签发者的公钥验证。
+#[derive(Clone, Debug)]
+pub struct Certificate {
+    der: Vec<u8>,
+    /// 签名覆盖的TBSCertificate的DER编码
+    tbs: Vec<u8>,
+    version: u8,
+    serial: Vec<u8>,
+    sm2_with_sm3: bool,
+    issuer: Name,
+    subject: Name,
+    not_before: SystemTime,
+    not_after: SystemTime,
+    spki: Vec<u8>,
+    extensions: Vec<Extension>,
+    key_usage: Option<KeyUsage>,
+    basic_constraints: Option<BasicConstraints>,
+    subject_alt_names: Vec<GeneralName>,
+    subject_key_identifier: Option<Vec<u8>>,
+    authority_key_identifier: Option<Vec<u8>>,
+    signature: Vec<u8>,
+}
+
+impl Certificate {
+    /// 解析DER编码的证书
+    pub fn from_der(der: &[u8]) -> Result<Certificate> {
+        let (tbs, outer, signature) = yasna::parse_der(der, |reader| {
+            reader.read_sequence(|reader| {
+                let tbs = reader.next().read_der()?;
+                let algorithm = reader.next().read_der()?;
+                let (signature, bits) = reader.next().read_bitvec_bytes()?;
+                if bits != signature.len() * 8 {
+                    return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+                }
+                Ok((tbs, algorithm, signature))
+            })
+        }).map_err(|_| Error::InvalidDer("The data is not a DER-encoded certificate."))?;
+
+        let (mut certificate, inner) = yasna::parse_der(&tbs, |reader| read_tbs(reader, der, &tbs, &signature))
+            .map_err(|_| Error::InvalidDer("The data is not a DER-encoded TBSCertificate."))?;
+
+        // 外层的签名算法须与TBSCertificate中的一致
+        if inner != outer {
+            return Err(Error::InvalidCertificate("The signature algorithm does not match the one in the TBSCertificate."));
+        }
+        certificate.sm2_with_sm3 = yasna::parse_der(&outer, read_signature_algorithm)
+            .map_err(|_| Error::InvalidDer("The signature algorithm is not a DER-encoded AlgorithmIdentifier."))?;
+
+        certificate.decode_extensions()?;
+        Ok(certificate)
+    }
+
+    /// 解析PEM编码的证书（CERTIFICATE）
+    pub fn from_pem(pem: &str) -> Result<Certificate> {
+        Certificate::from_der(&pem::decode("CERTIFICATE", pem)?)
+    }
+
+    pub fn to_der(&self) -> Vec<u8> {
+        self.der.clone()
+    }
+
+    pub fn to_pem(&self) -> String {
+        pem::encode("CERTIFICATE", &self.der)
+    }
+
+    /// 证书版本，v1、v2、v3分别为1、2、3
+    pub fn version(&self) -> u8 {
+        self.version + 1
+    }
+
+    /// 十六进制编码的序列号
+    pub fn serial_number(&self) -> String {
+        hex::encode(&self.serial)
+    }
+
+    /// 序列号INTEGER的内容字节
+    pub(crate) fn serial(&self) -> &[u8] {
+        &self.serial
+    }
+
+    pub fn issuer(&self) -> &Name {
+        &self.issuer
+    }
+
+    pub fn subject(&self) -> &Name {
+        &self.subject
+    }
+
+    pub fn not_before(&self) -> SystemTime {
+        self.not_before
+    }
+
+    pub fn not_after(&self) -> SystemTime {
+        self.not_after
+    }
+
+    /// time是否在有效期\[notBefore, notAfter]内
+    pub fn is_valid_at(&self, time: SystemTime) -> bool {
+        self.not_before <= time && time <= self.not_after
+    }
+
+    /// 证书中的SM2公钥，非压缩格式
+    pub fn public_key(&self) -> Result<String> {
+        Ok(self.sm2_public_key()?.encode())
+    }
+
+    /// DER编码的SubjectPublicKeyInfo
+    pub fn public_key_info(&self) -> Vec<u8> {
+        self.spki.clone()
+    }
+
+    pub fn extensions(&self) -> &[Extension] {
+        &self.extensions
+    }
+
+    pub fn key_usage(&self) -> Option<KeyUsage> {
+        self.key_usage
+    }
+
+    pub fn basic_constraints(&self) -> Option<BasicConstraints> {
+        self.basic_constraints
+    }
+
+    pub fn subject_alt_names(&self) -> &[GeneralName] {
+        &self.subject_alt_names
+    }
+
+    pub fn subject_key_identifier(&self) -> Option<&[u8]> {
+        self.subject_key_identifier.as_deref()
+    }
+
+    pub fn authority_key_identifier(&self) -> Option<&[u8]> {
+        self.authority_key_identifier.as_deref()
+    }
+
+    /// 是否为自签发证书：签发者与主体的名称相同
+    pub fn is_self_issued(&self) -> bool {
+        self.issuer == self.subject
+    }
+
+    /// 使用签发者的SM2公钥验证证书的签名，签名算法须为SM2-with-SM3，用户身份标识为默认值
+    pub fn verify(&self, issuer_public_key: &str) -> Result<bool> {
+        self.verify_with(&PublicKey::decode(issuer_public_key)?)
+    }
+
+    /// 验证证书由issuer签发：签发者名称与issuer的主体名称一致，且签名能用issuer的公钥验证
+    pub fn verify_issued_by(&self, issuer: &Certificate) -> Result<bool> {
+        if self.issuer != issuer.subject {
+            return Ok(false);
+        }
+        self.verify_with(&issuer.sm2_public_key()?)
+    }
+
+    pub(crate) fn sm2_public_key(&self) -> Result<PublicKey> {
+        PublicKey::decode_spki(&self.spki)
+            .map_err(|_| Error::InvalidCertificate("The subject public key is not a valid SM2 public key."))
+    }
+
+    /// SubjectPublicKeyInfo中subjectPublicKey的内容（不含未使用比特数）
+    pub(crate) fn public_key_bits(&self) -> Vec<u8> {
+        yasna::parse_der(&self.spki, |reader| {
+            reader.read_sequence(|reader| {
+                reader.next().read_der()?;
+                Ok(reader.next().read_bitvec_bytes()?.0)
+            })
+        }).unwrap_or_default()
+    }
+
+    pub(crate) fn verify_with(&self, key: &PublicKey) -> Result<bool> {
+        if !self.sm2_with_sm3 {
+            return Err(Error::InvalidCertificate("The signature algorithm is not SM2-with-SM3."));
+        }
+        let signature = Signature::decode(&self.signature)?;
+        Ok(Crypto::default().verifier(key.clone()).verify_bytes(&self.tbs, &signature))
+    }
+
+    /// 解码常用扩展，同一扩展不能出现多次
+    fn decode_extensions(&mut self) -> Result<()> {
+        for (i, extension) in self.extensions.iter().enumerate() {
+            if self.extensions[..i].iter().any(|e| e.oid() == extension.oid()) {
+                return Err(Error::InvalidCertificate("The certificate contains duplicate extensions."));
+            }
+
+            let value = extension.value();
+            let decoded = if extension.is(oid::KEY_USAGE) {
+                KeyUsage::decode(value).map(|e| self.key_usage = Some(e))
+            } else if extension.is(oid::BASIC_CONSTRAINTS) {
+                BasicConstraints::decode(value).map(|e| self.basic_constraints = Some(e))
+            } else if extension.is(oid::SUBJECT_ALT_NAME) {
+                GeneralName::decode_all(value).map(|e| self.subject_alt_names = e)
+            } else if extension.is(oid::SUBJECT_KEY_IDENTIFIER) {
+                decode_subject_key_identifier(value).map(|e| self.subject_key_identifier = Some(e))
+            } else if extension.is(oid::AUTHORITY_KEY_IDENTIFIER) {
+                decode_authority_key_identifier(value).map(|e| self.authority_key_identifier = e)
+            } else {
+                Ok(())
+            };
+            decoded.map_err(|_| Error::InvalidCertificate("The certificate contains a malformed extension."))?;
+        }
+        Ok(())
+    }
+}
+
+/// TBSCertificate ::= SEQUENCE {
+///     version          \[0] EXPLICIT Version DEFAULT v1,
+///     serialNumber         CertificateSerialNumber,
+///     signature            AlgorithmIdentifier,
+///     issuer               Name,
+///     validity             Validity,
+///     subject              Name,
+///     subjectPublicKeyInfo SubjectPublicKeyInfo,
+///     issuerUniqueID   \[1] IMPLICIT UniqueIdentifier OPTIONAL,
+///     subjectUniqueID  \[2] IMPLICIT UniqueIdentifier OPTIONAL,
+///     extensions       \[3] EXPLICIT Extensions OPTIONAL
+/// }
+///
+/// 同时返回签名算法标识的DER编码。
+fn read_tbs(reader: BERReader, der: &[u8], tbs: &[u8], signature: &[u8]) -> std::result::Result<(Certificate, Vec<u8>), ASN1Error> {
+    reader.read_sequence(|reader| {
+        let version = reader.read_default(0, |reader| reader.read_tagged(Tag::context(0), |reader| reader.read_u8()))?;
+        if version > 2 {
+            return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+        }
+        let (serial, _) = reader.next().read_bigint_bytes()?;
+        let algorithm = reader.next().read_der()?;
+        let issuer = Name::read(reader.next())?;
+        let (not_before, not_after) = reader.next().read_sequence(|reader| {
+            Ok((read_time(reader.next())?, read_time(reader.next())?))
+        })?;
+        let subject = Name::read(reader.next())?;
+        let spki = reader.next().read_der()?;
+        reader.read_optional(|reader| reader.read_tagged_implicit(Tag::context(1), |reader| reader.read_bitvec_bytes()))?;
+        reader.read_optional(|reader| reader.read_tagged_implicit(Tag::context(2), |reader| reader.read_bitvec_bytes()))?;
+        let extensions = reader.read_optional(|reader| {
+            reader.read_tagged(Tag::context(3), |reader| reader.collect_sequence_of(Extension::read))
+        })?.unwrap_or_default();
+
+        let certificate = Certificate {
+            der: der.to_vec(),
+            tbs: tbs.to_vec(),
+            version,
+            serial,
+            sm2_with_sm3: false,
+            issuer,
+            subject,
+            not_before,
+            not_after,
+            spki,
+            extensions,
+            key_usage: None,
+            basic_constraints: None,
+            subject_alt_names: vec![],
+            subject_key_identifier: None,
+            authority_key_identifier: None,
+            signature: signature.to_vec(),
+        };
+        Ok((certificate, algorithm))
+    })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr};
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use crate::x509::fixtures::{CA, CA_PUBLIC_KEY, LEAF, LEAF_PUBLIC_KEY, ROOT, ROOT_PUBLIC_KEY};
+
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let leaf = Certificate::from_pem(LEAF).unwrap();
+        assert_eq!(leaf.version(), 3);
+        assert_eq!(leaf.serial_number(), "0123456789abcdef");
+        assert_eq!(leaf.issuer().to_string(), "C=CN, O=Yarism, CN=Yarism Issuing CA");
+        assert_eq!(leaf.subject().common_name(), Some("www.example.com"));
+        assert_eq!(leaf.subject().organization(), Some("Yarism"));
+        assert_eq!(leaf.public_key().unwrap(), LEAF_PUBLIC_KEY);
+        assert_eq!(leaf.to_pem(), LEAF);
+
+        // 2026-10-16 23:03:19 至 2029-01-18 23:03:19
+        assert_eq!(leaf.not_before(), UNIX_EPOCH + Duration::from_secs(1792191799));
+        assert_eq!(leaf.not_after(), UNIX_EPOCH + Duration::from_secs(1863471799));
+        assert!(leaf.is_valid_at(leaf.not_before()));
+        assert!(!leaf.is_valid_at(leaf.not_after() + Duration::from_secs(1)));
+
+        let usage = leaf.key_usage().unwrap();
+        assert!(usage.contains(KeyUsage::DIGITAL_SIGNATURE | KeyUsage::KEY_ENCIPHERMENT));
+        assert!(!usage.contains(KeyUsage::KEY_CERT_SIGN));
+        assert_eq!(leaf.basic_constraints(), Some(BasicConstraints { ca: false, path_len: None }));
+        assert_eq!(leaf.subject_alt_names(), &[
+            GeneralName::Dns("www.example.com".to_string()),
+            GeneralName::Dns("example.com".to_string()),
+            GeneralName::Ip(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1))),
+            GeneralName::Email("admin@example.com".to_string()),
+        ]);
+        assert!(leaf.extensions().iter().any(|e| e.oid() == "2.5.29.15" && e.critical()));
+
+        let ca = Certificate::from_pem(CA).unwrap();
+        assert_eq!(ca.basic_constraints(), Some(BasicConstraints { ca: true, path_len: Some(0) }));
+        assert_eq!(ca.public_key().unwrap(), CA_PUBLIC_KEY);
+        assert_eq!(leaf.authority_key_identifier(), ca.subject_key_identifier());
+
+        let root = Certificate::from_pem(ROOT).unwrap();
+        assert!(root.is_self_issued());
+        assert_eq!(root.key_usage(), Some(KeyUsage::KEY_CERT_SIGN | KeyUsage::CRL_SIGN));
+        assert_eq!(hex::encode(root.subject_key_identifier().unwrap()), "bd9674997f5976aab5be44201ddb59b6cd71da08");
+        assert_eq!(ca.authority_key_identifier(), root.subject_key_identifier());
+    }
+
+    #[test]
+    fn verify() {
+        let (root, ca, leaf) = (
+            Certificate::from_pem(ROOT).unwrap(),
+            Certificate::from_pem(CA).unwrap(),
+            Certificate::from_pem(LEAF).unwrap(),
+        );
+
+        assert!(root.verify(ROOT_PUBLIC_KEY).unwrap());
+        assert!(ca.verify(ROOT_PUBLIC_KEY).unwrap());
+        assert!(leaf.verify(CA_PUBLIC_KEY).unwrap());
+        assert!(!leaf.verify(ROOT_PUBLIC_KEY).unwrap());
+
+        assert!(root.verify_issued_by(&root).unwrap());
+        assert!(ca.verify_issued_by(&root).unwrap());
+        assert!(leaf.verify_issued_by(&ca).unwrap());
+        assert!(!leaf.verify_issued_by(&root).unwrap());
+
+        // 篡改TBSCertificate中的主体名称
+        let mut der = leaf.to_der();
+        let at = der.windows(3).position(|e| e == b"www").unwrap();
+        der[at] = b'W';
+        let tampered = Certificate::from_der(&der).unwrap();
+        assert!(!tampered.verify(CA_PUBLIC_KEY).unwrap());
+    }
+
+    #[test]
+    fn invalid() {
+        let der = Certificate::from_pem(LEAF).unwrap().to_der();
+        assert!(matches!(Certificate::from_der(&der[..der.len() - 1]), Err(Error::InvalidDer(_))));
+        assert!(matches!(Certificate::from_der(&[der.clone(), vec![0]].concat()), Err(Error::InvalidDer(_))));
+        assert!(Certificate::from_pem("-----BEGIN CERTIFICATE-----\n-----END CERTIFICATE-----\n").is_err());
+
+        // 外层签名算法改为sm3WithSM2以外的OID（1.2.156.10197.1.502），签名算法在TBSCertificate中也出现一次，外层的在其后
+        let mut der = der;
+        let algorithm = hex::decode("300a06082a811ccf55018375").unwrap();
+        let outer = der.windows(algorithm.len()).rposition(|e| e == algorithm).unwrap();
+        assert!(der.windows(algorithm.len()).position(|e| e == algorithm).unwrap() < outer);
+        der[outer + 11] = 0x76;
+        assert!(matches!(Certificate::from_der(&der), Err(Error::InvalidCertificate(_))));
+    }
+}
@@ -0,0 +1,553 @@
+use std::time::{Duration, SystemTime};
+
+use yasna::{ASN1Error, ASN1ErrorKind, BERReader, DERWriter, Tag};
+
+use crate::error::{Error, Result};
+use crate::oid;
+use crate::pem;
+use crate::sm2::ecc::{Crypto, Signature};
+use crate::sm2::key::{HexKey, PublicKey};
+use crate::x509::builder::{keypair, sign};
+use crate::x509::extension::{decode_authority_key_identifier, encode_authority_key_identifier, key_identifier, write_extensions};
+use crate::x509::{read_signature_algorithm, read_time, write_signature_algorithm, write_time};
+use crate::x509::{Certificate, Extension, KeyUsage, Name, RevocationCheck};
+
+/// 默认的下次更新时间：7天后
+const NEXT_UPDATE: Duration = Duration::from_secs(7 * 86400);
+
+/// 撤销原因（CRLReason，RFC 5280 5.3.1）
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RevocationReason {
+    Unspecified = 0,
+    KeyCompromise = 1,
+    CaCompromise = 2,
+    AffiliationChanged = 3,
+    Superseded = 4,
+    CessationOfOperation = 5,
+    CertificateHold = 6,
+    RemoveFromCrl = 8,
+    PrivilegeWithdrawn = 9,
+    AaCompromise = 10,
+}
+
+impl RevocationReason {
+    pub(crate) fn from_code(code: i64) -> Option<Self> {
+        Some(match code {
+            0 => RevocationReason::Unspecified,
+            1 => RevocationReason::KeyCompromise,
+            2 => RevocationReason::CaCompromise,
+            3 => RevocationReason::AffiliationChanged,
+            4 => RevocationReason::Superseded,
+            5 => RevocationReason::CessationOfOperation,
+            6 => RevocationReason::CertificateHold,
+            8 => RevocationReason::RemoveFromCrl,
+            9 => RevocationReason::PrivilegeWithdrawn,
+            10 => RevocationReason::AaCompromise,
+            _ => return None,
+        })
+    }
+}
+
+/// CRL中的一个被撤销证书条目
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RevokedCertificate {
+    serial: Vec<u8>,
+    date: SystemTime,
+    reason: Option<RevocationReason>,
+}
+
+impl RevokedCertificate {
+    /// 十六进制编码的序列号
+    pub fn serial_number(&self) -> String {
+        hex::encode(&self.serial)
+    }
+
+    pub(crate) fn serial(&self) -> &[u8] {
+        &self.serial
+    }
+
+    pub fn revocation_date(&self) -> SystemTime {
+        self.date
+    }
+
+    pub fn reason(&self) -> Option<RevocationReason> {
+        self.reason
+    }
+
+    /// revokedCertificates ::= SEQUENCE {
+    ///     userCertificate     CertificateSerialNumber,
+    ///     revocationDate      Time,
+    ///     crlEntryExtensions  Extensions OPTIONAL
+    /// }
+    fn read(reader: BERReader) -> std::result::Result<RevokedCertificate, ASN1Error> {
+        reader.read_sequence(|reader| {
+            let (serial, _) = reader.next().read_bigint_bytes()?;
+            let date = read_time(reader.next())?;
+            let extensions = reader.read_optional(|reader| reader.collect_sequence_of(Extension::read))?.unwrap_or_default();
+
+            let mut reason = None;
+            for extension in extensions.iter() {
+                if extension.is(oid::CRL_REASON) {
+                    let code = yasna::parse_der(extension.value(), |reader| reader.read_enum())?;
+                    reason = Some(RevocationReason::from_code(code).ok_or(ASN1Error::new(ASN1ErrorKind::Invalid))?);
+                } else if extension.critical() {
+                    return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+                }
+            }
+            Ok(RevokedCertificate { serial, date, reason })
+        })
+    }
+
+    fn write(&self, writer: DERWriter) {
+        writer.write_sequence(|writer| {
+            writer.next().write_bigint_bytes(&self.serial, true);
+            write_time(writer.next(), self.date);
+            if let Some(reason) = self.reason {
+                let value = yasna::construct_der(|writer| writer.write_enum(reason as i64));
+                write_extensions(writer.next(), &[Extension::new(oid::CRL_REASON, false, value)]);
+            }
+        })
+    }
+}
+
+/// X.509 v2证书撤销列表
+///
+/// CertificateList ::= SEQUENCE {
+///     tbsCertList          TBSCertList,
+///     signatureAlgorithm   AlgorithmIdentifier,
+///     signatureValue       BIT STRING
+/// }
+#[derive(Clone, Debug)]
+pub struct Crl {
+    der: Vec<u8>,
+    tbs: Vec<u8>,
+    sm2_with_sm3: bool,
+    issuer: Name,
+    this_update: SystemTime,
+    next_update: Option<SystemTime>,
+    revoked: Vec<RevokedCertificate>,
+    extensions: Vec<Extension>,
+    number: Option<Vec<u8>>,
+    authority_key_identifier: Option<Vec<u8>>,
+    signature: Vec<u8>,
+}
+
+impl Crl {
+    /// 解析DER编码的CRL
+    pub fn from_der(der: &[u8]) -> Result<Crl> {
+        let (tbs, outer, signature) = yasna::parse_der(der, |reader| {
+            reader.read_sequence(|reader| {
+                let tbs = reader.next().read_der()?;
+                let algorithm = reader.next().read_der()?;
+                let (signature, bits) = reader.next().read_bitvec_bytes()?;
+                if bits != signature.len() * 8 {
+                    return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+                }
+                Ok((tbs, algorithm, signature))
+            })
+        }).map_err(|_| Error::InvalidDer("The data is not a DER-encoded CRL."))?;
+
+        let (mut crl, inner) = yasna::parse_der(&tbs, |reader| read_tbs(reader, der, &tbs, &signature))
+            .map_err(|_| Error::InvalidDer("The data is not a DER-encoded TBSCertList."))?;
+        if inner != outer {
+            return Err(Error::InvalidCertificate("The signature algorithm does not match the one in the TBSCertList."));
+        }
+        crl.sm2_with_sm3 = yasna::parse_der(&outer, read_signature_algorithm)
+            .map_err(|_| Error::InvalidDer("The signature algorithm is not a DER-encoded AlgorithmIdentifier."))?;
+
+        for extension in crl.extensions.iter() {
+            let decoded = if extension.is(oid::CRL_NUMBER) {
+                yasna::parse_der(extension.value(), |reader| reader.read_bigint_bytes()).map(|(e, _)| crl.number = Some(e))
+            } else if extension.is(oid::AUTHORITY_KEY_IDENTIFIER) {
+                decode_authority_key_identifier(extension.value()).map(|e| crl.authority_key_identifier = e)
+            } else {
+                Ok(())
+            };
+            decoded.map_err(|_| Error::InvalidCertificate("The CRL contains a malformed extension."))?;
+        }
+        Ok(crl)
+    }
+
+    /// 解析PEM编码的CRL（X509 CRL）
+    pub fn from_pem(pem: &str) -> Result<Crl> {
+        Crl::from_der(&pem::decode("X509 CRL", pem)?)
+    }
+
+    pub fn to_der(&self) -> Vec<u8> {
+        self.der.clone()
+    }
+
+    pub fn to_pem(&self) -> String {
+        pem::encode("X509 CRL", &self.der)
+    }
+
+    pub fn issuer(&self) -> &Name {
+        &self.issuer
+    }
+
+    pub fn this_update(&self) -> SystemTime {
+        self.this_update
+    }
+
+    pub fn next_update(&self) -> Option<SystemTime> {
+        self.next_update
+    }
+
+    /// 十六进制编码的CRL编号
+    pub fn number(&self) -> Option<String> {
+        self.number.as_ref().map(hex::encode)
+    }
+
+    pub fn authority_key_identifier(&self) -> Option<&[u8]> {
+        self.authority_key_identifier.as_deref()
+    }
+
+    pub fn extensions(&self) -> &[Extension] {
+        &self.extensions
+    }
+
+    pub fn revoked_certificates(&self) -> &[RevokedCertificate] {
+        &self.revoked
+    }
+
+    /// 查找证书的撤销条目，仅比较序列号，调用方须确认CRL的签发者与证书的签发者一致
+    pub fn revoked(&self, certificate: &Certificate) -> Option<&RevokedCertificate> {
+        let serial = trim(certificate.serial());
+        self.revoked.iter().find(|e| trim(&e.serial) == serial)
+    }
+
+    /// 使用签发者的SM2公钥验证CRL的签名
+    pub fn verify(&self, issuer_public_key: &str) -> Result<bool> {
+        self.verify_with(&PublicKey::decode(issuer_public_key)?)
+    }
+
+    /// 验证CRL由issuer签发：签发者名称一致，且签名能用issuer的公钥验证
+    pub fn verify_issued_by(&self, issuer: &Certificate) -> Result<bool> {
+        if self.issuer != *issuer.subject() {
+            return Ok(false);
+        }
+        self.verify_with(&issuer.sm2_public_key()?)
+    }
+
+    fn verify_with(&self, key: &PublicKey) -> Result<bool> {
+        if !self.sm2_with_sm3 {
+            return Err(Error::InvalidCertificate("The signature algorithm is not SM2-with-SM3."));
+        }
+        let signature = Signature::decode(&self.signature)?;
+        Ok(Crypto::default().verifier(key.clone()).verify_bytes(&self.tbs, &signature))
+    }
+}
+
+impl RevocationCheck for Crl {
+    /// CRL是否适用于issuer签发的证书：签发者名称一致，且秘钥标识（若均存在）一致
+    fn covers(&self, issuer: &Certificate) -> bool {
+        if self.issuer != *issuer.subject() {
+            return false;
+        }
+        match (self.authority_key_identifier(), issuer.subject_key_identifier()) {
+            (Some(aki), Some(ski)) => aki == ski,
+            _ => true,
+        }
+    }
+
+    /// CRL须适用于该签发者、由签发者签名、签发者须为有cRLSign用途的CA证书且CRL在time时刻有效
+    fn check(&self, certificate: &Certificate, issuer: &Certificate, time: SystemTime) -> Result<()> {
+        if certificate.issuer() != issuer.subject() || !self.covers(issuer) {
+            return Err(Error::CertificatePath("The CRL does not cover the issuer of the certificate."));
+        }
+        if !can_sign_crl(issuer) {
+            return Err(Error::CertificatePath("The issuer is not allowed to sign CRLs."));
+        }
+        if !self.verify_issued_by(issuer)? {
+            return Err(Error::CertificatePath("The signature of the CRL is invalid."));
+        }
+        if time < self.this_update || self.next_update.map(|e| e < time).unwrap_or(false) {
+            return Err(Error::CertificatePath("The CRL is expired or not yet valid."));
+        }
+        if self.extensions.iter().any(|e| e.critical() && !e.is(oid::CRL_NUMBER) && !e.is(oid::AUTHORITY_KEY_IDENTIFIER)) {
+            return Err(Error::CertificatePath("The CRL contains an unrecognized critical extension."));
+        }
+
+        match self.revoked(certificate) {
+            Some(entry) if entry.date <= time && entry.reason != Some(RevocationReason::RemoveFromCrl) => {
+                Err(Error::CertificatePath("The certificate has been revoked."))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// 签发者须为CA证书，且秘钥用途（若有该扩展）包含cRLSign
+fn can_sign_crl(issuer: &Certificate) -> bool {
+    issuer.basic_constraints().map(|e| e.ca).unwrap_or(false)
+        && issuer.key_usage().map(|e| e.contains(KeyUsage::CRL_SIGN)).unwrap_or(true)
+}
+
+/// CRL的构造器
+///
+/// 默认thisUpdate为当前时间，nextUpdate为7天后；签名时写入CRL编号与签发者秘钥标识扩展。
+pub struct CrlBuilder {
+    number: u64,
+    this_update: SystemTime,
+    next_update: SystemTime,
+    revoked: Vec<RevokedCertificate>,
+}
+
+impl CrlBuilder {
+    /// number为单调递增的CRL编号
+    pub fn new(number: u64) -> Self {
+        let now = SystemTime::now();
+        CrlBuilder { number, this_update: now, next_update: now + NEXT_UPDATE, revoked: vec![] }
+    }
+
+    pub fn with_validity(mut self, this_update: SystemTime, next_update: SystemTime) -> Self {
+        self.this_update = this_update;
+        self.next_update = next_update;
+        self
+    }
+
+    /// 撤销证书，date为撤销时间
+    pub fn revoke(mut self, certificate: &Certificate, date: SystemTime, reason: Option<RevocationReason>) -> Self {
+        self.revoked.push(RevokedCertificate { serial: trim(certificate.serial()).to_vec(), date, reason });
+        self
+    }
+
+    /// 按序列号撤销证书，serial为大端序的正整数
+    pub fn revoke_serial(mut self, serial: &[u8], date: SystemTime, reason: Option<RevocationReason>) -> Self {
+        self.revoked.push(RevokedCertificate { serial: trim(serial).to_vec(), date, reason });
+        self
+    }
+
+    /// 由CA签名，issuer须为CA证书且秘钥用途（若有该扩展）包含cRLSign，issuer_private_key须与其公钥匹配
+    pub fn sign(self, issuer: &Certificate, issuer_private_key: &str) -> Result<Crl> {
+        if !can_sign_crl(issuer) {
+            return Err(Error::InvalidCertificate("The issuer is not allowed to sign CRLs."));
+        }
+        if self.this_update > self.next_update {
+            return Err(Error::InvalidCertificate("The thisUpdate must not be later than the nextUpdate."));
+        }
+        if self.revoked.iter().any(|e| e.serial.is_empty() || e.serial.len() > 20) {
+            return Err(Error::InvalidCertificate("The serial number must be a positive integer of at most 20 bytes."));
+        }
+
+        let issuer_key = issuer.sm2_public_key()?;
+        let keypair = keypair(issuer_private_key, &issuer_key)?;
+        let id = match issuer.subject_key_identifier() {
+            Some(id) => id.to_vec(),
+            None => key_identifier(&issuer_key),
+        };
+        let extensions = [
+            Extension::new(oid::AUTHORITY_KEY_IDENTIFIER, false, encode_authority_key_identifier(&id)),
+            Extension::new(oid::CRL_NUMBER, false, yasna::construct_der(|writer| writer.write_u64(self.number))),
+        ];
+
+        let tbs = yasna::construct_der(|writer| {
+            writer.write_sequence(|writer| {
+                writer.next().write_u8(1);
+                write_signature_algorithm(writer.next());
+                issuer.subject().write(writer.next());
+                write_time(writer.next(), self.this_update);
+                write_time(writer.next(), self.next_update);
+                if !self.revoked.is_empty() {
+                    writer.next().write_sequence_of(|writer| {
+                        for entry in self.revoked.iter() {
+                            entry.write(writer.next());
+                        }
+                    });
+                }
+                writer.next().write_tagged(Tag::context(0), |writer| write_extensions(writer, &extensions));
+            })
+        });
+        Crl::from_der(&sign(tbs, keypair))
+    }
+}
+
+/// TBSCertList ::= SEQUENCE {
+///     version                 Version OPTIONAL,
+///     signature               AlgorithmIdentifier,
+///     issuer                  Name,
+///     thisUpdate              Time,
+///     nextUpdate              Time OPTIONAL,
+///     revokedCertificates     SEQUENCE OF ... OPTIONAL,
+///     crlExtensions       \[0] EXPLICIT Extensions OPTIONAL
+/// }
+///
+/// 同时返回签名算法标识的DER编码。
+fn read_tbs(reader: BERReader, der: &[u8], tbs: &[u8], signature: &[u8]) -> std::result::Result<(Crl, Vec<u8>), ASN1Error> {
+    reader.read_sequence(|reader| {
+        let version = reader.read_optional(|reader| reader.read_u8())?;
+        if version.map(|e| e != 1).unwrap_or(false) {
+            return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+        }
+        let algorithm = reader.next().read_der()?;
+        let issuer = Name::read(reader.next())?;
+        let this_update = read_time(reader.next())?;
+        let next_update = reader.read_optional(read_time)?;
+        let revoked = reader.read_optional(|reader| reader.collect_sequence_of(RevokedCertificate::read))?.unwrap_or_default();
+        let extensions = reader.read_optional(|reader| {
+            reader.read_tagged(Tag::context(0), |reader| reader.collect_sequence_of(Extension::read))
+        })?.unwrap_or_default();
+
+        let crl = Crl {
+            der: der.to_vec(),
+            tbs: tbs.to_vec(),
+            sm2_with_sm3: false,
+            issuer,
+            this_update,
+            next_update,
+            revoked,
+            extensions,
+            number: None,
+            authority_key_identifier: None,
+            signature: signature.to_vec(),
+        };
+        Ok((crl, algorithm))
+    })
+}
+
+/// 去除序列号的前导0
+pub(crate) fn trim(serial: &[u8]) -> &[u8] {
+    let start = serial.iter().position(|e| *e != 0).unwrap_or(serial.len());
+    &serial[start..]
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+    use std::time::UNIX_EPOCH;
+
+    use crate::x509::fixtures::{
+        CA, CA_PRIVATE_KEY, CA_PUBLIC_KEY, CRL, LEAF, LEAF_PRIVATE_KEY, LEAF_PUBLIC_KEY, ROOT, ROOT_PRIVATE_KEY, ROOT_PUBLIC_KEY,
+    };
+    use crate::x509::{BasicConstraints, CertificateBuilder, TrustStore};
+
+    use super::*;
+
+    fn at(seconds: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(seconds)
+    }
+
+    #[test]
+    fn parse() {
+        let crl = Crl::from_pem(CRL).unwrap();
+        assert_eq!(crl.issuer().common_name(), Some("Yarism Issuing CA"));
+        assert_eq!(crl.this_update(), at(1792192638));
+        assert_eq!(crl.next_update(), Some(at(1794784638)));
+        assert_eq!(crl.number(), Some("01".to_string()));
+        assert_eq!(crl.to_pem(), CRL);
+
+        let ca = Certificate::from_pem(CA).unwrap();
+        assert_eq!(crl.authority_key_identifier(), ca.subject_key_identifier());
+        assert!(crl.verify(CA_PUBLIC_KEY).unwrap());
+        assert!(!crl.verify(ROOT_PUBLIC_KEY).unwrap());
+        assert!(crl.verify_issued_by(&ca).unwrap());
+
+        let entry = crl.revoked(&Certificate::from_pem(LEAF).unwrap()).unwrap();
+        assert_eq!(entry.serial_number(), "0123456789abcdef");
+        assert_eq!(entry.revocation_date(), at(1792193400));
+        assert_eq!(entry.reason(), Some(RevocationReason::KeyCompromise));
+        assert!(crl.revoked(&ca).is_none());
+    }
+
+    #[test]
+    fn build() {
+        let ca = Certificate::from_pem(CA).unwrap();
+        let leaf = Certificate::from_pem(LEAF).unwrap();
+
+        let crl = CrlBuilder::new(7)
+            .with_validity(at(1792192638), at(1794784638))
+            .revoke(&leaf, at(1792193400), Some(RevocationReason::Superseded))
+            .revoke_serial(&[0x00, 0x80], at(1792193400), None)
+            .sign(&ca, CA_PRIVATE_KEY)
+            .unwrap();
+
+        let crl = Crl::from_der(&crl.to_der()).unwrap();
+        assert!(crl.verify_issued_by(&ca).unwrap());
+        assert_eq!(crl.number(), Some("07".to_string()));
+        assert_eq!(crl.authority_key_identifier(), ca.subject_key_identifier());
+
+        let entries = crl.revoked_certificates();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].reason(), Some(RevocationReason::Superseded));
+        assert_eq!((entries[1].serial_number(), entries[1].reason()), ("0080".to_string(), None));
+        assert!(crl.revoked(&leaf).is_some());
+
+        // 终端实体证书的秘钥用途不包含cRLSign
+        let result = CrlBuilder::new(1).sign(&leaf, CA_PRIVATE_KEY);
+        assert!(matches!(result, Err(Error::InvalidCertificate(_))));
+    }
+
+    #[test]
+    fn leaf_issuer() {
+        let ca = Certificate::from_pem(CA).unwrap();
+        let subject = Name::new(&[("CN", "终端实体")]).unwrap();
+        let time = at(1798761600);
+
+        // 没有秘钥用途扩展的终端实体证书
+        let issuer = CertificateBuilder::new(subject.clone(), LEAF_PUBLIC_KEY).unwrap()
+            .with_validity(at(1792191799), at(1863471799))
+            .sign(&ca, CA_PRIVATE_KEY)
+            .unwrap();
+        assert_eq!(issuer.key_usage(), None);
+        let result = CrlBuilder::new(1).sign(&issuer, LEAF_PRIVATE_KEY);
+        assert_eq!(result.unwrap_err(), Error::InvalidCertificate("The issuer is not allowed to sign CRLs."));
+
+        // 同名同秘钥的CA证书签名的CRL，以终端实体证书作为签发者检查时失败
+        let fake = CertificateBuilder::new(subject.clone(), LEAF_PUBLIC_KEY).unwrap()
+            .with_validity(at(1792191799), at(1863471799))
+            .with_basic_constraints(BasicConstraints { ca: true, path_len: None })
+            .self_sign(LEAF_PRIVATE_KEY)
+            .unwrap();
+        let crl = CrlBuilder::new(1).with_validity(at(1792192638), at(1863471799)).sign(&fake, LEAF_PRIVATE_KEY).unwrap();
+        let certificate = CertificateBuilder::new(Name::new(&[("CN", "example")]).unwrap(), CA_PUBLIC_KEY).unwrap()
+            .with_validity(at(1792191799), at(1863471799))
+            .sign(&fake, LEAF_PRIVATE_KEY)
+            .unwrap();
+        assert!(crl.covers(&issuer));
+        assert!(crl.check(&certificate, &fake, time).is_ok());
+        let result = crl.check(&certificate, &issuer, time);
+        assert_eq!(result.unwrap_err(), Error::CertificatePath("The issuer is not allowed to sign CRLs."));
+    }
+
+    #[test]
+    fn revocation() {
+        let (ca, leaf) = (Certificate::from_pem(CA).unwrap(), Certificate::from_pem(LEAF).unwrap());
+        let mut store = TrustStore::new();
+        store.add(Certificate::from_pem(ROOT).unwrap());
+        let intermediates = [ca.clone()];
+
+        let crl = Crl::from_pem(CRL).unwrap();
+        let root_crl = CrlBuilder::new(1)
+            .with_validity(at(1792192638), at(1794784638))
+            .sign(&Certificate::from_pem(ROOT).unwrap(), ROOT_PRIVATE_KEY)
+            .unwrap();
+        let partial = store.clone().with_revocation_check(Rc::new(crl.clone()));
+        let store = partial.clone().with_revocation_check(Rc::new(root_crl));
+
+        // 撤销之前
+        assert!(store.verify(&leaf, &intermediates, at(1792193000)).is_ok());
+        let result = store.verify(&leaf, &intermediates, at(1792200000));
+        assert_eq!(result.unwrap_err(), Error::CertificatePath("The certificate has been revoked."));
+        // CRL已过期
+        let result = store.verify(&leaf, &intermediates, at(1794784639));
+        assert_eq!(result.unwrap_err(), Error::CertificatePath("The CRL is expired or not yet valid."));
+        // 适用的检查失败时，允许跳过没有适用检查的签发者也不影响结果
+        let result = store.clone().with_uncovered_issuers_allowed(true).verify(&leaf, &intermediates, at(1794784639));
+        assert_eq!(result.unwrap_err(), Error::CertificatePath("The CRL is expired or not yet valid."));
+        // 该CRL不适用于根CA签发的中间CA证书，无法确认其撤销状态
+        assert!(store.verify(&ca, &[], at(1792200000)).is_ok());
+        let result = partial.verify(&ca, &[], at(1792200000));
+        assert_eq!(result.unwrap_err(), Error::CertificatePath("The revocation status of the certificate cannot be determined."));
+        assert!(partial.clone().with_uncovered_issuers_allowed(true).verify(&ca, &[], at(1792200000)).is_ok());
+        assert!(partial.verify(&leaf, &intermediates, at(1792193000)).is_err());
+        assert!(crl.check(&ca, &Certificate::from_pem(ROOT).unwrap(), at(1792200000)).is_err());
+
+        // 篡改CRL的签名
+        let mut der = crl.to_der();
+        let len = der.len();
+        der[len - 1] ^= 0x01;
+        let result = Crl::from_der(&der).unwrap().check(&leaf, &ca, at(1792200000));
+        assert_eq!(result.unwrap_err(), Error::CertificatePath("The signature of the CRL is invalid."));
+    }
+}
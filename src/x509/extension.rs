@@ -0,0 +1,315 @@
+use std::net::IpAddr;
+use std::ops::BitOr;
+
+use yasna::models::ObjectIdentifier;
+use yasna::{ASN1Error, ASN1ErrorKind, BERReader, DERWriter, Tag, TagClass};
+
+use crate::sm2::key::{HexKey, PublicKey};
+use crate::sm3;
+use crate::x509::name::Name;
+
+/// 证书扩展
+///
+/// Extension ::= SEQUENCE { extnID OBJECT IDENTIFIER, critical BOOLEAN DEFAULT FALSE, extnValue OCTET STRING }
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Extension {
+    oid: ObjectIdentifier,
+    critical: bool,
+    value: Vec<u8>,
+}
+
+impl Extension {
+    pub(crate) fn new(oid: &[u64], critical: bool, value: Vec<u8>) -> Self {
+        Extension { oid: ObjectIdentifier::from_slice(oid), critical, value }
+    }
+
+    /// 点分形式的OID
+    pub fn oid(&self) -> String {
+        self.oid.to_string()
+    }
+
+    pub fn critical(&self) -> bool {
+        self.critical
+    }
+
+    /// extnValue中的DER编码
+    pub fn value(&self) -> &[u8] {
+        &self.value
+    }
+
+    pub(crate) fn is(&self, oid: &[u64]) -> bool {
+        self.oid.components().as_slice() == oid
+    }
+
+    pub(crate) fn read(reader: BERReader) -> Result<Extension, ASN1Error> {
+        reader.read_sequence(|reader| {
+            let oid = reader.next().read_oid()?;
+            let critical = reader.read_default(false, |reader| reader.read_bool())?;
+            let value = reader.next().read_bytes()?;
+            Ok(Extension { oid, critical, value })
+        })
+    }
+
+    pub(crate) fn write(&self, writer: DERWriter) {
+        writer.write_sequence(|writer| {
+            writer.next().write_oid(&self.oid);
+            if self.critical {
+                writer.next().write_bool(true);
+            }
+            writer.next().write_bytes(&self.value);
+        })
+    }
+}
+
+/// 基本约束
+///
+/// BasicConstraints ::= SEQUENCE { cA BOOLEAN DEFAULT FALSE, pathLenConstraint INTEGER (0..MAX) OPTIONAL }
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct BasicConstraints {
+    /// 是否为CA证书
+    pub ca: bool,
+    /// 该CA证书之下允许的中间CA证书的最大数量
+    pub path_len: Option<u32>,
+}
+
+impl BasicConstraints {
+    pub(crate) fn decode(der: &[u8]) -> Result<Self, ASN1Error> {
+        yasna::parse_der(der, |reader| {
+            reader.read_sequence(|reader| {
+                let ca = reader.read_default(false, |reader| reader.read_bool())?;
+                let path_len = reader.read_optional(|reader| reader.read_u32())?;
+                Ok(BasicConstraints { ca, path_len })
+            })
+        })
+    }
+
+    pub(crate) fn encode(&self) -> Vec<u8> {
+        yasna::construct_der(|writer| {
+            writer.write_sequence(|writer| {
+                if self.ca {
+                    writer.next().write_bool(true);
+                }
+                if let Some(path_len) = self.path_len {
+                    writer.next().write_u32(path_len);
+                }
+            })
+        })
+    }
+}
+
+/// 秘钥用途，第i位对应KeyUsage ::= BIT STRING中的第i个比特
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct KeyUsage(u16);
+
+impl KeyUsage {
+    pub const DIGITAL_SIGNATURE: KeyUsage = KeyUsage(1 << 0);
+    pub const NON_REPUDIATION: KeyUsage = KeyUsage(1 << 1);
+    pub const KEY_ENCIPHERMENT: KeyUsage = KeyUsage(1 << 2);
+    pub const DATA_ENCIPHERMENT: KeyUsage = KeyUsage(1 << 3);
+    pub const KEY_AGREEMENT: KeyUsage = KeyUsage(1 << 4);
+    pub const KEY_CERT_SIGN: KeyUsage = KeyUsage(1 << 5);
+    pub const CRL_SIGN: KeyUsage = KeyUsage(1 << 6);
+    pub const ENCIPHER_ONLY: KeyUsage = KeyUsage(1 << 7);
+    pub const DECIPHER_ONLY: KeyUsage = KeyUsage(1 << 8);
+
+    /// 是否包含other中的全部用途
+    pub fn contains(&self, other: KeyUsage) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn bits(&self) -> u16 {
+        self.0
+    }
+
+    pub(crate) fn decode(der: &[u8]) -> Result<Self, ASN1Error> {
+        let (bytes, len) = yasna::parse_der(der, |reader| reader.read_bitvec_bytes())?;
+        let mut usage = 0u16;
+        for i in 0..len.min(9) {
+            if bytes[i / 8] & (0x80 >> (i % 8)) != 0 {
+                usage |= 1 << i;
+            }
+        }
+        Ok(KeyUsage(usage))
+    }
+
+    /// DER要求去除末尾的0比特
+    pub(crate) fn encode(&self) -> Vec<u8> {
+        let len = 16 - self.0.leading_zeros() as usize;
+        let mut bytes = vec![0u8; len.div_ceil(8)];
+        for i in 0..len {
+            if self.0 & (1 << i) != 0 {
+                bytes[i / 8] |= 0x80 >> (i % 8);
+            }
+        }
+        yasna::construct_der(|writer| writer.write_bitvec_bytes(&bytes, len))
+    }
+}
+
+impl BitOr for KeyUsage {
+    type Output = KeyUsage;
+
+    fn bitor(self, rhs: KeyUsage) -> KeyUsage {
+        KeyUsage(self.0 | rhs.0)
+    }
+}
+
+/// 通用名称，用于主体备用名称（SubjectAltName）
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GeneralName {
+    /// \[1] rfc822Name
+    Email(String),
+    /// \[2] dNSName
+    Dns(String),
+    /// \[4] directoryName
+    Directory(Name),
+    /// \[6] uniformResourceIdentifier
+    Uri(String),
+    /// \[7] iPAddress
+    Ip(IpAddr),
+    /// 其他类型，保留原始的DER编码
+    Other(Vec<u8>),
+}
+
+impl GeneralName {
+    /// GeneralNames ::= SEQUENCE SIZE (1..MAX) OF GeneralName
+    pub(crate) fn decode_all(der: &[u8]) -> Result<Vec<GeneralName>, ASN1Error> {
+        yasna::parse_der(der, |reader| reader.collect_sequence_of(GeneralName::read))
+    }
+
+    pub(crate) fn encode_all(names: &[GeneralName]) -> Vec<u8> {
+        yasna::construct_der(|writer| {
+            writer.write_sequence_of(|writer| {
+                for name in names {
+                    name.write(writer.next());
+                }
+            })
+        })
+    }
+
+    pub(crate) fn read(reader: BERReader) -> Result<GeneralName, ASN1Error> {
+        let tag = reader.lookahead_tag()?;
+        if tag.tag_class != TagClass::ContextSpecific {
+            return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+        }
+        match tag.tag_number {
+            1 => reader.read_tagged_implicit(tag, |reader| reader.read_ia5_string()).map(GeneralName::Email),
+            2 => reader.read_tagged_implicit(tag, |reader| reader.read_ia5_string()).map(GeneralName::Dns),
+            4 => reader.read_tagged(tag, Name::read).map(GeneralName::Directory),
+            6 => reader.read_tagged_implicit(tag, |reader| reader.read_ia5_string()).map(GeneralName::Uri),
+            7 => {
+                let bytes = reader.read_tagged_implicit(tag, |reader| reader.read_bytes())?;
+                match bytes.len() {
+                    4 => Ok(GeneralName::Ip(IpAddr::from(<[u8; 4]>::try_from(bytes).unwrap()))),
+                    16 => Ok(GeneralName::Ip(IpAddr::from(<[u8; 16]>::try_from(bytes).unwrap()))),
+                    _ => Err(ASN1Error::new(ASN1ErrorKind::Invalid)),
+                }
+            }
+            _ => reader.read_der().map(GeneralName::Other),
+        }
+    }
+
+    /// IA5String只能包含ASCII字符
+    pub(crate) fn is_valid(&self) -> bool {
+        match self {
+            GeneralName::Email(text) | GeneralName::Dns(text) | GeneralName::Uri(text) => text.is_ascii(),
+            _ => true,
+        }
+    }
+
+    pub(crate) fn write(&self, writer: DERWriter) {
+        match self {
+            GeneralName::Email(email) => writer.write_tagged_implicit(Tag::context(1), |writer| writer.write_ia5_string(email)),
+            GeneralName::Dns(dns) => writer.write_tagged_implicit(Tag::context(2), |writer| writer.write_ia5_string(dns)),
+            GeneralName::Directory(name) => writer.write_tagged(Tag::context(4), |writer| name.write(writer)),
+            GeneralName::Uri(uri) => writer.write_tagged_implicit(Tag::context(6), |writer| writer.write_ia5_string(uri)),
+            GeneralName::Ip(IpAddr::V4(ip)) => writer.write_tagged_implicit(Tag::context(7), |writer| writer.write_bytes(&ip.octets())),
+            GeneralName::Ip(IpAddr::V6(ip)) => writer.write_tagged_implicit(Tag::context(7), |writer| writer.write_bytes(&ip.octets())),
+            GeneralName::Other(der) => writer.write_der(der),
+        }
+    }
+}
+
+/// Extensions ::= SEQUENCE SIZE (1..MAX) OF Extension
+pub(crate) fn write_extensions(writer: DERWriter, extensions: &[Extension]) {
+    writer.write_sequence_of(|writer| {
+        for extension in extensions {
+            extension.write(writer.next());
+        }
+    })
+}
+
+/// 由公钥计算秘钥标识：SM3(subjectPublicKey)的前160比特（RFC 7093 方法1）
+pub(crate) fn key_identifier(public_key: &PublicKey) -> Vec<u8> {
+    let key = hex::decode(public_key.encode()).unwrap();
+    sm3::hash(&key)[..20].to_vec()
+}
+
+/// SubjectKeyIdentifier ::= KeyIdentifier（OCTET STRING）
+pub(crate) fn encode_subject_key_identifier(id: &[u8]) -> Vec<u8> {
+    yasna::construct_der(|writer| writer.write_bytes(id))
+}
+
+/// SubjectKeyIdentifier ::= KeyIdentifier（OCTET STRING）
+pub(crate) fn decode_subject_key_identifier(der: &[u8]) -> Result<Vec<u8>, ASN1Error> {
+    yasna::parse_der(der, |reader| reader.read_bytes())
+}
+
+/// AuthorityKeyIdentifier ::= SEQUENCE {
+///     keyIdentifier             \[0] KeyIdentifier OPTIONAL,
+///     authorityCertIssuer       \[1] GeneralNames OPTIONAL,
+///     authorityCertSerialNumber \[2] CertificateSerialNumber OPTIONAL
+/// }
+///
+/// 仅使用keyIdentifier。
+pub(crate) fn encode_authority_key_identifier(id: &[u8]) -> Vec<u8> {
+    yasna::construct_der(|writer| {
+        writer.write_sequence(|writer| {
+            writer.next().write_tagged_implicit(Tag::context(0), |writer| writer.write_bytes(id));
+        })
+    })
+}
+
+/// 解码AuthorityKeyIdentifier，仅返回keyIdentifier
+pub(crate) fn decode_authority_key_identifier(der: &[u8]) -> Result<Option<Vec<u8>>, ASN1Error> {
+    yasna::parse_der(der, |reader| {
+        reader.read_sequence(|reader| {
+            let id = reader.read_optional(|reader| reader.read_tagged_implicit(Tag::context(0), |reader| reader.read_bytes()))?;
+            reader.read_optional(|reader| {
+                reader.read_tagged_implicit(Tag::context(1), |reader| reader.collect_sequence_of(GeneralName::read))
+            })?;
+            reader.read_optional(|reader| reader.read_tagged_implicit(Tag::context(2), |reader| reader.read_bigint_bytes()))?;
+            Ok(id)
+        })
+    })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key_usage() {
+        for (usage, der) in [
+            (KeyUsage::DIGITAL_SIGNATURE, "03020780"),
+            (KeyUsage::DIGITAL_SIGNATURE | KeyUsage::KEY_ENCIPHERMENT, "030205a0"),
+            (KeyUsage::KEY_CERT_SIGN | KeyUsage::CRL_SIGN, "03020106"),
+            (KeyUsage::DIGITAL_SIGNATURE | KeyUsage::DECIPHER_ONLY, "0303078080"),
+        ] {
+            assert_eq!(hex::encode(usage.encode()), der);
+            assert_eq!(KeyUsage::decode(&hex::decode(der).unwrap()).unwrap(), usage);
+        }
+    }
+
+    #[test]
+    fn basic_constraints() {
+        for (constraints, der) in [
+            (BasicConstraints { ca: false, path_len: None }, "3000"),
+            (BasicConstraints { ca: true, path_len: None }, "30030101ff"),
+            (BasicConstraints { ca: true, path_len: Some(0) }, "30060101ff020100"),
+        ] {
+            assert_eq!(hex::encode(constraints.encode()), der);
+            assert_eq!(BasicConstraints::decode(&hex::decode(der).unwrap()).unwrap(), constraints);
+        }
+    }
+}
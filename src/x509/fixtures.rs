@@ -0,0 +1,67 @@
+//! 由OpenSSL 3生成的SM2证书链：根CA、中间CA（pathlen:0）与终端实体证书
+
+pub(crate) const ROOT: &str = "\
+-----BEGIN CERTIFICATE-----
+MIIBnzCCAUWgAwIBAgIBATAKBggqgRzPVQGDdTA3MQswCQYDVQQGEwJDTjEPMA0G
+A1UECgwGWWFyaXNtMRcwFQYDVQQDDA5ZYXJpc20gUm9vdCBDQTAeFw0yNjEwMTYy
+MzAzMTlaFw00NjEwMTEyMzAzMTlaMDcxCzAJBgNVBAYTAkNOMQ8wDQYDVQQKDAZZ
+YXJpc20xFzAVBgNVBAMMDllhcmlzbSBSb290IENBMFkwEwYHKoZIzj0CAQYIKoEc
+z1UBgi0DQgAEiin773yDTGQoZjcbglDAgUh0YHbJemzhAwKcrdk8f+BO37ScF/NT
+tSLnguxHn158NWo53+3EdmUZsKrNFfgYt6NCMEAwDwYDVR0TAQH/BAUwAwEB/zAO
+BgNVHQ8BAf8EBAMCAQYwHQYDVR0OBBYEFL2WdJl/WXaqtb5EIB3bWbbNcdoIMAoG
+CCqBHM9VAYN1A0gAMEUCIQD343ZcpkfwQCwGVTuz7b9q4WtMpRuDnbtCeLwrhGqY
+EQIgbzH3ao8G3qDzSvG2TtCEa5NXzJKvYwJAwjZemCHTdi8=
+-----END CERTIFICATE-----
+";
+
+pub(crate) const CA: &str = "\
+-----BEGIN CERTIFICATE-----
+MIIBxzCCAWygAwIBAgIBAjAKBggqgRzPVQGDdTA3MQswCQYDVQQGEwJDTjEPMA0G
+A1UECgwGWWFyaXNtMRcwFQYDVQQDDA5ZYXJpc20gUm9vdCBDQTAeFw0yNjEwMTYy
+MzAzMTlaFw0zNjEwMTMyMzAzMTlaMDoxCzAJBgNVBAYTAkNOMQ8wDQYDVQQKDAZZ
+YXJpc20xGjAYBgNVBAMMEVlhcmlzbSBJc3N1aW5nIENBMFkwEwYHKoZIzj0CAQYI
+KoEcz1UBgi0DQgAEHIYOYJ2s6uDbt3IAIXk0ngleegWzNhKs7iIgwf/J70yndeOP
+NPRmgeMFX264IV4V8pZJfvTHQt02NU3Szguqb6NmMGQwEgYDVR0TAQH/BAgwBgEB
+/wIBADAOBgNVHQ8BAf8EBAMCAQYwHQYDVR0OBBYEFAeHBHo0GsYu0k26e5r1722Y
+21s7MB8GA1UdIwQYMBaAFL2WdJl/WXaqtb5EIB3bWbbNcdoIMAoGCCqBHM9VAYN1
+A0kAMEYCIQDvRufaSdxDnIMaloXBHQhwLKVHcAnmi6vKZwiI0XOClAIhALu/AO32
+pB62jd3hq/0R8Yh1nZFL7qeT8bUFBp2VgHdM
+-----END CERTIFICATE-----
+";
+
+pub(crate) const LEAF: &str = "\
+-----BEGIN CERTIFICATE-----
+MIICCTCCAa+gAwIBAgIIASNFZ4mrze8wCgYIKoEcz1UBg3UwOjELMAkGA1UEBhMC
+Q04xDzANBgNVBAoMBllhcmlzbTEaMBgGA1UEAwwRWWFyaXNtIElzc3VpbmcgQ0Ew
+HhcNMjYxMDE2MjMwMzE5WhcNMjkwMTE4MjMwMzE5WjA4MQswCQYDVQQGEwJDTjEP
+MA0GA1UECgwGWWFyaXNtMRgwFgYDVQQDDA93d3cuZXhhbXBsZS5jb20wWTATBgcq
+hkjOPQIBBggqgRzPVQGCLQNCAAS0ZiKf6ojgUdBlNVIVO0mPZC+wzBkzvt89LB9V
+6OHDG0sKYN6JpBxQPMRyo9+8zvBObw9MRyIXf6gAo0FW74yao4GgMIGdMAkGA1Ud
+EwQCMAAwDgYDVR0PAQH/BAQDAgWgMEAGA1UdEQQ5MDeCD3d3dy5leGFtcGxlLmNv
+bYILZXhhbXBsZS5jb22HBH8AAAGBEWFkbWluQGV4YW1wbGUuY29tMB0GA1UdDgQW
+BBTaY0BZmLgrqbCaaLuYPsFl96pp8DAfBgNVHSMEGDAWgBQHhwR6NBrGLtJNunua
+9e9tmNtbOzAKBggqgRzPVQGDdQNIADBFAiEAmAaFU8LF3PAMaaHa2+lVE11CidiL
+qbD8hrzxgRhjdKICID8MUnsIMlVwQCxNgiYqa0tTdLjL9PUCjuXZ7Goa7+R1
+-----END CERTIFICATE-----
+";
+
+pub(crate) const ROOT_PRIVATE_KEY: &str = "48fd32d0ac5cdf62b735abe595ff7ce875df20ed93fa169e6115a9032a578f00";
+pub(crate) const CA_PRIVATE_KEY: &str = "104e5ecd0b28cc61721398ca2494c7855a8193f95dbad521b4747404151bf60e";
+pub(crate) const LEAF_PRIVATE_KEY: &str = "b2f37cb22e28154e84603ff94f70a5091da85a9edd90dc2521fcd3613ca727bb";
+
+pub(crate) const ROOT_PUBLIC_KEY: &str = "048a29fbef7c834c642866371b8250c08148746076c97a6ce103029cadd93c7fe04edfb49c17f353b522e782ec479f5e7c356a39dfedc4766519b0aacd15f818b7";
+pub(crate) const CA_PUBLIC_KEY: &str = "041c860e609daceae0dbb772002179349e095e7a05b33612acee2220c1ffc9ef4ca775e38f34f46681e3055f6eb8215e15f296497ef4c742dd36354dd2ce0baa6f";
+pub(crate) const LEAF_PUBLIC_KEY: &str = "04b466229fea88e051d0653552153b498f642fb0cc1933bedf3d2c1f55e8e1c31b4b0a60de89a41c503cc472a3dfbccef04e6f0f4c4722177fa800a34156ef8c9a";
+
+/// 由OpenSSL生成的中间CA的CRL，撤销了终端实体证书（keyCompromise）
+pub(crate) const CRL: &str = "\
+-----BEGIN X509 CRL-----
+MIIBHzCBxQIBATAKBggqgRzPVQGDdTA6MQswCQYDVQQGEwJDTjEPMA0GA1UECgwG
+WWFyaXNtMRowGAYDVQQDDBFZYXJpc20gSXNzdWluZyBDQRcNMjYxMDE2MjMxNzE4
+WhcNMjYxMTE1MjMxNzE4WjApMCcCCAEjRWeJq83vFw0yNjEwMTYyMzMwMDBaMAww
+CgYDVR0VBAMKAQGgLzAtMB8GA1UdIwQYMBaAFAeHBHo0GsYu0k26e5r1722Y21s7
+MAoGA1UdFAQDAgEBMAoGCCqBHM9VAYN1A0kAMEYCIQDA51BNTrM2K3Khln7ofEJZ
+x07EPJvhBNlEYrj+rgbziAIhAJlYSapOMvgnocN+E8eTZjT7XA1nx0mAz7aHl1bm
+C6oV
+-----END X509 CRL-----
+";
@@ -0,0 +1,152 @@
+//! OCSP使用的最小HTTP/1.0实现（RFC 6960 附录A）
+//!
+//! 仅支持http，每个连接处理一次请求与响应，不支持分块传输编码。
+
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+
+use crate::error::{Error, Result};
+
+/// 报文头的最大长度
+const MAX_HEADER: usize = 8192;
+/// 报文体的最大长度
+const MAX_BODY: usize = 1 << 20;
+/// 连接与读写超时
+pub(crate) const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 发送POST请求，返回状态码为200的响应体
+pub(crate) fn post(url: &str, content_type: &str, body: &[u8]) -> Result<Vec<u8>> {
+    let (host, path) = parse_url(url)?;
+    let mut stream = connect(&host)?;
+
+    let head = format!(
+        "POST {} HTTP/1.0\r\nHost: {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        path, host, content_type, body.len()
+    );
+    stream.write_all(&[head.as_bytes(), body].concat()).map_err(|_| Error::Ocsp("Failed to send the HTTP request."))?;
+
+    let (start, body) = read_message(&mut stream, true)?;
+    match start.split(' ').nth(1) {
+        Some("200") => Ok(body),
+        _ => Err(Error::Ocsp("The HTTP status of the response is not 200.")),
+    }
+}
+
+/// 读取一个HTTP请求，返回请求体：POST为报文体，GET为路径最后一段中base64编码的数据
+pub(crate) fn read_request(stream: &mut TcpStream) -> Result<Vec<u8>> {
+    let (start, body) = read_message(stream, false)?;
+    let mut parts = start.split(' ');
+    match (parts.next(), parts.next()) {
+        (Some("POST"), Some(_)) => Ok(body),
+        (Some("GET"), Some(path)) => {
+            let encoded = percent_decode(path.rsplit('/').next().unwrap_or(""))?;
+            STANDARD.decode(encoded).map_err(|_| Error::Ocsp("The HTTP request path is not valid base64."))
+        }
+        _ => Err(Error::Ocsp("The HTTP request method is not supported.")),
+    }
+}
+
+/// 写入HTTP响应，status为状态码及原因短语，如"200 OK"
+pub(crate) fn write_response(stream: &mut TcpStream, status: &str, content_type: &str, body: &[u8]) -> Result<()> {
+    let head = format!(
+        "HTTP/1.0 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        status, content_type, body.len()
+    );
+    stream.write_all(&[head.as_bytes(), body].concat())
+        .and_then(|_| stream.flush())
+        .map_err(|_| Error::Ocsp("Failed to send the HTTP response."))
+}
+
+/// http://host[:port]/path，返回(host:port, /path)
+fn parse_url(url: &str) -> Result<(String, String)> {
+    let rest = url.strip_prefix("http://").ok_or(Error::Ocsp("Only http URLs are supported."))?;
+    let (host, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, "/"),
+    };
+    if host.is_empty() {
+        return Err(Error::Ocsp("The URL does not contain a host."));
+    }
+    let host = if host.contains(':') { host.to_string() } else { format!("{}:80", host) };
+    Ok((host, path.to_string()))
+}
+
+fn connect(host: &str) -> Result<TcpStream> {
+    use std::net::ToSocketAddrs;
+
+    let addresses = host.to_socket_addrs().map_err(|_| Error::Ocsp("Failed to resolve the host."))?;
+    for address in addresses {
+        if let Ok(stream) = TcpStream::connect_timeout(&address, TIMEOUT) {
+            stream.set_read_timeout(Some(TIMEOUT)).and_then(|_| stream.set_write_timeout(Some(TIMEOUT)))
+                .map_err(|_| Error::Ocsp("Failed to configure the connection."))?;
+            return Ok(stream);
+        }
+    }
+    Err(Error::Ocsp("Failed to connect to the host."))
+}
+
+/// 读取起始行与报文体，报文体的长度由Content-Length给出；缺少Content-Length时，
+/// 响应（until_close为true）读到连接关闭为止，请求则没有报文体
+fn read_message(stream: &mut TcpStream, until_close: bool) -> Result<(String, Vec<u8>)> {
+    let mut buffer = vec![];
+    let mut chunk = [0u8; 4096];
+    let end = loop {
+        if let Some(i) = buffer.windows(4).position(|e| e == b"\r\n\r\n") {
+            break i;
+        }
+        if buffer.len() > MAX_HEADER {
+            return Err(Error::Ocsp("The HTTP header is too large."));
+        }
+        match stream.read(&mut chunk) {
+            Ok(0) | Err(_) => return Err(Error::Ocsp("The HTTP message is incomplete.")),
+            Ok(n) => buffer.extend_from_slice(&chunk[..n]),
+        }
+    };
+
+    let head = String::from_utf8(buffer[..end].to_vec()).map_err(|_| Error::Ocsp("The HTTP header is not valid."))?;
+    let mut lines = head.split("\r\n");
+    let start = lines.next().unwrap_or("").to_string();
+    let length = match lines.filter_map(|line| line.split_once(':')).find(|(name, _)| name.trim().eq_ignore_ascii_case("content-length")) {
+        Some((_, value)) => value.trim().parse::<usize>().map_err(|_| Error::Ocsp("The Content-Length is not valid."))?,
+        None if until_close => usize::MAX,
+        None => 0,
+    };
+    if length != usize::MAX && length > MAX_BODY {
+        return Err(Error::Ocsp("The HTTP body is too large."));
+    }
+
+    let mut body = buffer[end + 4..].to_vec();
+    while body.len() < length {
+        if body.len() > MAX_BODY {
+            return Err(Error::Ocsp("The HTTP body is too large."));
+        }
+        match stream.read(&mut chunk) {
+            Ok(0) if length == usize::MAX => break,
+            Ok(0) | Err(_) => return Err(Error::Ocsp("The HTTP message is incomplete.")),
+            Ok(n) => body.extend_from_slice(&chunk[..n]),
+        }
+    }
+    body.truncate(length);
+    Ok((start, body))
+}
+
+fn percent_decode(text: &str) -> Result<Vec<u8>> {
+    let bytes = text.as_bytes();
+    let mut decoded = vec![];
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = text.get(i + 1..i + 3).ok_or(Error::Ocsp("The HTTP request path is not valid."))?;
+            decoded.push(u8::from_str_radix(hex, 16).map_err(|_| Error::Ocsp("The HTTP request path is not valid."))?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    Ok(decoded)
+}
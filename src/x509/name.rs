@@ -0,0 +1,177 @@
+use std::fmt::{Display, Formatter};
+
+use yasna::models::ObjectIdentifier;
+use yasna::{ASN1Error, ASN1ErrorKind, BERReader, DERWriter};
+use yasna::tags::{TAG_BMPSTRING, TAG_UNIVERSALSTRING};
+
+use crate::error::{Error, Result};
+use crate::oid;
+
+/// 常用属性的简称
+const ATTRIBUTES: [(&str, &[u64]); 8] = [
+    ("CN", oid::COMMON_NAME),
+    ("serialNumber", oid::SERIAL_NUMBER),
+    ("C", oid::COUNTRY),
+    ("L", oid::LOCALITY),
+    ("ST", oid::STATE),
+    ("O", oid::ORGANIZATION),
+    ("OU", oid::ORGANIZATIONAL_UNIT),
+    ("emailAddress", oid::EMAIL_ADDRESS),
+];
+
+/// 可分辨名称（Distinguished Name）
+///
+/// Name ::= SEQUENCE OF RelativeDistinguishedName，
+/// RelativeDistinguishedName ::= SET OF AttributeTypeAndValue。
+///
+/// 保留原始的DER编码，证书链中的名称按编码逐字节比较。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Name {
+    der: Vec<u8>,
+    entries: Vec<(ObjectIdentifier, String)>,
+}
+
+impl Name {
+    /// 由（属性简称，值）按顺序构造名称，每个属性单独构成一个RDN
+    ///
+    /// 支持的简称：CN、serialNumber、C、L、ST、O、OU、emailAddress。
+    /// C与serialNumber编码为PrintableString，emailAddress编码为IA5String，其余编码为UTF8String。
+    pub fn new(entries: &[(&str, &str)]) -> Result<Name> {
+        let mut attributes = vec![];
+        for (name, value) in entries {
+            let oid = match ATTRIBUTES.iter().find(|(short, _)| short == name) {
+                Some((_, attribute)) => ObjectIdentifier::from_slice(attribute),
+                None => return Err(Error::InvalidCertificate("The name contains an unsupported attribute.")),
+            };
+            if !is_valid_string(&oid, value) {
+                return Err(Error::InvalidCertificate("The name contains characters not allowed by the string type."));
+            }
+            attributes.push((oid, value.to_string()));
+        }
+
+        let der = yasna::construct_der(|writer| {
+            writer.write_sequence_of(|writer| {
+                for (oid, value) in attributes.iter() {
+                    writer.next().write_set_of(|writer| {
+                        writer.next().write_sequence(|writer| {
+                            writer.next().write_oid(oid);
+                            write_string(writer.next(), oid, value);
+                        })
+                    })
+                }
+            })
+        });
+        Ok(Name { der, entries: attributes })
+    }
+
+    /// 第一个通用名称（CN）
+    pub fn common_name(&self) -> Option<&str> {
+        self.get(oid::COMMON_NAME)
+    }
+
+    /// 第一个组织名称（O）
+    pub fn organization(&self) -> Option<&str> {
+        self.get(oid::ORGANIZATION)
+    }
+
+    /// 按出现顺序列出所有属性，常用属性使用简称，其余使用点分形式的OID
+    pub fn entries(&self) -> Vec<(String, String)> {
+        self.entries.iter().map(|(oid, value)| (short_name(oid), value.clone())).collect()
+    }
+
+    /// DER编码
+    pub fn to_der(&self) -> Vec<u8> {
+        self.der.clone()
+    }
+
+    fn get(&self, attribute: &[u64]) -> Option<&str> {
+        let attribute = ObjectIdentifier::from_slice(attribute);
+        self.entries.iter().find(|(oid, _)| *oid == attribute).map(|(_, value)| value.as_str())
+    }
+
+    pub(crate) fn read(reader: BERReader) -> std::result::Result<Name, ASN1Error> {
+        let der = reader.read_der()?;
+        let entries = yasna::parse_der(&der, |reader| {
+            let mut entries = vec![];
+            reader.read_sequence_of(|reader| {
+                reader.read_set_of(|reader| {
+                    let entry = reader.read_sequence(|reader| {
+                        let oid = reader.next().read_oid()?;
+                        let value = read_string(reader.next())?;
+                        Ok((oid, value))
+                    })?;
+                    entries.push(entry);
+                    Ok(())
+                })
+            })?;
+            Ok(entries)
+        })?;
+        Ok(Name { der, entries })
+    }
+
+    pub(crate) fn write(&self, writer: DERWriter) {
+        writer.write_der(&self.der);
+    }
+}
+
+impl Display for Name {
+    /// C=CN, O=Yarism, CN=Yarism Root CA
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let entries: Vec<String> = self.entries().iter().map(|(name, value)| format!("{}={}", name, value)).collect();
+        write!(f, "{}", entries.join(", "))
+    }
+}
+
+fn short_name(oid: &ObjectIdentifier) -> String {
+    match ATTRIBUTES.iter().find(|(_, attribute)| oid.components().as_slice() == *attribute) {
+        Some((name, _)) => name.to_string(),
+        None => oid.to_string(),
+    }
+}
+
+fn is_valid_string(oid: &ObjectIdentifier, value: &str) -> bool {
+    let attribute = oid.components().as_slice();
+    if attribute == oid::COUNTRY || attribute == oid::SERIAL_NUMBER {
+        value.bytes().all(|e| e.is_ascii_alphanumeric() || b" '()+,-./:=".contains(&e))
+    } else if attribute == oid::EMAIL_ADDRESS {
+        value.is_ascii()
+    } else {
+        true
+    }
+}
+
+fn write_string(writer: DERWriter, oid: &ObjectIdentifier, value: &str) {
+    let attribute = oid.components().as_slice();
+    if attribute == oid::COUNTRY || attribute == oid::SERIAL_NUMBER {
+        writer.write_printable_string(value);
+    } else if attribute == oid::EMAIL_ADDRESS {
+        writer.write_ia5_string(value);
+    } else {
+        writer.write_utf8_string(value);
+    }
+}
+
+/// 读取DirectoryString等字符串类型的属性值
+fn read_string(reader: BERReader) -> std::result::Result<String, ASN1Error> {
+    let value = reader.read_tagged_der()?;
+    let bytes = value.value();
+    match value.tag() {
+        TAG_BMPSTRING => {
+            if bytes.len() % 2 != 0 {
+                return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+            }
+            let units: Vec<u16> = bytes.chunks(2).map(|e| u16::from_be_bytes([e[0], e[1]])).collect();
+            String::from_utf16(&units).map_err(|_| ASN1Error::new(ASN1ErrorKind::Invalid))
+        }
+        TAG_UNIVERSALSTRING => {
+            if bytes.len() % 4 != 0 {
+                return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+            }
+            bytes.chunks(4)
+                .map(|e| char::from_u32(u32::from_be_bytes([e[0], e[1], e[2], e[3]])))
+                .collect::<Option<String>>()
+                .ok_or(ASN1Error::new(ASN1ErrorKind::Invalid))
+        }
+        _ => Ok(String::from_utf8_lossy(bytes).to_string()),
+    }
+}
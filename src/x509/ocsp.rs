@@ -0,0 +1,919 @@
+use std::collections::HashMap;
+use std::net::TcpStream;
+use std::time::{Duration, SystemTime};
+
+use rand::RngCore;
+use yasna::models::ObjectIdentifier;
+use yasna::{ASN1Error, ASN1ErrorKind, BERReader, DERWriter, Tag};
+
+use crate::error::{Error, Result};
+use crate::oid;
+use crate::sm2::ecc::{Crypto, Signature};
+use crate::sm2::key::{HexKey, PublicKey};
+use crate::sm3;
+use crate::x509::builder::{keypair, sign};
+use crate::x509::crl::trim;
+use crate::x509::extension::write_extensions;
+use crate::x509::{http, read_signature_algorithm, read_time, write_generalized_time};
+use crate::x509::{Certificate, Crl, Extension, Name, RevocationCheck, RevocationReason};
+
+/// 随机数扩展的长度（RFC 8954建议32字节以内）
+const NONCE_LEN: usize = 16;
+/// 校验响应时间时允许的时钟偏差
+const CLOCK_SKEW: Duration = Duration::from_secs(300);
+/// 响应没有nextUpdate时，thisUpdate距当前时间的默认最大间隔
+const MAX_AGE: Duration = Duration::from_secs(86400);
+
+/// 证书标识
+///
+/// CertID ::= SEQUENCE {
+///     hashAlgorithm       AlgorithmIdentifier,
+///     issuerNameHash      OCTET STRING,
+///     issuerKeyHash       OCTET STRING,
+///     serialNumber        CertificateSerialNumber
+/// }
+///
+/// 杂凑算法为SM3，issuerNameHash为签发者名称DER编码的杂凑值，issuerKeyHash为签发者subjectPublicKey的杂凑值。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CertId {
+    sm3: bool,
+    name_hash: Vec<u8>,
+    key_hash: Vec<u8>,
+    serial: Vec<u8>,
+}
+
+impl CertId {
+    /// certificate须由issuer签发
+    pub fn new(certificate: &Certificate, issuer: &Certificate) -> Result<CertId> {
+        if certificate.issuer() != issuer.subject() {
+            return Err(Error::Ocsp("The issuer name of the certificate does not match the issuer."));
+        }
+        let mut id = CertId::issuer(issuer);
+        id.serial = trim(certificate.serial()).to_vec();
+        Ok(id)
+    }
+
+    /// 序列号为空的CertId，用于比较签发者
+    fn issuer(issuer: &Certificate) -> CertId {
+        CertId {
+            sm3: true,
+            name_hash: sm3::hash(&issuer.subject().to_der()).to_vec(),
+            key_hash: sm3::hash(&issuer.public_key_bits()).to_vec(),
+            serial: vec![],
+        }
+    }
+
+    /// 十六进制编码的序列号
+    pub fn serial_number(&self) -> String {
+        hex::encode(&self.serial)
+    }
+
+    pub fn issuer_name_hash(&self) -> &[u8] {
+        &self.name_hash
+    }
+
+    pub fn issuer_key_hash(&self) -> &[u8] {
+        &self.key_hash
+    }
+
+    /// 杂凑算法与签发者一致
+    fn same_issuer(&self, other: &CertId) -> bool {
+        self.sm3 && other.sm3 && self.name_hash == other.name_hash && self.key_hash == other.key_hash
+    }
+
+    fn read(reader: BERReader) -> std::result::Result<CertId, ASN1Error> {
+        reader.read_sequence(|reader| {
+            let sm3 = reader.next().read_sequence(|reader| {
+                let algorithm = reader.next().read_oid()?;
+                reader.read_optional(|reader| reader.read_null())?;
+                Ok(algorithm == ObjectIdentifier::from_slice(oid::SM3))
+            })?;
+            let name_hash = reader.next().read_bytes()?;
+            let key_hash = reader.next().read_bytes()?;
+            let (serial, _) = reader.next().read_bigint_bytes()?;
+            Ok(CertId { sm3, name_hash, key_hash, serial: trim(&serial).to_vec() })
+        })
+    }
+
+    fn write(&self, writer: DERWriter) {
+        writer.write_sequence(|writer| {
+            writer.next().write_sequence(|writer| {
+                writer.next().write_oid(&ObjectIdentifier::from_slice(oid::SM3));
+                writer.next().write_null();
+            });
+            writer.next().write_bytes(&self.name_hash);
+            writer.next().write_bytes(&self.key_hash);
+            writer.next().write_bigint_bytes(&self.serial, true);
+        })
+    }
+}
+
+/// OCSP请求（RFC 6960 4.1）
+///
+/// OCSPRequest ::= SEQUENCE {
+///     tbsRequest              TBSRequest,
+///     optionalSignature   \[0] EXPLICIT Signature OPTIONAL
+/// }
+///
+/// 构造的请求不签名，带有随机数扩展（RFC 8954）；解析时忽略请求者名称与请求签名。
+#[derive(Clone, Debug)]
+pub struct OcspRequest {
+    der: Vec<u8>,
+    cert_ids: Vec<CertId>,
+    nonce: Option<Vec<u8>>,
+}
+
+impl OcspRequest {
+    /// 查询certificate的状态，certificate须由issuer签发
+    pub fn new(certificate: &Certificate, issuer: &Certificate) -> Result<OcspRequest> {
+        let mut nonce = vec![0u8; NONCE_LEN];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let cert_ids = vec![CertId::new(certificate, issuer)?];
+
+        let extensions = [nonce_extension(&nonce)];
+        let der = yasna::construct_der(|writer| {
+            writer.write_sequence(|writer| {
+                writer.next().write_sequence(|writer| {
+                    writer.next().write_sequence_of(|writer| {
+                        for id in cert_ids.iter() {
+                            writer.next().write_sequence(|writer| id.write(writer.next()));
+                        }
+                    });
+                    writer.next().write_tagged(Tag::context(2), |writer| write_extensions(writer, &extensions));
+                })
+            })
+        });
+        Ok(OcspRequest { der, cert_ids, nonce: Some(nonce) })
+    }
+
+    /// 解析DER编码的OCSP请求
+    pub fn from_der(der: &[u8]) -> Result<OcspRequest> {
+        let (cert_ids, extensions) = yasna::parse_der(der, |reader| {
+            reader.read_sequence(|reader| {
+                let request = reader.next().read_sequence(|reader| {
+                    let version = reader.read_optional(|reader| reader.read_tagged(Tag::context(0), |reader| reader.read_u8()))?;
+                    if version.map(|e| e != 0).unwrap_or(false) {
+                        return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+                    }
+                    reader.read_optional(|reader| reader.read_tagged(Tag::context(1), |reader| reader.read_der()))?;
+                    let cert_ids = reader.next().collect_sequence_of(|reader| {
+                        reader.read_sequence(|reader| {
+                            let id = CertId::read(reader.next())?;
+                            reader.read_optional(|reader| reader.read_tagged(Tag::context(0), |reader| reader.read_der()))?;
+                            Ok(id)
+                        })
+                    })?;
+                    let extensions = reader.read_optional(|reader| {
+                        reader.read_tagged(Tag::context(2), |reader| reader.collect_sequence_of(Extension::read))
+                    })?.unwrap_or_default();
+                    Ok((cert_ids, extensions))
+                })?;
+                reader.read_optional(|reader| reader.read_tagged(Tag::context(0), |reader| reader.read_der()))?;
+                Ok(request)
+            })
+        }).map_err(|_| Error::InvalidDer("The data is not a DER-encoded OCSP request."))?;
+
+        if cert_ids.is_empty() {
+            return Err(Error::InvalidDer("The OCSP request does not contain any certificate."));
+        }
+        let nonce = find_nonce(&extensions)?;
+        Ok(OcspRequest { der: der.to_vec(), cert_ids, nonce })
+    }
+
+    pub fn to_der(&self) -> Vec<u8> {
+        self.der.clone()
+    }
+
+    pub fn cert_ids(&self) -> &[CertId] {
+        &self.cert_ids
+    }
+
+    pub fn nonce(&self) -> Option<&[u8]> {
+        self.nonce.as_deref()
+    }
+
+    /// 以HTTP POST发送到url（http://host\[:port]/path）并解析响应，不验证响应的签名
+    pub fn send(&self, url: &str) -> Result<OcspResponse> {
+        let body = http::post(url, "application/ocsp-request", &self.der)?;
+        OcspResponse::from_der(&body)
+    }
+}
+
+/// OCSP响应状态（OCSPResponseStatus）
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum OcspResponseStatus {
+    Successful = 0,
+    MalformedRequest = 1,
+    InternalError = 2,
+    TryLater = 3,
+    SigRequired = 5,
+    Unauthorized = 6,
+}
+
+impl OcspResponseStatus {
+    fn from_code(code: i64) -> Option<Self> {
+        Some(match code {
+            0 => OcspResponseStatus::Successful,
+            1 => OcspResponseStatus::MalformedRequest,
+            2 => OcspResponseStatus::InternalError,
+            3 => OcspResponseStatus::TryLater,
+            5 => OcspResponseStatus::SigRequired,
+            6 => OcspResponseStatus::Unauthorized,
+            _ => return None,
+        })
+    }
+}
+
+/// 证书状态
+///
+/// CertStatus ::= CHOICE {
+///     good        \[0] IMPLICIT NULL,
+///     revoked     \[1] IMPLICIT RevokedInfo,
+///     unknown     \[2] IMPLICIT NULL
+/// }
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CertStatus {
+    Good,
+    Revoked { time: SystemTime, reason: Option<RevocationReason> },
+    Unknown,
+}
+
+impl CertStatus {
+    fn read(reader: BERReader) -> std::result::Result<CertStatus, ASN1Error> {
+        let tag = reader.lookahead_tag()?;
+        if tag == Tag::context(0) {
+            reader.read_tagged_implicit(tag, |reader| reader.read_null())?;
+            Ok(CertStatus::Good)
+        } else if tag == Tag::context(1) {
+            // RevokedInfo ::= SEQUENCE { revocationTime GeneralizedTime, revocationReason [0] EXPLICIT CRLReason OPTIONAL }
+            reader.read_tagged_implicit(tag, |reader| {
+                reader.read_sequence(|reader| {
+                    let time = read_time(reader.next())?;
+                    let reason = match reader.read_optional(|reader| reader.read_tagged(Tag::context(0), |reader| reader.read_enum()))? {
+                        Some(code) => Some(RevocationReason::from_code(code).ok_or(ASN1Error::new(ASN1ErrorKind::Invalid))?),
+                        None => None,
+                    };
+                    Ok(CertStatus::Revoked { time, reason })
+                })
+            })
+        } else if tag == Tag::context(2) {
+            reader.read_tagged_implicit(tag, |reader| reader.read_null())?;
+            Ok(CertStatus::Unknown)
+        } else {
+            Err(ASN1Error::new(ASN1ErrorKind::Invalid))
+        }
+    }
+
+    fn write(&self, writer: DERWriter) {
+        match self {
+            CertStatus::Good => writer.write_tagged_implicit(Tag::context(0), |writer| writer.write_null()),
+            CertStatus::Revoked { time, reason } => writer.write_tagged_implicit(Tag::context(1), |writer| {
+                writer.write_sequence(|writer| {
+                    write_generalized_time(writer.next(), *time);
+                    if let Some(reason) = reason {
+                        writer.next().write_tagged(Tag::context(0), |writer| writer.write_enum(*reason as i64));
+                    }
+                })
+            }),
+            CertStatus::Unknown => writer.write_tagged_implicit(Tag::context(2), |writer| writer.write_null()),
+        }
+    }
+}
+
+/// 单个证书的状态
+///
+/// SingleResponse ::= SEQUENCE {
+///     certID                  CertID,
+///     certStatus              CertStatus,
+///     thisUpdate              GeneralizedTime,
+///     nextUpdate          \[0] EXPLICIT GeneralizedTime OPTIONAL,
+///     singleExtensions    \[1] EXPLICIT Extensions OPTIONAL
+/// }
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SingleResponse {
+    cert_id: CertId,
+    status: CertStatus,
+    this_update: SystemTime,
+    next_update: Option<SystemTime>,
+}
+
+impl SingleResponse {
+    pub fn cert_id(&self) -> &CertId {
+        &self.cert_id
+    }
+
+    pub fn status(&self) -> CertStatus {
+        self.status
+    }
+
+    pub fn this_update(&self) -> SystemTime {
+        self.this_update
+    }
+
+    pub fn next_update(&self) -> Option<SystemTime> {
+        self.next_update
+    }
+
+    fn read(reader: BERReader) -> std::result::Result<SingleResponse, ASN1Error> {
+        reader.read_sequence(|reader| {
+            let cert_id = CertId::read(reader.next())?;
+            let status = CertStatus::read(reader.next())?;
+            let this_update = read_time(reader.next())?;
+            let next_update = reader.read_optional(|reader| reader.read_tagged(Tag::context(0), read_time))?;
+            let extensions = reader.read_optional(|reader| {
+                reader.read_tagged(Tag::context(1), |reader| reader.collect_sequence_of(Extension::read))
+            })?.unwrap_or_default();
+            if extensions.iter().any(Extension::critical) {
+                return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+            }
+            Ok(SingleResponse { cert_id, status, this_update, next_update })
+        })
+    }
+
+    fn write(&self, writer: DERWriter) {
+        writer.write_sequence(|writer| {
+            self.cert_id.write(writer.next());
+            self.status.write(writer.next());
+            write_generalized_time(writer.next(), self.this_update);
+            if let Some(next_update) = self.next_update {
+                writer.next().write_tagged(Tag::context(0), |writer| write_generalized_time(writer, next_update));
+            }
+        })
+    }
+}
+
+/// OCSP响应（RFC 6960 4.2）
+///
+/// OCSPResponse ::= SEQUENCE {
+///     responseStatus          OCSPResponseStatus,
+///     responseBytes       \[0] EXPLICIT ResponseBytes OPTIONAL
+/// }
+///
+/// 仅支持id-pkix-ocsp-basic类型的响应，签名算法须为SM2-with-SM3。
+#[derive(Clone, Debug)]
+pub struct OcspResponse {
+    der: Vec<u8>,
+    status: OcspResponseStatus,
+    tbs: Vec<u8>,
+    sm2_with_sm3: bool,
+    produced_at: Option<SystemTime>,
+    responses: Vec<SingleResponse>,
+    nonce: Option<Vec<u8>>,
+    certificates: Vec<Certificate>,
+    signature: Vec<u8>,
+}
+
+impl OcspResponse {
+    /// 解析DER编码的OCSP响应
+    pub fn from_der(der: &[u8]) -> Result<OcspResponse> {
+        let (status, basic) = yasna::parse_der(der, |reader| {
+            reader.read_sequence(|reader| {
+                let status = reader.next().read_enum()?;
+                let basic = reader.read_optional(|reader| {
+                    reader.read_tagged(Tag::context(0), |reader| {
+                        reader.read_sequence(|reader| {
+                            let kind = reader.next().read_oid()?;
+                            let response = reader.next().read_bytes()?;
+                            Ok((kind, response))
+                        })
+                    })
+                })?;
+                Ok((status, basic))
+            })
+        }).map_err(|_| Error::InvalidDer("The data is not a DER-encoded OCSP response."))?;
+
+        let status = OcspResponseStatus::from_code(status).ok_or(Error::InvalidDer("The OCSP response status is unknown."))?;
+        let mut response = OcspResponse {
+            der: der.to_vec(),
+            status,
+            tbs: vec![],
+            sm2_with_sm3: false,
+            produced_at: None,
+            responses: vec![],
+            nonce: None,
+            certificates: vec![],
+            signature: vec![],
+        };
+
+        match (status, basic) {
+            (OcspResponseStatus::Successful, Some((kind, basic))) => {
+                if kind != ObjectIdentifier::from_slice(oid::OCSP_BASIC) {
+                    return Err(Error::InvalidDer("The OCSP response type is not supported."));
+                }
+                response.read_basic(&basic)?;
+                Ok(response)
+            }
+            (OcspResponseStatus::Successful, None) => Err(Error::InvalidDer("The successful OCSP response has no body.")),
+            (_, _) => Ok(response),
+        }
+    }
+
+    /// BasicOCSPResponse ::= SEQUENCE {
+    ///     tbsResponseData      ResponseData,
+    ///     signatureAlgorithm   AlgorithmIdentifier,
+    ///     signature            BIT STRING,
+    ///     certs            \[0] EXPLICIT SEQUENCE OF Certificate OPTIONAL
+    /// }
+    fn read_basic(&mut self, der: &[u8]) -> Result<()> {
+        let (tbs, algorithm, signature, certificates) = yasna::parse_der(der, |reader| {
+            reader.read_sequence(|reader| {
+                let tbs = reader.next().read_der()?;
+                let algorithm = reader.next().read_der()?;
+                let (signature, bits) = reader.next().read_bitvec_bytes()?;
+                if bits != signature.len() * 8 {
+                    return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+                }
+                let certificates = reader.read_optional(|reader| {
+                    reader.read_tagged(Tag::context(0), |reader| reader.collect_sequence_of(|reader| reader.read_der()))
+                })?.unwrap_or_default();
+                Ok((tbs, algorithm, signature, certificates))
+            })
+        }).map_err(|_| Error::InvalidDer("The data is not a DER-encoded BasicOCSPResponse."))?;
+
+        self.sm2_with_sm3 = yasna::parse_der(&algorithm, read_signature_algorithm)
+            .map_err(|_| Error::InvalidDer("The signature algorithm is not a DER-encoded AlgorithmIdentifier."))?;
+        self.certificates = certificates.iter().map(|e| Certificate::from_der(e)).collect::<Result<_>>()?;
+        self.signature = signature;
+
+        let (produced_at, responses, extensions) = yasna::parse_der(&tbs, read_response_data)
+            .map_err(|_| Error::InvalidDer("The data is not a DER-encoded ResponseData."))?;
+        if extensions.iter().any(|e| e.critical() && !e.is(oid::OCSP_NONCE)) {
+            return Err(Error::Ocsp("The response contains an unrecognized critical extension."));
+        }
+        self.nonce = find_nonce(&extensions)?;
+        self.produced_at = Some(produced_at);
+        self.responses = responses;
+        self.tbs = tbs;
+        Ok(())
+    }
+
+    pub fn to_der(&self) -> Vec<u8> {
+        self.der.clone()
+    }
+
+    pub fn status(&self) -> OcspResponseStatus {
+        self.status
+    }
+
+    /// 响应的签名时间，仅成功的响应有该字段
+    pub fn produced_at(&self) -> Option<SystemTime> {
+        self.produced_at
+    }
+
+    pub fn responses(&self) -> &[SingleResponse] {
+        &self.responses
+    }
+
+    pub fn nonce(&self) -> Option<&[u8]> {
+        self.nonce.as_deref()
+    }
+
+    /// 响应中附带的证书
+    pub fn certificates(&self) -> &[Certificate] {
+        &self.certificates
+    }
+
+    /// 查找CertId对应的证书状态
+    pub fn find(&self, id: &CertId) -> Option<&SingleResponse> {
+        self.responses.iter().find(|e| e.cert_id == *id)
+    }
+
+    /// 使用响应者的SM2公钥验证签名
+    pub fn verify(&self, responder_public_key: &str) -> Result<bool> {
+        self.verify_with(&PublicKey::decode(responder_public_key)?)
+    }
+
+    /// 验证响应由CA直接签名（不支持委托的OCSP签名证书）
+    pub fn verify_issued_by(&self, issuer: &Certificate) -> Result<bool> {
+        self.verify_with(&issuer.sm2_public_key()?)
+    }
+
+    fn verify_with(&self, key: &PublicKey) -> Result<bool> {
+        if self.status != OcspResponseStatus::Successful {
+            return Err(Error::Ocsp("The response is not successful."));
+        }
+        if !self.sm2_with_sm3 {
+            return Err(Error::Ocsp("The signature algorithm is not SM2-with-SM3."));
+        }
+        let signature = Signature::decode(&self.signature)?;
+        Ok(Crypto::default().verifier(key.clone()).verify_bytes(&self.tbs, &signature))
+    }
+
+    /// 不成功的响应，没有responseBytes
+    fn error(status: OcspResponseStatus) -> Vec<u8> {
+        yasna::construct_der(|writer| {
+            writer.write_sequence(|writer| writer.next().write_enum(status as i64))
+        })
+    }
+}
+
+/// ResponseData ::= SEQUENCE {
+///     version             \[0] EXPLICIT Version DEFAULT v1,
+///     responderID              ResponderID,
+///     producedAt               GeneralizedTime,
+///     responses                SEQUENCE OF SingleResponse,
+///     responseExtensions  \[1] EXPLICIT Extensions OPTIONAL
+/// }
+///
+/// ResponderID ::= CHOICE { byName \[1] Name, byKey \[2] KeyHash }
+type ResponseData = (SystemTime, Vec<SingleResponse>, Vec<Extension>);
+
+fn read_response_data(reader: BERReader) -> std::result::Result<ResponseData, ASN1Error> {
+    reader.read_sequence(|reader| {
+        let version = reader.read_optional(|reader| reader.read_tagged(Tag::context(0), |reader| reader.read_u8()))?;
+        if version.map(|e| e != 0).unwrap_or(false) {
+            return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+        }
+        let responder = reader.next();
+        if responder.lookahead_tag()? == Tag::context(1) {
+            responder.read_tagged(Tag::context(1), |reader| Name::read(reader).map(|_| ()))?;
+        } else {
+            responder.read_tagged(Tag::context(2), |reader| reader.read_bytes().map(|_| ()))?;
+        }
+        let produced_at = read_time(reader.next())?;
+        let responses = reader.next().collect_sequence_of(SingleResponse::read)?;
+        let extensions = reader.read_optional(|reader| {
+            reader.read_tagged(Tag::context(1), |reader| reader.collect_sequence_of(Extension::read))
+        })?.unwrap_or_default();
+        Ok((produced_at, responses, extensions))
+    })
+}
+
+/// 仅根据本地撤销表应答的OCSP响应者，由CA直接签名
+///
+/// 只应答本CA签发的证书：表中已撤销的证书返回revoked，其余返回good；
+/// 签发者不符或杂凑算法不是SM3的CertId返回unknown，无法解析的请求返回malformedRequest。
+/// 请求中的随机数会原样写入响应。
+pub struct OcspResponder {
+    issuer: Certificate,
+    issuer_key: PublicKey,
+    private_key: String,
+    id: CertId,
+    next_update: Option<Duration>,
+    revoked: HashMap<Vec<u8>, (SystemTime, Option<RevocationReason>)>,
+}
+
+impl OcspResponder {
+    /// issuer为CA证书，private_key须与其公钥匹配
+    pub fn new(issuer: Certificate, private_key: &str) -> Result<Self> {
+        let issuer_key = issuer.sm2_public_key()?;
+        keypair(private_key, &issuer_key)?;
+        let id = CertId::issuer(&issuer);
+        Ok(OcspResponder { issuer, issuer_key, private_key: private_key.to_string(), id, next_update: None, revoked: HashMap::new() })
+    }
+
+    /// 设置响应中nextUpdate距thisUpdate的间隔，默认不设置nextUpdate
+    pub fn with_next_update(mut self, interval: Duration) -> Self {
+        self.next_update = Some(interval);
+        self
+    }
+
+    /// 将证书加入撤销表，serial为大端序的正整数
+    pub fn revoke(&mut self, serial: &[u8], time: SystemTime, reason: Option<RevocationReason>) {
+        self.revoked.insert(trim(serial).to_vec(), (time, reason));
+    }
+
+    /// 将证书移出撤销表（如解除证书冻结）
+    pub fn unrevoke(&mut self, serial: &[u8]) {
+        self.revoked.remove(trim(serial));
+    }
+
+    /// 导入CA签发的CRL中的撤销条目，CRL须能用CA的公钥验证
+    pub fn load_crl(&mut self, crl: &Crl) -> Result<()> {
+        if !crl.verify_issued_by(&self.issuer)? {
+            return Err(Error::Ocsp("The CRL is not issued by the responder."));
+        }
+        for entry in crl.revoked_certificates() {
+            match entry.reason() {
+                Some(RevocationReason::RemoveFromCrl) => self.unrevoke(entry.serial()),
+                reason => self.revoke(entry.serial(), entry.revocation_date(), reason),
+            }
+        }
+        Ok(())
+    }
+
+    /// 应答DER编码的OCSP请求，返回DER编码的OCSP响应
+    pub fn respond(&self, request: &[u8]) -> Vec<u8> {
+        let request = match OcspRequest::from_der(request) {
+            Ok(request) => request,
+            Err(_) => return OcspResponse::error(OcspResponseStatus::MalformedRequest),
+        };
+
+        let now = SystemTime::now();
+        let responses: Vec<SingleResponse> = request.cert_ids.iter().map(|id| {
+            let status = match self.revoked.get(&id.serial) {
+                _ if !id.same_issuer(&self.id) => CertStatus::Unknown,
+                Some((time, reason)) => CertStatus::Revoked { time: *time, reason: *reason },
+                None => CertStatus::Good,
+            };
+            SingleResponse { cert_id: id.clone(), status, this_update: now, next_update: self.next_update.map(|e| now + e) }
+        }).collect();
+        let extensions: Vec<Extension> = request.nonce.iter().map(|e| nonce_extension(e)).collect();
+
+        let tbs = yasna::construct_der(|writer| {
+            writer.write_sequence(|writer| {
+                writer.next().write_tagged(Tag::context(1), |writer| self.issuer.subject().write(writer));
+                write_generalized_time(writer.next(), now);
+                writer.next().write_sequence_of(|writer| {
+                    for response in responses.iter() {
+                        response.write(writer.next());
+                    }
+                });
+                if !extensions.is_empty() {
+                    writer.next().write_tagged(Tag::context(1), |writer| write_extensions(writer, &extensions));
+                }
+            })
+        });
+        let keypair = match keypair(&self.private_key, &self.issuer_key) {
+            Ok(keypair) => keypair,
+            Err(_) => return OcspResponse::error(OcspResponseStatus::InternalError),
+        };
+        let basic = sign(tbs, keypair);
+
+        yasna::construct_der(|writer| {
+            writer.write_sequence(|writer| {
+                writer.next().write_enum(OcspResponseStatus::Successful as i64);
+                writer.next().write_tagged(Tag::context(0), |writer| {
+                    writer.write_sequence(|writer| {
+                        writer.next().write_oid(&ObjectIdentifier::from_slice(oid::OCSP_BASIC));
+                        writer.next().write_bytes(&basic);
+                    })
+                });
+            })
+        })
+    }
+
+    /// 处理一个HTTP连接上的一次请求（POST，或路径为base64编码请求的GET）
+    pub fn serve(&self, mut stream: TcpStream) -> Result<()> {
+        stream.set_read_timeout(Some(http::TIMEOUT))
+            .and_then(|_| stream.set_write_timeout(Some(http::TIMEOUT)))
+            .map_err(|_| Error::Ocsp("Failed to configure the connection."))?;
+        match http::read_request(&mut stream) {
+            Ok(request) => http::write_response(&mut stream, "200 OK", "application/ocsp-response", &self.respond(&request)),
+            Err(e) => {
+                http::write_response(&mut stream, "400 Bad Request", "text/plain", b"")?;
+                Err(e)
+            }
+        }
+    }
+}
+
+/// 通过OCSP响应者查询证书状态的撤销检查
+///
+/// 只能检查由指定CA签发的证书，其余证书检查失败（可通过[crate::x509::TrustStore::with_uncovered_issuers_allowed]跳过）。
+/// 响应须由该CA签名、原样带回请求中的随机数，且thisUpdate与nextUpdate相对当前时间有效；
+/// 没有nextUpdate时thisUpdate距当前时间不能超过最大间隔（默认1天），避免重放旧的响应。
+/// 证书在验证时刻之前已被撤销或状态未知时检查失败。
+pub struct OcspClient {
+    url: String,
+    issuer: Certificate,
+    max_age: Duration,
+}
+
+impl OcspClient {
+    /// url为响应者地址（http://host\[:port]/path），issuer为响应者所属的CA证书
+    pub fn new(url: &str, issuer: Certificate) -> Self {
+        OcspClient { url: url.to_string(), issuer, max_age: MAX_AGE }
+    }
+
+    /// 设置响应没有nextUpdate时thisUpdate距当前时间的最大间隔
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// 查询证书的状态并验证响应
+    pub fn query(&self, certificate: &Certificate) -> Result<SingleResponse> {
+        let request = OcspRequest::new(certificate, &self.issuer)?;
+        let response = request.send(&self.url)?;
+        self.validate(&request, &response, SystemTime::now())
+    }
+
+    /// 验证response是对request的有效应答，now为当前时间
+    fn validate(&self, request: &OcspRequest, response: &OcspResponse, now: SystemTime) -> Result<SingleResponse> {
+        if response.status != OcspResponseStatus::Successful {
+            return Err(Error::Ocsp("The response is not successful."));
+        }
+        if !response.verify_issued_by(&self.issuer)? {
+            return Err(Error::Ocsp("The signature of the response is invalid."));
+        }
+        if request.nonce.is_some() && response.nonce != request.nonce {
+            return Err(Error::Ocsp("The nonce of the response does not match the request."));
+        }
+
+        let single = response.find(&request.cert_ids[0]).ok_or(Error::Ocsp("The response does not contain the certificate."))?;
+        let expired = match single.next_update {
+            Some(next_update) => next_update + CLOCK_SKEW < now,
+            None => single.this_update + self.max_age + CLOCK_SKEW < now,
+        };
+        if single.this_update > now + CLOCK_SKEW || expired {
+            return Err(Error::Ocsp("The response is expired or not yet valid."));
+        }
+        Ok(single.clone())
+    }
+}
+
+impl RevocationCheck for OcspClient {
+    /// 只能查询构造时指定的签发者签发的证书
+    fn covers(&self, issuer: &Certificate) -> bool {
+        issuer.subject() == self.issuer.subject() && issuer.public_key_bits() == self.issuer.public_key_bits()
+    }
+
+    /// 签发者不是构造时指定的签发者时返回错误
+    fn check(&self, certificate: &Certificate, issuer: &Certificate, time: SystemTime) -> Result<()> {
+        if !self.covers(issuer) {
+            return Err(Error::Ocsp("The OCSP client does not serve the issuer of the certificate."));
+        }
+        match self.query(certificate)?.status {
+            CertStatus::Good => Ok(()),
+            CertStatus::Revoked { time: revoked, reason } if revoked <= time && reason != Some(RevocationReason::RemoveFromCrl) => {
+                Err(Error::CertificatePath("The certificate has been revoked."))
+            }
+            CertStatus::Revoked { .. } => Ok(()),
+            CertStatus::Unknown => Err(Error::Ocsp("The status of the certificate is unknown.")),
+        }
+    }
+}
+
+/// id-pkix-ocsp-nonce，extnValue为OCTET STRING
+fn nonce_extension(nonce: &[u8]) -> Extension {
+    Extension::new(oid::OCSP_NONCE, false, yasna::construct_der(|writer| writer.write_bytes(nonce)))
+}
+
+fn find_nonce(extensions: &[Extension]) -> Result<Option<Vec<u8>>> {
+    match extensions.iter().find(|e| e.is(oid::OCSP_NONCE)) {
+        Some(extension) => yasna::parse_der(extension.value(), |reader| reader.read_bytes())
+            .map(Some)
+            .map_err(|_| Error::InvalidDer("The OCSP nonce is not an OCTET STRING.")),
+        None => Ok(None),
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+    use std::rc::Rc;
+    use std::thread;
+    use std::time::UNIX_EPOCH;
+
+    use crate::x509::fixtures::{CA, CA_PRIVATE_KEY, CA_PUBLIC_KEY, CRL, LEAF, ROOT, ROOT_PRIVATE_KEY};
+    use crate::x509::TrustStore;
+
+    use super::*;
+
+    fn at(seconds: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(seconds)
+    }
+
+    /// 在回环地址上启动响应者，返回其URL
+    fn spawn(responder: OcspResponder) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let _ = responder.serve(stream.unwrap());
+            }
+        });
+        format!("http://{}/ocsp", address)
+    }
+
+    #[test]
+    fn request() {
+        let (root, ca, leaf) = (
+            Certificate::from_pem(ROOT).unwrap(),
+            Certificate::from_pem(CA).unwrap(),
+            Certificate::from_pem(LEAF).unwrap(),
+        );
+        let request = OcspRequest::new(&leaf, &ca).unwrap();
+        assert_eq!(request.nonce().unwrap().len(), NONCE_LEN);
+
+        let parsed = OcspRequest::from_der(&request.to_der()).unwrap();
+        assert_eq!(parsed.nonce(), request.nonce());
+        assert_eq!(parsed.cert_ids(), request.cert_ids());
+        assert_eq!(parsed.cert_ids()[0].serial_number(), hex::encode(trim(leaf.serial())));
+        assert_eq!(parsed.cert_ids()[0].issuer_name_hash(), sm3::hash(&ca.subject().to_der()));
+
+        assert!(OcspRequest::new(&leaf, &root).is_err());
+        assert!(OcspRequest::from_der(&request.to_der()[1..]).is_err());
+    }
+
+    #[test]
+    fn respond() {
+        let (root, ca, leaf) = (
+            Certificate::from_pem(ROOT).unwrap(),
+            Certificate::from_pem(CA).unwrap(),
+            Certificate::from_pem(LEAF).unwrap(),
+        );
+        let mut responder = OcspResponder::new(ca.clone(), CA_PRIVATE_KEY).unwrap()
+            .with_next_update(Duration::from_secs(3600));
+        assert!(OcspResponder::new(ca.clone(), ROOT_PRIVATE_KEY).is_err());
+
+        let request = OcspRequest::new(&leaf, &ca).unwrap();
+        let response = OcspResponse::from_der(&responder.respond(&request.to_der())).unwrap();
+        assert_eq!(response.status(), OcspResponseStatus::Successful);
+        assert_eq!(response.nonce(), request.nonce());
+        assert!(response.verify(CA_PUBLIC_KEY).unwrap());
+        assert!(response.verify_issued_by(&ca).unwrap());
+        assert!(!response.verify_issued_by(&root).unwrap());
+
+        let single = response.find(&request.cert_ids()[0]).unwrap();
+        assert_eq!(single.status(), CertStatus::Good);
+        assert_eq!(single.next_update().unwrap(), single.this_update() + Duration::from_secs(3600));
+
+        // CRL中的撤销条目
+        responder.load_crl(&Crl::from_pem(CRL).unwrap()).unwrap();
+        responder.revoke(leaf.serial(), at(1792193400), Some(RevocationReason::KeyCompromise));
+        let response = OcspResponse::from_der(&responder.respond(&request.to_der())).unwrap();
+        assert_eq!(response.responses()[0].status(), CertStatus::Revoked { time: at(1792193400), reason: Some(RevocationReason::KeyCompromise) });
+        let response = OcspResponse::from_der(&responder.respond(&OcspRequest::new(&ca, &root).unwrap().to_der())).unwrap();
+        assert_eq!(response.responses()[0].status(), CertStatus::Unknown);
+
+        let response = OcspResponse::from_der(&responder.respond(b"\x30\x00")).unwrap();
+        assert_eq!(response.status(), OcspResponseStatus::MalformedRequest);
+        assert!(response.responses().is_empty());
+        assert!(response.verify_issued_by(&ca).is_err());
+    }
+
+    #[test]
+    fn loopback() {
+        let (root, ca, leaf) = (
+            Certificate::from_pem(ROOT).unwrap(),
+            Certificate::from_pem(CA).unwrap(),
+            Certificate::from_pem(LEAF).unwrap(),
+        );
+        let time = at(1798761600);
+        let mut store = TrustStore::new();
+        store.add(root.clone());
+
+        let url = spawn(OcspResponder::new(ca.clone(), CA_PRIVATE_KEY).unwrap());
+        let client = OcspClient::new(&url, ca.clone());
+        assert_eq!(client.query(&leaf).unwrap().status(), CertStatus::Good);
+        let store = store.with_revocation_check(Rc::new(client));
+        // 没有查询根CA签发的中间CA证书的途径
+        assert!(store.verify(&leaf, std::slice::from_ref(&ca), time).is_err());
+        let client = OcspClient::new(&url, ca.clone());
+        assert!(!client.covers(&root));
+        assert_eq!(client.check(&ca, &root, time).unwrap_err(), Error::Ocsp("The OCSP client does not serve the issuer of the certificate."));
+        let store = store.with_uncovered_issuers_allowed(true);
+        assert!(store.verify(&leaf, std::slice::from_ref(&ca), time).is_ok());
+
+        let mut responder = OcspResponder::new(ca.clone(), CA_PRIVATE_KEY).unwrap();
+        responder.revoke(leaf.serial(), at(1792193400), None);
+        let url = spawn(responder);
+        let mut store = TrustStore::new().with_revocation_check(Rc::new(OcspClient::new(&url, ca.clone()))).with_uncovered_issuers_allowed(true);
+        store.add(root.clone());
+        assert_eq!(
+            store.verify(&leaf, std::slice::from_ref(&ca), time).unwrap_err(),
+            Error::CertificatePath("The certificate has been revoked.")
+        );
+        assert!(store.verify(&leaf, std::slice::from_ref(&ca), at(1792193399)).is_ok());
+
+        // 响应者不是证书的签发者
+        let url = spawn(OcspResponder::new(root.clone(), ROOT_PRIVATE_KEY).unwrap());
+        assert!(OcspClient::new(&url, ca.clone()).query(&leaf).is_err());
+        assert!(OcspClient::new("http://127.0.0.1:1/ocsp", ca).query(&leaf).is_err());
+    }
+
+    #[test]
+    fn replay() {
+        let (ca, leaf) = (Certificate::from_pem(CA).unwrap(), Certificate::from_pem(LEAF).unwrap());
+        let responder = OcspResponder::new(ca.clone(), CA_PRIVATE_KEY).unwrap();
+
+        // 不带随机数的请求得到的响应没有nextUpdate，可以被保存下来
+        let id = CertId::new(&leaf, &ca).unwrap();
+        let der = yasna::construct_der(|writer| {
+            writer.write_sequence(|writer| {
+                writer.next().write_sequence(|writer| {
+                    writer.next().write_sequence_of(|writer| writer.next().write_sequence(|writer| id.write(writer.next())));
+                })
+            })
+        });
+        let request = OcspRequest::from_der(&der).unwrap();
+        assert!(request.nonce().is_none());
+        let stale = responder.respond(&der);
+        let response = OcspResponse::from_der(&stale).unwrap();
+        assert!(response.nonce().is_none());
+
+        // 重放保存的响应
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/ocsp", listener.local_addr().unwrap());
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let _ = http::read_request(&mut stream);
+                let _ = http::write_response(&mut stream, "200 OK", "application/ocsp-response", &stale);
+            }
+        });
+        let client = OcspClient::new(&url, ca.clone());
+        assert_eq!(client.query(&leaf).unwrap_err(), Error::Ocsp("The nonce of the response does not match the request."));
+
+        // 没有nextUpdate时按最大间隔判断响应是否过期
+        let now = SystemTime::now();
+        assert!(client.validate(&request, &response, now).is_ok());
+        let later = now + MAX_AGE + CLOCK_SKEW * 2;
+        assert_eq!(client.validate(&request, &response, later).unwrap_err(), Error::Ocsp("The response is expired or not yet valid."));
+        let client = client.with_max_age(MAX_AGE * 2);
+        assert!(client.validate(&request, &response, later).is_ok());
+    }
+}
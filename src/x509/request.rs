@@ -0,0 +1,149 @@
+use yasna::models::ObjectIdentifier;
+use yasna::{ASN1Error, ASN1ErrorKind, BERReader, Tag};
+
+use crate::error::{Error, Result};
+use crate::oid;
+use crate::pem;
+use crate::sm2::ecc::{Crypto, Signature};
+use crate::sm2::key::{HexKey, PublicKey};
+use crate::x509::{read_signature_algorithm, Extension, GeneralName, Name};
+
+/// PKCS#10证书签名请求（RFC 2986）
+///
+/// CertificationRequest ::= SEQUENCE {
+///     certificationRequestInfo  CertificationRequestInfo,
+///     signatureAlgorithm        AlgorithmIdentifier,
+///     signature                 BIT STRING
+/// }
+///
+/// 请求的扩展通过extensionRequest属性（PKCS#9）携带。
+#[derive(Clone, Debug)]
+pub struct CertificateRequest {
+    der: Vec<u8>,
+    /// 签名覆盖的CertificationRequestInfo的DER编码
+    info: Vec<u8>,
+    subject: Name,
+    spki: Vec<u8>,
+    extensions: Vec<Extension>,
+    subject_alt_names: Vec<GeneralName>,
+    sm2_with_sm3: bool,
+    signature: Vec<u8>,
+}
+
+impl CertificateRequest {
+    /// 解析DER编码的证书签名请求
+    pub fn from_der(der: &[u8]) -> Result<CertificateRequest> {
+        let (info, sm2_with_sm3, signature) = yasna::parse_der(der, |reader| {
+            reader.read_sequence(|reader| {
+                let info = reader.next().read_der()?;
+                let sm2_with_sm3 = read_signature_algorithm(reader.next())?;
+                let (signature, bits) = reader.next().read_bitvec_bytes()?;
+                if bits != signature.len() * 8 {
+                    return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+                }
+                Ok((info, sm2_with_sm3, signature))
+            })
+        }).map_err(|_| Error::InvalidDer("The data is not a DER-encoded certificate request."))?;
+
+        let (subject, spki, extensions) = yasna::parse_der(&info, read_info)
+            .map_err(|_| Error::InvalidDer("The data is not a DER-encoded CertificationRequestInfo."))?;
+
+        let mut subject_alt_names = vec![];
+        for extension in extensions.iter().filter(|e| e.is(oid::SUBJECT_ALT_NAME)) {
+            subject_alt_names = GeneralName::decode_all(extension.value())
+                .map_err(|_| Error::InvalidCertificate("The certificate request contains a malformed extension."))?;
+        }
+
+        Ok(CertificateRequest {
+            der: der.to_vec(),
+            info,
+            subject,
+            spki,
+            extensions,
+            subject_alt_names,
+            sm2_with_sm3,
+            signature,
+        })
+    }
+
+    /// 解析PEM编码的证书签名请求（CERTIFICATE REQUEST）
+    pub fn from_pem(pem: &str) -> Result<CertificateRequest> {
+        CertificateRequest::from_der(&pem::decode("CERTIFICATE REQUEST", pem)?)
+    }
+
+    pub fn to_der(&self) -> Vec<u8> {
+        self.der.clone()
+    }
+
+    pub fn to_pem(&self) -> String {
+        pem::encode("CERTIFICATE REQUEST", &self.der)
+    }
+
+    pub fn subject(&self) -> &Name {
+        &self.subject
+    }
+
+    /// 请求中的SM2公钥，非压缩格式
+    pub fn public_key(&self) -> Result<String> {
+        Ok(self.sm2_public_key()?.encode())
+    }
+
+    /// 请求的扩展
+    pub fn extensions(&self) -> &[Extension] {
+        &self.extensions
+    }
+
+    pub fn subject_alt_names(&self) -> &[GeneralName] {
+        &self.subject_alt_names
+    }
+
+    /// 使用请求中的公钥验证请求的签名，以证明申请者持有对应的私钥
+    pub fn verify(&self) -> Result<bool> {
+        if !self.sm2_with_sm3 {
+            return Err(Error::InvalidCertificate("The signature algorithm is not SM2-with-SM3."));
+        }
+        let signature = Signature::decode(&self.signature)?;
+        Ok(Crypto::default().verifier(self.sm2_public_key()?).verify_bytes(&self.info, &signature))
+    }
+
+    pub(crate) fn sm2_public_key(&self) -> Result<PublicKey> {
+        PublicKey::decode_spki(&self.spki)
+            .map_err(|_| Error::InvalidCertificate("The subject public key is not a valid SM2 public key."))
+    }
+}
+
+/// CertificationRequestInfo ::= SEQUENCE {
+///     version       INTEGER { v1(0) },
+///     subject       Name,
+///     subjectPKInfo SubjectPublicKeyInfo,
+///     attributes    \[0] IMPLICIT SET OF Attribute
+/// }
+///
+/// Attribute ::= SEQUENCE { type OBJECT IDENTIFIER, values SET OF ANY }，仅解析extensionRequest。
+fn read_info(reader: BERReader) -> std::result::Result<(Name, Vec<u8>, Vec<Extension>), ASN1Error> {
+    reader.read_sequence(|reader| {
+        if reader.next().read_u8()? != 0 {
+            return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+        }
+        let subject = Name::read(reader.next())?;
+        let spki = reader.next().read_der()?;
+
+        let mut extensions = vec![];
+        reader.next().read_tagged_implicit(Tag::context(0), |reader| {
+            reader.read_set_of(|reader| {
+                reader.read_sequence(|reader| {
+                    let attribute = reader.next().read_oid()?;
+                    if attribute == ObjectIdentifier::from_slice(oid::EXTENSION_REQUEST) {
+                        reader.next().read_set_of(|reader| {
+                            extensions = reader.collect_sequence_of(Extension::read)?;
+                            Ok(())
+                        })
+                    } else {
+                        reader.next().read_der().map(|_| ())
+                    }
+                })
+            })
+        })?;
+        Ok((subject, spki, extensions))
+    })
+}
@@ -0,0 +1,330 @@
+use std::rc::Rc;
+use std::time::SystemTime;
+
+use crate::error::{Error, Result};
+use crate::oid;
+use crate::x509::{Certificate, KeyUsage};
+
+/// 默认允许的最大中间CA证书数量
+const MAX_DEPTH: usize = 8;
+
+/// 可以识别并处理的扩展，证书中其余的关键扩展会导致验证失败
+const RECOGNIZED: [&[u64]; 5] = [
+    oid::BASIC_CONSTRAINTS,
+    oid::KEY_USAGE,
+    oid::SUBJECT_ALT_NAME,
+    oid::SUBJECT_KEY_IDENTIFIER,
+    oid::AUTHORITY_KEY_IDENTIFIER,
+];
+
+/// 撤销状态检查
+///
+/// 路径验证时对路径中除信任锚外的每一张证书，调用所有适用于其签发者的检查，已撤销或无法确认状态时返回错误。
+/// 对不适用的签发者调用[RevocationCheck::check]同样返回错误。
+pub trait RevocationCheck {
+    /// 能否确认issuer签发的证书的撤销状态
+    fn covers(&self, issuer: &Certificate) -> bool;
+
+    /// certificate由issuer签发，time为验证时刻
+    fn check(&self, certificate: &Certificate, issuer: &Certificate, time: SystemTime) -> Result<()>;
+}
+
+/// 信任锚集合与证书路径验证（RFC 5280 第6章）
+///
+/// 从待验证的证书出发，在调用方提供的中间CA证书中按名称链接构建到某个信任锚的路径，
+/// 再对路径逐一检查签名、有效期、基本约束（cA与pathLenConstraint）以及秘钥用途。
+///
+/// 信任锚自身不验证签名，但须在有效期内，且须为CA证书；其pathLenConstraint同样生效。
+/// 可以注册撤销状态检查（如CRL），路径中的证书须通过全部适用的检查。
+/// 注册了撤销状态检查时，路径中每一张证书的签发者都须至少有一个适用的检查，否则验证失败，
+/// 除非通过[TrustStore::with_uncovered_issuers_allowed]显式允许跳过这些证书。
+/// 适用的检查本身失败（如响应者不可达、状态未知）时，验证总是失败。
+#[derive(Clone)]
+pub struct TrustStore {
+    anchors: Vec<Certificate>,
+    max_depth: usize,
+    revocation: Vec<Rc<dyn RevocationCheck>>,
+    allow_uncovered: bool,
+}
+
+impl Default for TrustStore {
+    fn default() -> Self {
+        TrustStore::new()
+    }
+}
+
+impl TrustStore {
+    pub fn new() -> Self {
+        TrustStore { anchors: vec![], max_depth: MAX_DEPTH, revocation: vec![], allow_uncovered: false }
+    }
+
+    /// 设置路径中允许的最大中间CA证书数量
+    pub fn with_max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = depth;
+        self
+    }
+
+    /// 注册撤销状态检查
+    pub fn with_revocation_check(mut self, check: Rc<dyn RevocationCheck>) -> Self {
+        self.revocation.push(check);
+        self
+    }
+
+    /// 是否允许签发者没有适用的撤销状态检查（默认不允许），允许时这些证书的撤销状态不会被检查
+    ///
+    /// 只影响没有适用检查的签发者，不会忽略适用的检查返回的任何错误。
+    pub fn with_uncovered_issuers_allowed(mut self, allowed: bool) -> Self {
+        self.allow_uncovered = allowed;
+        self
+    }
+
+    /// 添加信任锚，重复添加同一证书时忽略
+    pub fn add(&mut self, anchor: Certificate) {
+        if !self.anchors.iter().any(|e| e.to_der() == anchor.to_der()) {
+            self.anchors.push(anchor);
+        }
+    }
+
+    pub fn anchors(&self) -> &[Certificate] {
+        &self.anchors
+    }
+
+    /// 验证证书在time时刻可信，intermediates为可用于构建路径的中间CA证书（顺序任意）
+    ///
+    /// 成功时返回从该证书到信任锚的完整路径。
+    pub fn verify(&self, certificate: &Certificate, intermediates: &[Certificate], time: SystemTime) -> Result<Vec<Certificate>> {
+        self.verify_path(certificate, intermediates, time, None)
+    }
+
+    /// 同verify，并要求证书的秘钥用途（若有该扩展）包含usage
+    pub fn verify_for_usage(
+        &self, certificate: &Certificate, intermediates: &[Certificate], time: SystemTime, usage: KeyUsage,
+    ) -> Result<Vec<Certificate>> {
+        self.verify_path(certificate, intermediates, time, Some(usage))
+    }
+
+    fn verify_path(
+        &self, certificate: &Certificate, intermediates: &[Certificate], time: SystemTime, usage: Option<KeyUsage>,
+    ) -> Result<Vec<Certificate>> {
+        if let Some(usage) = usage {
+            if !certificate.key_usage().map(|e| e.contains(usage)).unwrap_or(true) {
+                return Err(Error::CertificatePath("The key usage of the certificate does not permit the requested usage."));
+            }
+        }
+
+        // 证书本身即为信任锚
+        if self.anchors.iter().any(|e| e.to_der() == certificate.to_der()) {
+            return match certificate.is_valid_at(time) {
+                true => Ok(vec![certificate.clone()]),
+                false => Err(Error::CertificatePath("The certificate is expired or not yet valid.")),
+            };
+        }
+
+        let mut path = vec![certificate.clone()];
+        let mut error = Error::CertificatePath("No path to a trust anchor was found.");
+        match self.build(&mut path, intermediates, time, &mut error) {
+            true => Ok(path),
+            false => Err(error),
+        }
+    }
+
+    /// 深度优先构建路径，path的末尾为当前待寻找签发者的证书；失败时error记录最近一次的失败原因
+    fn build(&self, path: &mut Vec<Certificate>, intermediates: &[Certificate], time: SystemTime, error: &mut Error) -> bool {
+        let current = path.last().unwrap().clone();
+
+        for anchor in self.anchors.iter() {
+            if !issued_by(&current, anchor, error) {
+                continue;
+            }
+            match self.validate(path, anchor, time) {
+                Ok(()) => {
+                    path.push(anchor.clone());
+                    return true;
+                }
+                Err(e) => *error = e,
+            }
+        }
+
+        if path.len() > self.max_depth {
+            return false;
+        }
+
+        for candidate in intermediates {
+            if path.iter().any(|e| e.to_der() == candidate.to_der()) || !issued_by(&current, candidate, error) {
+                continue;
+            }
+            path.push(candidate.clone());
+            if self.build(path, intermediates, time, error) {
+                return true;
+            }
+            path.pop();
+        }
+        false
+    }
+
+    /// 验证path（path\[0]为终端证书，最后一个由anchor签发）
+    fn validate(&self, path: &[Certificate], anchor: &Certificate, time: SystemTime) -> Result<()> {
+        check_ca(anchor)?;
+        if !anchor.is_valid_at(time) {
+            return Err(Error::CertificatePath("The trust anchor is expired or not yet valid."));
+        }
+
+        // 其后还允许出现的非自签发中间CA证书数量
+        let mut max_path_length = anchor.basic_constraints().and_then(|e| e.path_len).map(|e| e as usize).unwrap_or(usize::MAX);
+
+        for (i, certificate) in path.iter().enumerate().rev() {
+            if !certificate.is_valid_at(time) {
+                return Err(Error::CertificatePath("The certificate is expired or not yet valid."));
+            }
+            if certificate.extensions().iter().any(|e| e.critical() && !RECOGNIZED.iter().any(|oid| e.is(oid))) {
+                return Err(Error::CertificatePath("The certificate contains an unrecognized critical extension."));
+            }
+
+            // 中间CA证书
+            if i > 0 {
+                check_ca(certificate)?;
+                if !certificate.is_self_issued() {
+                    if max_path_length == 0 {
+                        return Err(Error::CertificatePath("The path length constraint is exceeded."));
+                    }
+                    max_path_length -= 1;
+                }
+                if let Some(path_len) = certificate.basic_constraints().and_then(|e| e.path_len) {
+                    max_path_length = max_path_length.min(path_len as usize);
+                }
+            }
+
+            let issuer = path.get(i + 1).unwrap_or(anchor);
+            let checks: Vec<_> = self.revocation.iter().filter(|e| e.covers(issuer)).collect();
+            if checks.is_empty() && !self.revocation.is_empty() && !self.allow_uncovered {
+                return Err(Error::CertificatePath("The revocation status of the certificate cannot be determined."));
+            }
+            for check in checks {
+                check.check(certificate, issuer, time)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 名称链接：证书的签发者名称与issuer的主体名称一致、秘钥标识（若均存在）一致，且签名有效
+fn issued_by(certificate: &Certificate, issuer: &Certificate, error: &mut Error) -> bool {
+    if certificate.issuer() != issuer.subject() {
+        return false;
+    }
+    if let (Some(aki), Some(ski)) = (certificate.authority_key_identifier(), issuer.subject_key_identifier()) {
+        if aki != ski {
+            return false;
+        }
+    }
+    match certificate.verify_issued_by(issuer) {
+        Ok(true) => true,
+        _ => {
+            *error = Error::CertificatePath("The signature of the certificate is invalid.");
+            false
+        }
+    }
+}
+
+/// 签发证书的CA须为v3证书，基本约束的cA为真，且秘钥用途（若有该扩展）包含keyCertSign
+fn check_ca(certificate: &Certificate) -> Result<()> {
+    if certificate.version() != 3 || !certificate.basic_constraints().map(|e| e.ca).unwrap_or(false) {
+        return Err(Error::CertificatePath("The issuer is not a CA certificate."));
+    }
+    if !certificate.key_usage().map(|e| e.contains(KeyUsage::KEY_CERT_SIGN)).unwrap_or(true) {
+        return Err(Error::CertificatePath("The issuer is not allowed to sign certificates."));
+    }
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use crate::x509::fixtures::{CA, CA_PRIVATE_KEY, LEAF, LEAF_PRIVATE_KEY, LEAF_PUBLIC_KEY, ROOT};
+    use crate::x509::{BasicConstraints, CertificateBuilder, Name};
+
+    use super::*;
+
+    /// 2027-01-01 00:00:00
+    fn now() -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(1798761600)
+    }
+
+    fn store() -> TrustStore {
+        let mut store = TrustStore::new();
+        store.add(Certificate::from_pem(ROOT).unwrap());
+        store
+    }
+
+    #[test]
+    fn verify() {
+        let (root, ca, leaf) = (
+            Certificate::from_pem(ROOT).unwrap(),
+            Certificate::from_pem(CA).unwrap(),
+            Certificate::from_pem(LEAF).unwrap(),
+        );
+        let store = store();
+        let intermediates = [ca.clone()];
+
+        let path = store.verify(&leaf, &[root.clone(), ca.clone()], now()).unwrap();
+        let subjects: Vec<_> = path.iter().map(|e| e.subject().common_name().unwrap()).collect();
+        assert_eq!(subjects, ["www.example.com", "Yarism Issuing CA", "Yarism Root CA"]);
+
+        assert_eq!(store.verify(&ca, &[], now()).unwrap().len(), 2);
+        assert_eq!(store.verify(&root, &[], now()).unwrap().len(), 1);
+        assert!(store.verify_for_usage(&leaf, &intermediates, now(), KeyUsage::DIGITAL_SIGNATURE).is_ok());
+
+        let failures = [
+            // 缺少中间CA证书
+            store.verify(&leaf, &[], now()),
+            // 没有信任锚
+            TrustStore::new().verify(&leaf, &[ca.clone(), root.clone()], now()),
+            // 已过期与尚未生效
+            store.verify(&leaf, &intermediates, UNIX_EPOCH + Duration::from_secs(1893456000)),
+            store.verify(&leaf, &intermediates, UNIX_EPOCH + Duration::from_secs(1792191798)),
+            // 秘钥用途不包含keyAgreement
+            store.verify_for_usage(&leaf, &intermediates, now(), KeyUsage::KEY_AGREEMENT),
+            // 中间CA证书数量超过限制
+            store.clone().with_max_depth(0).verify(&leaf, &intermediates, now()),
+        ];
+        for result in failures {
+            assert!(matches!(result, Err(Error::CertificatePath(_))));
+        }
+    }
+
+    #[test]
+    fn path_len() {
+        let ca = Certificate::from_pem(CA).unwrap();
+        let subject = Name::new(&[("CN", "Yarism Sub CA")]).unwrap();
+
+        // 中间CA的pathLenConstraint为0，不能再签发CA证书
+        let sub = CertificateBuilder::new(subject.clone(), LEAF_PUBLIC_KEY).unwrap()
+            .with_validity(now(), now() + Duration::from_secs(86400))
+            .with_basic_constraints(BasicConstraints { ca: true, path_len: None })
+            .sign(&ca, CA_PRIVATE_KEY)
+            .unwrap();
+        let leaf = CertificateBuilder::new(Name::new(&[("CN", "leaf")]).unwrap(), LEAF_PUBLIC_KEY).unwrap()
+            .with_validity(now(), now() + Duration::from_secs(86400))
+            .sign(&sub, LEAF_PRIVATE_KEY)
+            .unwrap();
+
+        assert!(store().verify(&sub, std::slice::from_ref(&ca), now()).is_ok());
+        let result = store().verify(&leaf, &[ca, sub], now());
+        assert_eq!(result.unwrap_err(), Error::CertificatePath("The path length constraint is exceeded."));
+    }
+
+    #[test]
+    fn signature() {
+        // 篡改签名后无法链接到签发者
+        let ca = Certificate::from_pem(CA).unwrap();
+        let mut der = Certificate::from_pem(LEAF).unwrap().to_der();
+        let len = der.len();
+        der[len - 1] ^= 0x01;
+        let leaf = Certificate::from_der(&der).unwrap();
+
+        let result = store().verify(&leaf, &[ca], now());
+        assert_eq!(result.unwrap_err(), Error::CertificatePath("The signature of the certificate is invalid."));
+    }
+}